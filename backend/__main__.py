@@ -35,6 +35,6 @@ if __name__ == '__main__':
         app, 
         debug=False,  # Never debug in packaged mode
         host='127.0.0.1', 
-        port=5000, 
+        port=int(os.environ.get('TALUS_PORT', '5000')),
         allow_unsafe_werkzeug=True  # Required for packaged deployments
     )
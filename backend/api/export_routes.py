@@ -148,6 +148,52 @@ def list_templates():
         }), 500
 
 
+@export_bp.route('/<session_id>/snapshot', methods=['GET'])
+def snapshot_export(session_id):
+    """
+    Point-in-time copy of a session's tallies for sharing, in the same node
+    layout as saved project files. Taken from the in-memory graph, so it
+    reflects unsaved changes and never touches the project file.
+    """
+    from datetime import datetime, timezone
+    from backend.api.routes import _get_session_data
+
+    session_data = _get_session_data(session_id)
+    if not session_data:
+        return jsonify({
+            'error': {
+                'code': 'SESSION_NOT_FOUND',
+                'message': f'Session {session_id} not found'
+            }
+        }), 404
+    graph = session_data.get('graph')
+    if not graph:
+        return jsonify({
+            'error': {
+                'code': 'NO_GRAPH',
+                'message': 'No graph loaded in session'
+            }
+        }), 400
+
+    nodes = {}
+    for node in graph.nodes.values():
+        nodes[str(node.id)] = {
+            'id': str(node.id),
+            'type': node.blueprint_type_id,
+            'name': node.name,
+            'created_at': node.created_at.isoformat(),
+            'properties': node.properties,
+            'children': [str(child_id) for child_id in node.children],
+            'parent_id': str(node.parent_id) if node.parent_id else None
+        }
+    return jsonify({
+        'format_version': '1.0',
+        'taken_at': datetime.now(timezone.utc).isoformat(),
+        'session_id': session_id,
+        'nodes': nodes,
+    }), 200
+
+
 @export_bp.route('/<session_id>/download', methods=['POST'])
 def download_export(session_id):
     """
@@ -57,7 +57,11 @@ def _resolve_assets_subpath(*parts: str, prefer_writable: bool = False) -> Path:
     # Fallback: return repo path (will be created if needed for write operations)
     fallback = repo_root.joinpath(*parts)
     if prefer_writable:
-        fallback.parent.mkdir(parents=True, exist_ok=True)
+        try:
+            fallback.parent.mkdir(parents=True, exist_ok=True)
+        except OSError:
+            # Read-only install; packaged callers write to the user data dir instead
+            pass
     return fallback
 
 def get_indicator_metadata(node, blueprint):
@@ -186,6 +190,7 @@ from typing import Optional, Dict, Any
 import io
 import json
 import logging
+import time
 import tempfile
 import uuid
 from datetime import datetime, timezone
@@ -331,8 +336,10 @@ def _cleanup_inactive_sessions(max_inactive_hours=24):
 
 @api_bp.route('/health', methods=['GET'])
 def health_check():
-    """Health check endpoint."""
-    return jsonify({'status': 'ok'}), 200
+    """Health check endpoint. Status is 'needs-setup' until first-run setup has seeded templates."""
+    from backend.infra.first_run_copy import needs_setup
+    status = 'needs-setup' if needs_setup() else 'ok'
+    return jsonify({'status': status, 'time': time.time()}), 200
 
 
 @api_bp.route('/sessions', methods=['GET'])
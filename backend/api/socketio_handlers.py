@@ -16,12 +16,20 @@ logger = logging.getLogger(__name__)
 # Format: {session_id: set(client_ids)}
 _session_clients = {}
 
+# All connected Socket.IO clients, joined to a session or not
+_connected_sids = set()
+
 
 def get_session_client_count(session_id):
     """Get the number of active clients in a session."""
     return len(_session_clients.get(session_id, set()))
 
 
+def get_connected_client_count():
+    """Get the number of connected Socket.IO clients across all sessions."""
+    return len(_connected_sids)
+
+
 def update_session_metadata(session_id, active_clients):
     """Update session metadata with client count (called from routes module)."""
     # Import here to avoid circular import
@@ -36,12 +44,14 @@ class GraphNamespace(Namespace):
     def on_connect(self):
         """Handle client connection."""
         sid = request.sid
+        _connected_sids.add(sid)
         logger.info(f"Client connected: {sid}")
         return True
 
     def on_disconnect(self):
         """Handle client disconnection."""
         sid = request.sid
+        _connected_sids.discard(sid)
         logger.info(f"Client disconnected: {sid}")
         # Remove client from all session rooms
         for session_id in list(_session_clients.keys()):
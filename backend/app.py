@@ -8,7 +8,9 @@ Layer 5: REST API Bridge
 """
 
 import os
+import re
 import sys
+import time
 from pathlib import Path
 
 # Load environment variables from .env file
@@ -167,10 +169,282 @@ def create_app(config=None):
             }
         }), 500
     
+    # Count in-flight HTTP requests for /api/v1/metrics
+    import threading
+    in_flight_lock = threading.Lock()
+    in_flight = {'count': 0}
+
+    @app.before_request
+    def _track_request_start():
+        with in_flight_lock:
+            in_flight['count'] += 1
+
+    @app.teardown_request
+    def _track_request_end(exc):
+        with in_flight_lock:
+            in_flight['count'] = max(0, in_flight['count'] - 1)
+
+    # Paused by the desktop shell (e.g. during a break in an event): reads keep
+    # working, but requests that change a session's tally data are refused.
+    paused = {'value': False}
+    tally_writes = [
+        re.compile(r'^/api/v1/commands/execute$'),
+        re.compile(r'^/api/v1/sessions/[^/]+/(undo|redo|load-graph)$'),
+        re.compile(r'^/api/v1/session/[^/]+/migrations/apply$'),
+        re.compile(r'^/api/v1/imports/'),
+    ]
+
+    @app.before_request
+    def _reject_writes_while_paused():
+        if not paused['value'] or request.method in ('GET', 'HEAD', 'OPTIONS'):
+            return None
+        if any(pattern.match(request.path) for pattern in tally_writes):
+            return jsonify({
+                'error': {
+                    'code': 'PAUSED',
+                    'message': 'Tallying is paused; resume to make changes'
+                }
+            }), 423
+        return None
+
+    @app.route('/api/v1/pause', methods=['POST'])
+    def pause():
+        paused['value'] = True
+        logger.info("Backend paused; tally writes are refused until resumed")
+        return jsonify({'status': 'ok', 'paused': True}), 200
+
+    @app.route('/api/v1/resume', methods=['POST'])
+    def resume():
+        paused['value'] = False
+        logger.info("Backend resumed")
+        return jsonify({'status': 'ok', 'paused': False}), 200
+
+    # Optional features this backend implements, for the desktop shell and UI
+    # to gate on instead of probing endpoints. Add to this when adding one.
+    capabilities = [
+        'bench-write',
+        'export',
+        'health-socket',
+        'health-time',
+        'inmemory',
+        'metrics',
+        'pause',
+        'schema',
+        'self-test',
+        'setup',
+        'snapshot',
+        'trim-memory',
+        'warmup',
+    ]
+
+    @app.route('/api/v1/capabilities', methods=['GET'])
+    def get_capabilities():
+        return jsonify({'capabilities': capabilities}), 200
+
+    @app.route('/api/v1/metrics', methods=['GET'])
+    def metrics():
+        """Connection metrics so the desktop shell can spot leaked or runaway requests."""
+        from backend.api.socketio_handlers import get_connected_client_count
+        with in_flight_lock:
+            # Don't count this metrics request itself
+            http_in_flight = max(0, in_flight['count'] - 1)
+        socket_clients = get_connected_client_count()
+        return jsonify({
+            'active_connections': http_in_flight + socket_clients,
+            'http_requests_in_flight': http_in_flight,
+            'socket_clients': socket_clients,
+        }), 200
+
+    @app.route('/api/v1/warmup', methods=['GET'])
+    def warmup():
+        """Load lazily imported modules and scan the template catalog so the
+        first real request doesn't pay for it. Called once by the desktop shell
+        before it reports the backend ready."""
+        started = time.time()
+        from backend.infra.template_persistence import TemplatePersistence
+        templates = TemplatePersistence().list_templates()
+        return jsonify({
+            'status': 'ok',
+            'templates': len(templates),
+            'duration_ms': int((time.time() - started) * 1000),
+        }), 200
+
+    @app.route('/api/v1/bench/write', methods=['POST'])
+    def bench_write():
+        """One persisted write and delete through the normal save path, with no
+        lasting effect; the desktop shell times these to measure write latency."""
+        import uuid
+        from backend.core.graph import ProjectGraph
+        from backend.infra.persistence import PersistenceManager
+        from backend.infra.user_data_dir import get_user_data_dir
+        path = get_user_data_dir() / f'.bench-{uuid.uuid4().hex}.json'
+        try:
+            PersistenceManager(path).save(ProjectGraph())
+        finally:
+            path.unlink(missing_ok=True)
+        return jsonify({'status': 'ok'}), 200
+
+    @app.route('/api/v1/self-test', methods=['GET'])
+    def self_test():
+        """The backend's half of the desktop shell's self-test: the data dir is
+        writable, a sample tally survives a save and load, and which version is
+        running. Each check is pass/warn/fail with a message."""
+        import uuid
+        from backend.core.graph import ProjectGraph
+        from backend.core.node import Node
+        from backend.infra import project_schema
+        from backend.infra.persistence import PersistenceManager
+        from backend.infra.user_data_dir import get_user_data_dir
+
+        def check(name, status, message):
+            return {'name': name, 'status': status, 'message': message}
+
+        checks = []
+        data_dir = get_user_data_dir()
+        probe = data_dir / f'.self-test-{uuid.uuid4().hex}'
+        try:
+            probe.write_text('ok')
+            checks.append(check('data_dir', 'pass', f'{data_dir} is writable'))
+        except OSError as exc:
+            checks.append(check('data_dir', 'fail', f'{data_dir} is not writable: {exc}'))
+        finally:
+            probe.unlink(missing_ok=True)
+
+        path = data_dir / f'.self-test-{uuid.uuid4().hex}.json'
+        try:
+            graph = ProjectGraph()
+            node = Node(blueprint_type_id='self_test', name='Self-test')
+            node.properties = {'count': 3}
+            graph.add_node(node)
+            PersistenceManager(path).save(graph)
+            if not path.exists():
+                # TALUS_INMEMORY sessions never write
+                checks.append(check('tally_round_trip', 'warn', 'In-memory session; nothing is persisted'))
+            else:
+                loaded = PersistenceManager(path).load()[0].get_node(node.id)
+                if loaded is not None and loaded.properties.get('count') == 3:
+                    checks.append(check('tally_round_trip', 'pass', 'Sample tally saved and loaded back'))
+                else:
+                    checks.append(check('tally_round_trip', 'fail', 'Sample tally did not load back as saved'))
+        except Exception as exc:
+            checks.append(check('tally_round_trip', 'fail', f'Sample tally round-trip failed: {exc}'))
+        finally:
+            path.unlink(missing_ok=True)
+
+        checks.append(check(
+            'version', 'pass',
+            f'Project schema {project_schema.SCHEMA_VERSION}, Python {sys.version.split()[0]}'
+        ))
+        return jsonify({'checks': checks}), 200
+
+    @app.route('/api/v1/schema/check', methods=['POST'])
+    def project_schema_version():
+        """Schema version of the project file at `path` and whether this
+        backend can migrate it, so the desktop shell can refuse to load a file
+        it doesn't understand. A missing or unreadable file is reported as
+        unknown (`schema_version` null), never as current."""
+        from backend.infra import project_schema
+        path = ((request.get_json(silent=True) or {}).get('path') or '').strip()
+        if not path:
+            return jsonify({'error': 'Missing path'}), 400
+        version = project_schema.file_version(Path(path))
+        return jsonify({
+            'schema_version': version,
+            'latest_schema': project_schema.SCHEMA_VERSION,
+            'migration_available': project_schema.migration_available(version),
+        }), 200
+
+    @app.route('/api/v1/schema/migrate', methods=['POST'])
+    def migrate_project_schema():
+        """Upgrade the project file at `path` to the latest schema. The
+        desktop shell takes a backup first."""
+        from backend.infra import project_schema
+        path = ((request.get_json(silent=True) or {}).get('path') or '').strip()
+        if not path:
+            return jsonify({'error': 'Missing path'}), 400
+        try:
+            version = project_schema.migrate(Path(path))
+        except ValueError as exc:
+            return jsonify({'status': 'error', 'error': str(exc)}), 409
+        return jsonify({'status': 'ok', 'schema_version': version}), 200
+
+    @app.route('/api/v1/trim-memory', methods=['POST'])
+    def trim_memory():
+        """Run a full GC and, on glibc, hand freed heap back to the OS."""
+        import gc
+        collected = gc.collect()
+        trimmed = False
+        if sys.platform.startswith('linux'):
+            try:
+                import ctypes
+                trimmed = bool(ctypes.CDLL('libc.so.6').malloc_trim(0))
+            except (OSError, AttributeError):
+                pass
+        return jsonify({'collected': collected, 'malloc_trim': trimmed}), 200
+
+    def dev_mode():
+        """Debug endpoints only exist when TALUS_DEV is set."""
+        return os.environ.get('TALUS_DEV', '').strip().lower() in {'1', 'true', 'yes'}
+
+    @app.route('/api/v1/debug/crash', methods=['POST'])
+    def simulate_crash():
+        """Crash on purpose so the desktop shell's recovery can be tested.
+        Only exists with TALUS_DEV=1. 'hang' stops every thread from running
+        (the process stays alive but never answers); the exit modes end the
+        process with that status."""
+        if not dev_mode():
+            return jsonify({'error': 'Not found'}), 404
+        mode = (request.get_json(silent=True) or {}).get('mode')
+        if mode not in {'hang', 'exit-nonzero', 'exit-clean'}:
+            return jsonify({'error': f'Unknown crash mode: {mode}'}), 400
+        logger.warning(f"Simulating backend crash: {mode}")
+
+        def crash():
+            time.sleep(0.2)  # let the response go out first
+            if mode == 'hang':
+                # Never let go of the GIL, so no other thread runs again
+                sys.setswitchinterval(3600)
+                while True:
+                    pass
+            os._exit(0 if mode == 'exit-clean' else 1)
+
+        import threading
+        threading.Thread(target=crash, daemon=True).start()
+        return jsonify({'status': 'ok', 'mode': mode}), 202
+
+    @app.route('/api/v1/debug/dump', methods=['POST'])
+    def dump_threads():
+        """Return a traceback of every thread, for the desktop shell to save
+        when the backend looks hung (used where SIGQUIT isn't available).
+        Only exists with TALUS_DEV=1, since the traces expose file paths and
+        local values to anything that can reach the port."""
+        if not dev_mode():
+            return jsonify({'error': 'Not found'}), 404
+        import faulthandler
+        import tempfile
+        with tempfile.TemporaryFile(mode='w+') as out:
+            faulthandler.dump_traceback(file=out, all_threads=True)
+            out.seek(0)
+            return out.read(), 200, {'Content-Type': 'text/plain; charset=utf-8'}
+
     # Health check endpoint (legacy, also in routes)
     @app.route('/api/v1/health', methods=['GET'])
     def health_check():
-        """Health check endpoint."""
+        """Health check endpoint. `time` lets the desktop shell detect clock skew;
+        status is 'needs-setup' until first-run setup has seeded templates."""
+        from backend.infra.first_run_copy import needs_setup
+        status = 'needs-setup' if needs_setup() else 'ok'
+        return jsonify({'status': status, 'time': time.time()}), 200
+
+    @app.route('/api/v1/setup', methods=['POST'])
+    def run_setup():
+        """First-run setup: seed the user data dir. `template_sources` adds
+        directories to copy templates from, e.g. an organization's own set."""
+        from backend.infra.first_run_copy import ensure_user_data_populated, needs_setup
+        params = request.get_json(silent=True) or {}
+        ensure_user_data_populated(params.get('template_sources') or [])
+        if needs_setup():
+            return jsonify({'status': 'needs-setup', 'error': 'No template sources were found'}), 422
         return jsonify({'status': 'ok'}), 200
     
     # Serve static frontend files if available
@@ -219,6 +493,85 @@ def create_app(config=None):
     return app
 
 
+def register_stack_dump(path):
+    """
+    Append a traceback of every thread to `path` on SIGQUIT.
+
+    The desktop shell sets TALUS_STACK_DUMP and signals the backend before
+    killing it for not answering, so the dump shows where it was stuck.
+    faulthandler writes from the signal handler itself, so this works even
+    when no Python thread can run.
+    """
+    import faulthandler
+    import signal
+
+    if not hasattr(signal, 'SIGQUIT'):
+        return
+    try:
+        dump_file = open(path, 'a')
+    except OSError as exc:
+        logger.warning(f"Failed to open stack dump file {path}: {exc}")
+        return
+    # faulthandler keeps the file descriptor; the file must stay open
+    faulthandler.register(signal.SIGQUIT, file=dump_file, all_threads=True)
+    logger.info(f"Thread dumps on SIGQUIT go to {path}")
+
+
+def start_health_socket(path):
+    """
+    Answer health checks on a Unix domain socket in addition to HTTP.
+
+    The desktop shell sets TALUS_HEALTH_SOCKET so it can probe readiness
+    without loopback TCP, which some locked-down machines firewall. Each
+    connection gets a single JSON line and is closed.
+    """
+    import atexit
+    import json
+    import socket
+    import threading
+
+    if not hasattr(socket, 'AF_UNIX'):
+        logger.info("Unix domain sockets unavailable; health socket disabled")
+        return
+
+    try:
+        os.unlink(path)
+    except FileNotFoundError:
+        pass
+    server = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+    try:
+        server.bind(path)
+        server.listen(8)
+    except OSError as exc:
+        logger.warning(f"Failed to open health socket {path}: {exc}")
+        server.close()
+        return
+
+    def _cleanup():
+        server.close()
+        try:
+            os.unlink(path)
+        except OSError:
+            pass
+
+    atexit.register(_cleanup)
+
+    def _serve():
+        while True:
+            try:
+                conn, _ = server.accept()
+            except OSError:
+                return
+            with conn:
+                try:
+                    conn.sendall((json.dumps({'status': 'ok', 'time': time.time()}) + '\n').encode())
+                except OSError:
+                    pass
+
+    threading.Thread(target=_serve, name='health-socket', daemon=True).start()
+    logger.info(f"Health socket listening at {path}")
+
+
 if __name__ == '__main__':
     # Ensure user data dirs are seeded with defaults (templates, icons, etc.)
     # This is the PyInstaller entry point; __main__.py also calls this for `python -m backend`.
@@ -229,6 +582,14 @@ if __name__ == '__main__':
         logger.warning(f"[Startup] Failed to seed user data directories: {_e}")
 
     app = create_app()
+
+    health_socket = os.environ.get('TALUS_HEALTH_SOCKET')
+    if health_socket:
+        start_health_socket(health_socket)
+
+    stack_dump = os.environ.get('TALUS_STACK_DUMP')
+    if stack_dump:
+        register_stack_dump(stack_dump)
     
     # Check if running as background daemon (via TALUS_DAEMON env var)
     # When running in background, Flask's reloader causes SIGTTOU/SIGTTIN job control signals
@@ -239,6 +600,17 @@ if __name__ == '__main__':
     
     if is_daemon:
         logger.info("Running in daemon mode - reloader disabled")
+
+    # Exit via SystemExit on SIGTERM (sent by Tauri on app close) so wrappers
+    # like cProfile (TALUS_PROFILE) get to write their output.
+    import signal
+
+    def _shutdown(signum, frame):
+        logger.info(f"Received signal {signum}, shutting down")
+        sys.exit(0)
+
+    signal.signal(signal.SIGTERM, _shutdown)
     
-    socketio.run(app, debug=True, host='127.0.0.1', port=5000, 
+    port = int(os.environ.get('TALUS_PORT', '5000'))
+    socketio.run(app, debug=True, host='127.0.0.1', port=port, 
                  allow_unsafe_werkzeug=True, use_reloader=use_reloader)
@@ -70,7 +70,14 @@ def copy_if_empty(user_dir: Path, source_dirs):
             copy_from_source(source, skip_existing=has_existing_content)
 
 
-def ensure_user_data_populated():
+def needs_setup() -> bool:
+    """True when the user data dir has no templates, i.e. seeding found no
+    sources (or failed) and the app would open to an empty state."""
+    templates = get_user_templates_dir()
+    return not any(p.is_file() for p in templates.rglob('*'))
+
+
+def ensure_user_data_populated(extra_template_sources=()):
     # Discover the Tauri-bundled resources root (if running as a packaged binary)
     bundled_root = _get_bundled_resource_root()
 
@@ -85,7 +92,7 @@ def ensure_user_data_populated():
         return candidates
 
     mappings = [
-        (get_user_templates_dir(), _sources('data', 'templates')),
+        (get_user_templates_dir(), [Path(p) for p in extra_template_sources] + _sources('data', 'templates')),
         (get_user_icons_dir(),     _sources('assets', 'icons')),
         (get_user_indicators_dir(), _sources('assets', 'indicators')),
         (get_user_markups_dir(),   _sources('data', 'markups')),
@@ -7,6 +7,7 @@ from datetime import datetime
 from typing import Any, Dict
 from backend.core.graph import ProjectGraph
 from backend.core.node import Node
+from backend.infra.project_schema import SCHEMA_VERSION
 from backend.infra.schema_loader import SchemaLoader
 
 
@@ -39,8 +40,14 @@ class PersistenceManager:
             graph: The ProjectGraph to save
             template_paths: Optional list of template file paths used in this project
         """
+        # TALUS_INMEMORY=1 (set by the desktop shell) is a disposable session; the
+        # graph stays in memory and nothing is written.
+        if os.environ.get('TALUS_INMEMORY') == '1':
+            return
+
         data = {
             'version': '1.0',
+            'schema_version': SCHEMA_VERSION,
             'templates': template_paths or [],
             'nodes': {}
         }
@@ -0,0 +1,93 @@
+"""Schema version of saved project files (the JSON `PersistenceManager`
+writes) and the steps that upgrade older files.
+
+Files written before versioning have no `schema_version` and count as
+version 0. The desktop shell compares a file's version with the one it was
+built for before loading it, and backs the file up before asking for a
+migration. Add a step to `MIGRATIONS` and bump `SCHEMA_VERSION` whenever the
+saved format changes shape.
+"""
+import json
+import logging
+import os
+import tempfile
+from pathlib import Path
+from typing import Any, Callable, Dict, Optional
+
+logger = logging.getLogger(__name__)
+
+SCHEMA_VERSION = 1
+
+
+def _nodes_as_map(data: Dict[str, Any]) -> Dict[str, Any]:
+    """0 -> 1: some early files kept nodes as a list under `graph.nodes`;
+    version 1 always has a top-level `nodes` map keyed by id."""
+    graph = data.pop('graph', None)
+    if not data.get('nodes') and isinstance(graph, dict) and isinstance(graph.get('nodes'), list):
+        data['nodes'] = {str(node['id']): node for node in graph['nodes'] if isinstance(node, dict) and 'id' in node}
+    data.setdefault('nodes', {})
+    data.setdefault('templates', [])
+    return data
+
+
+# Step that takes a file from version N-1 to N, keyed by N
+MIGRATIONS: Dict[int, Callable[[Dict[str, Any]], Dict[str, Any]]] = {
+    1: _nodes_as_map,
+}
+
+
+def _read(path: Path) -> Dict[str, Any]:
+    with open(path, 'r') as f:
+        data = json.load(f)
+    if not isinstance(data, dict):
+        raise ValueError(f'{path} is not a Talus project file')
+    return data
+
+
+def file_version(path: Path) -> Optional[int]:
+    """Schema version of the project file at `path`, or None when there is no
+    readable project there; a missing file is unknown, not current."""
+    try:
+        data = _read(Path(path))
+    except (OSError, ValueError):
+        return None
+    version = data.get('schema_version', 0)
+    return version if isinstance(version, int) and version >= 0 else None
+
+
+def migration_available(version: Optional[int]) -> bool:
+    """Whether every step from `version` up to `SCHEMA_VERSION` exists."""
+    return (
+        version is not None
+        and version < SCHEMA_VERSION
+        and all(step in MIGRATIONS for step in range(version + 1, SCHEMA_VERSION + 1))
+    )
+
+
+def migrate(path: Path) -> int:
+    """Run each missing step and atomically replace the file with the result.
+    Returns the version reached."""
+    path = Path(path)
+    version = file_version(path)
+    if version is None:
+        raise ValueError(f'{path} is not a readable project file')
+    if version > SCHEMA_VERSION:
+        raise ValueError(f'Project schema {version} is newer than this backend supports ({SCHEMA_VERSION})')
+    if version == SCHEMA_VERSION:
+        return version
+    if not migration_available(version):
+        raise ValueError(f'No migration path from schema {version} to {SCHEMA_VERSION}')
+    data = _read(path)
+    for step in range(version + 1, SCHEMA_VERSION + 1):
+        logger.info(f"Migrating project {path} schema {step - 1} -> {step}")
+        data = MIGRATIONS[step](data)
+        data['schema_version'] = step
+    fd, temp_path = tempfile.mkstemp(prefix=path.name, dir=str(path.parent))
+    try:
+        with os.fdopen(fd, 'w') as f:
+            json.dump(data, f, indent=2)
+        os.replace(temp_path, path)
+    finally:
+        if os.path.exists(temp_path):
+            os.unlink(temp_path)
+    return SCHEMA_VERSION
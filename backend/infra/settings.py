@@ -7,6 +7,8 @@ file inside the XDG user data directory.
 
 import json
 import logging
+import os
+import tempfile
 from pathlib import Path
 from typing import Any, Dict, Optional
 
@@ -99,8 +101,16 @@ def save_settings(data: Dict[str, Any]) -> None:
     path = _settings_path()
     path.parent.mkdir(parents=True, exist_ok=True)
     normalized = _normalize_settings(data)
-    with open(path, "w") as f:
-        json.dump(normalized, f, indent=2)
+    # Replace atomically so a kill mid-write (or a backup copying the data
+    # dir) never sees a truncated file
+    fd, temp_path = tempfile.mkstemp(prefix=path.name, dir=str(path.parent))
+    try:
+        with os.fdopen(fd, "w") as f:
+            json.dump(normalized, f, indent=2)
+        os.replace(temp_path, path)
+    finally:
+        if os.path.exists(temp_path):
+            os.unlink(temp_path)
     _cache = normalized
     logger.info(f"Settings saved to {path}")
 
@@ -16,9 +16,17 @@ def get_user_data_dir() -> Path:
     Windows:  %LOCALAPPDATA%/talus_tally
     macOS:    ~/Library/Application Support/talus_tally
     Linux:    $XDG_DATA_HOME/talus_tally  (default ~/.local/share/talus_tally)
+
+    TALUS_DATA_DIR (set by the desktop shell) overrides all of the above.
     """
     import sys
 
+    override = os.environ.get('TALUS_DATA_DIR')
+    if override:
+        user_dir = Path(override)
+        user_dir.mkdir(parents=True, exist_ok=True)
+        return user_dir
+
     if sys.platform == 'win32':
         local = os.environ.get('LOCALAPPDATA')
         if local:
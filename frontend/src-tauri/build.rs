@@ -1,3 +1,46 @@
+use std::process::Command;
+
 fn main() {
+  embed_build_info();
   tauri_build::build()
 }
+
+/// Expose the commit, build time and target to `build_info` as env vars.
+/// TALUS_BUILD_COMMIT can be preset by CI building from a source tarball.
+fn embed_build_info() {
+  let commit = std::env::var("TALUS_BUILD_COMMIT").ok().filter(|v| !v.is_empty()).unwrap_or_else(git_commit);
+  let build_time = std::time::SystemTime::now()
+    .duration_since(std::time::UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or(0);
+  println!("cargo:rustc-env=TALUS_BUILD_COMMIT={}", commit);
+  println!("cargo:rustc-env=TALUS_BUILD_TIME={}", build_time);
+  println!("cargo:rustc-env=TALUS_BUILD_TARGET={}", std::env::var("TARGET").unwrap_or_default());
+  println!("cargo:rerun-if-env-changed=TALUS_BUILD_COMMIT");
+
+  if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+    println!("cargo:rerun-if-changed={}/HEAD", git_dir);
+    println!("cargo:rerun-if-changed={}/index", git_dir);
+  }
+}
+
+/// Short hash of HEAD, suffixed "-dirty" with uncommitted changes; "unknown" outside a checkout.
+fn git_commit() -> String {
+  let Some(hash) = git(&["rev-parse", "--short=12", "HEAD"]) else {
+    return "unknown".to_string();
+  };
+  let dirty = git(&["status", "--porcelain", "--untracked-files=no"]).is_some_and(|status| !status.is_empty());
+  if dirty {
+    format!("{}-dirty", hash)
+  } else {
+    hash
+  }
+}
+
+fn git(args: &[&str]) -> Option<String> {
+  let output = Command::new("git").args(args).output().ok()?;
+  output
+    .status
+    .success()
+    .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,284 @@
+//! Commands that are thin wrappers over backend HTTP endpoints. Endpoints an
+//! older backend doesn't implement surface as a clear error, not a hang.
+
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use crate::{config, diag, env_flag, events, http, BackendState};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Error from a command that waits on the backend. Serialized with a `kind`
+/// tag (`"timeout"` or `"failed"`) so the frontend can tell a backend that
+/// stopped answering from one that answered with an error.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CommandError {
+  Timeout { command: String, timeout_secs: u64, message: String },
+  Failed { message: String },
+}
+
+impl From<String> for CommandError {
+  fn from(message: String) -> Self {
+    CommandError::Failed { message }
+  }
+}
+
+impl std::fmt::Display for CommandError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      CommandError::Timeout { message, .. } | CommandError::Failed { message } => f.write_str(message),
+    }
+  }
+}
+
+/// Run `work` on its own thread and give up after the configured
+/// `command_timeout_secs`. Socket timeouts only bound each read, so a backend
+/// trickling bytes (or a step that blocks on a lock) could otherwise keep a
+/// command, and the frontend's `invoke`, pending forever. The abandoned thread
+/// finishes in the background; its result is dropped.
+pub fn with_timeout<T: Send + 'static>(
+  app: &tauri::AppHandle,
+  command: &str,
+  work: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, CommandError> {
+  with_timeout_of(command, command_timeout(app), work)
+}
+
+/// The configured `command_timeout_secs`, at least one second.
+pub fn command_timeout(app: &tauri::AppHandle) -> Duration {
+  Duration::from_secs(config::current(app).command_timeout_secs.max(1))
+}
+
+/// The error for `command` giving up after `timeout`, logged once here.
+pub fn timed_out(command: &str, timeout: Duration) -> CommandError {
+  diag(&format!("{} timed out after {}s", command, timeout.as_secs()));
+  CommandError::Timeout {
+    command: command.to_string(),
+    timeout_secs: timeout.as_secs(),
+    message: format!("{} got no answer from the backend within {}s", command, timeout.as_secs()),
+  }
+}
+
+/// `with_timeout` with an explicit limit, for steps known to run longer than
+/// the usual command timeout (setup, schema migration).
+pub fn with_timeout_of<T: Send + 'static>(
+  command: &str,
+  timeout: Duration,
+  work: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, CommandError> {
+  let (done, result) = mpsc::channel();
+  std::thread::spawn(move || {
+    let _ = done.send(work());
+  });
+  match result.recv_timeout(timeout) {
+    Ok(result) => result.map_err(CommandError::from),
+    Err(_) => Err(timed_out(command, timeout)),
+  }
+}
+
+#[derive(Clone, Serialize)]
+struct PausedChanged {
+  paused: bool,
+}
+
+/// POST `body` to `path` on our backend, mapping 404 to an "unsupported" error.
+fn post(backend: &BackendState, path: &str, body: serde_json::Value) -> Result<http::Response, String> {
+  let response = http::post_json(&backend.host(), backend.port(), path, &body, REQUEST_TIMEOUT)?;
+  if response.status == 404 {
+    return Err(format!("This backend version does not support {}", path));
+  }
+  if !response.is_success() {
+    return Err(format!("{} returned HTTP {}: {}", path, response.status, response.body.trim()));
+  }
+  Ok(response)
+}
+
+pub fn set_paused(app: &tauri::AppHandle, backend: &BackendState, paused: bool) -> Result<(), CommandError> {
+  let path = if paused { "/api/v1/pause" } else { "/api/v1/resume" };
+  let target = backend.clone();
+  let command = if paused { "pause_backend" } else { "resume_backend" };
+  with_timeout(app, command, move || post(&target, path, serde_json::json!({})))?;
+  backend.paused.store(paused, Ordering::SeqCst);
+  diag(&format!("Backend {}", if paused { "paused" } else { "resumed" }));
+  let event = if paused { "backend://paused" } else { "backend://resumed" };
+  events::emit_lifecycle(app, backend, event, PausedChanged { paused });
+  Ok(())
+}
+
+/// Stop the backend accepting tallies (e.g. during a break) without quitting.
+#[tauri::command(async)]
+pub fn pause_backend(app: tauri::AppHandle, state: tauri::State<BackendState>) -> Result<(), CommandError> {
+  set_paused(&app, state.inner(), true)
+}
+
+#[tauri::command(async)]
+pub fn resume_backend(app: tauri::AppHandle, state: tauri::State<BackendState>) -> Result<(), CommandError> {
+  set_paused(&app, state.inner(), false)
+}
+
+#[derive(Serialize)]
+pub struct ProxyResponse {
+  status: u16,
+  body: String,
+}
+
+/// Forward a request to the backend at the configured host/port, so the
+/// frontend doesn't need to know where the backend lives.
+#[tauri::command(async)]
+pub fn proxy_request(
+  app: tauri::AppHandle,
+  state: tauri::State<BackendState>,
+  method: String,
+  path: String,
+  body: Option<serde_json::Value>,
+) -> Result<ProxyResponse, CommandError> {
+  if !path.starts_with('/') {
+    return Err(format!("Proxy path must start with '/': {}", path).into());
+  }
+  let (host, port) = (state.host(), state.port());
+  with_timeout(&app, "proxy_request", move || {
+    let response = match method.to_uppercase().as_str() {
+      "GET" => http::get(&host, port, &path, REQUEST_TIMEOUT)?,
+      "POST" => http::post_json(&host, port, &path, &body.unwrap_or_default(), REQUEST_TIMEOUT)?,
+      other => return Err(format!("Unsupported proxy method {}", other)),
+    };
+    Ok(ProxyResponse {
+      status: response.status,
+      body: response.body,
+    })
+  })
+}
+
+#[derive(Serialize)]
+pub struct MemoryTrim {
+  rss_before: u64,
+  rss_after: u64,
+  /// `rss_before - rss_after`, or 0 if memory grew meanwhile.
+  reclaimed_bytes: u64,
+  /// Objects freed by the backend's garbage collection.
+  collected: u64,
+  /// Whether glibc `malloc_trim` released memory (Linux only).
+  malloc_trim: bool,
+}
+
+/// Resident memory of process `pid` in bytes.
+pub fn rss(pid: u32) -> Option<u64> {
+  let pid = Pid::from_u32(pid);
+  let mut system = System::new();
+  system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, ProcessRefreshKind::nothing().with_memory());
+  system.process(pid).map(|process| process.memory())
+}
+
+/// Ask the backend to garbage-collect and trim its heap, reporting its RSS
+/// before and after. Only works for a backend we spawned, since we need its PID.
+#[tauri::command(async)]
+pub fn trim_backend_memory(app: tauri::AppHandle, state: tauri::State<BackendState>) -> Result<MemoryTrim, CommandError> {
+  let pid = state
+    .process
+    .lock()
+    .ok()
+    .and_then(|proc| proc.as_ref().map(|child| child.id()))
+    .ok_or_else(|| "No spawned backend process to measure".to_string())?;
+  let rss_before = rss(pid).ok_or_else(|| format!("Backend process {} not found", pid))?;
+  let backend = state.inner().clone();
+  let result = with_timeout(&app, "trim_backend_memory", move || post(&backend, "/api/v1/trim-memory", serde_json::json!({}))?.json())?;
+  let rss_after = rss(pid).unwrap_or(rss_before);
+  let reclaimed_bytes = rss_before.saturating_sub(rss_after);
+  diag(&format!("Backend memory trim: {} -> {} bytes ({} reclaimed)", rss_before, rss_after, reclaimed_bytes));
+  Ok(MemoryTrim {
+    rss_before,
+    rss_after,
+    reclaimed_bytes,
+    collected: result.get("collected").and_then(|n| n.as_u64()).unwrap_or_default(),
+    malloc_trim: result.get("malloc_trim").and_then(|b| b.as_bool()).unwrap_or_default(),
+  })
+}
+
+/// Optional features the running backend supports (e.g. "metrics",
+/// "warmup"), so the UI can hide what an older backend lacks. A backend
+/// without the endpoint reports none. Cached until the backend restarts.
+#[tauri::command(async)]
+pub fn get_backend_capabilities(app: tauri::AppHandle, state: tauri::State<BackendState>) -> Result<Vec<String>, CommandError> {
+  if let Some(cached) = state.capabilities.lock().ok().and_then(|cached| cached.clone()) {
+    return Ok(cached);
+  }
+  let (host, port) = (state.host(), state.port());
+  let response = with_timeout(&app, "get_backend_capabilities", move || http::get(&host, port, "/api/v1/capabilities", REQUEST_TIMEOUT))?;
+  let capabilities: Vec<String> = match response.status {
+    404 => Vec::new(),
+    _ if response.is_success() => response
+      .json()?
+      .get("capabilities")
+      .and_then(|list| list.as_array())
+      .map(|list| list.iter().filter_map(|c| c.as_str().map(str::to_string)).collect())
+      .unwrap_or_default(),
+    status => return Err(format!("/api/v1/capabilities returned HTTP {}", status).into()),
+  };
+  if let Ok(mut cached) = state.capabilities.lock() {
+    *cached = Some(capabilities.clone());
+  }
+  Ok(capabilities)
+}
+
+/// How long first-run setup may take (copying templates and assets).
+const SETUP_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Clone, Serialize)]
+struct NeedsSetup {
+  port: u16,
+}
+
+/// After the backend comes up, emit `backend://needs-setup` if its health
+/// check says first-run setup hasn't happened yet.
+pub fn check_needs_setup(app: &tauri::AppHandle, backend: &BackendState) {
+  let port = backend.port();
+  if http::health_status(&backend.host(), port, REQUEST_TIMEOUT).as_deref() == Some(http::NEEDS_SETUP) {
+    diag("Backend reports first-run setup is required");
+    events::emit_lifecycle(app, backend, "backend://needs-setup", NeedsSetup { port });
+  }
+}
+
+/// Run the backend's first-run setup and wait for it to finish. `params` is
+/// passed through, e.g. `{"template_sources": ["/path/to/templates"]}`.
+#[tauri::command(async)]
+pub fn run_backend_setup(state: tauri::State<BackendState>, params: Option<serde_json::Value>) -> Result<serde_json::Value, CommandError> {
+  let body = params.unwrap_or_else(|| serde_json::json!({}));
+  let (host, port) = (state.host(), state.port());
+  let response = with_timeout_of("run_backend_setup", SETUP_TIMEOUT, move || http::post_json(&host, port, "/api/v1/setup", &body, SETUP_TIMEOUT))?;
+  if response.status == 404 {
+    return Err("This backend version does not support /api/v1/setup".to_string().into());
+  }
+  let result = response.json()?;
+  if !response.is_success() {
+    let error = result.get("error").and_then(|e| e.as_str()).unwrap_or(response.body.trim());
+    return Err(format!("Backend setup failed: {}", error).into());
+  }
+  diag("Backend first-run setup completed");
+  Ok(result)
+}
+
+const CRASH_MODES: [&str; 3] = ["hang", "exit-nonzero", "exit-clean"];
+
+/// Make the backend crash on purpose ("hang", "exit-nonzero" or
+/// "exit-clean") so QA can exercise the watchdog, crash supervisor and fatal
+/// screen. Requires TALUS_DEV=1 in both the app and the backend it spawned.
+#[tauri::command(async)]
+pub fn simulate_backend_crash(app: tauri::AppHandle, state: tauri::State<BackendState>, mode: String) -> Result<(), CommandError> {
+  if !env_flag("TALUS_DEV") {
+    return Err("simulate_backend_crash requires TALUS_DEV=1".to_string().into());
+  }
+  if !CRASH_MODES.contains(&mode.as_str()) {
+    return Err(format!("Unknown crash mode '{}'; expected one of {}", mode, CRASH_MODES.join(", ")).into());
+  }
+  if state.process.lock().map(|proc| proc.is_none()).unwrap_or(true) {
+    return Err("No spawned backend to crash".to_string().into());
+  }
+  diag(&format!("Simulating backend crash: {}", mode));
+  let backend = state.inner().clone();
+  with_timeout(&app, "simulate_backend_crash", move || post(&backend, "/api/v1/debug/crash", serde_json::json!({ "mode": mode })))?;
+  Ok(())
+}
@@ -0,0 +1,104 @@
+//! ERROR/CRITICAL lines picked out of the backend's log output, kept apart
+//! from the full log buffer. The backend logs as
+//! `<asctime> - <logger> - <LEVEL> - <message>`; a Python traceback on the
+//! following lines is attached to the entry. Each new entry is emitted as
+//! `backend://error` right away; its traceback fills in as it arrives.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use crate::{events, unix_now_ms};
+
+const MAX_ERRORS: usize = 200;
+const LEVELS: [&str; 2] = [" - ERROR - ", " - CRITICAL - "];
+
+static ERRORS: Mutex<VecDeque<ErrorEntry>> = Mutex::new(VecDeque::new());
+
+#[derive(Clone, Serialize)]
+pub struct ErrorEntry {
+  /// When we read the line, unix milliseconds.
+  timestamp_ms: u64,
+  /// The backend's own timestamp text, e.g. "2026-10-14 09:30:01,123".
+  logged_at: Option<String>,
+  logger: Option<String>,
+  message: String,
+  traceback: Option<String>,
+}
+
+/// Tracks a traceback that may follow the latest error line.
+#[derive(Default)]
+pub struct Collector {
+  /// Whether the latest error may still receive traceback lines.
+  open: bool,
+  in_traceback: bool,
+}
+
+fn parse(line: &str) -> Option<ErrorEntry> {
+  let level = LEVELS.iter().find(|level| line.contains(*level))?;
+  let (head, message) = line.split_once(level)?;
+  let (logged_at, logger) = match head.split_once(" - ") {
+    Some((logged_at, logger)) => (Some(logged_at.trim().to_string()), Some(logger.trim().to_string())),
+    None => (None, None),
+  };
+  Some(ErrorEntry {
+    timestamp_ms: unix_now_ms(),
+    logged_at,
+    logger,
+    message: message.trim().to_string(),
+    traceback: None,
+  })
+}
+
+fn append_traceback(line: &str) {
+  if let Some(entry) = ERRORS.lock().ok().as_mut().and_then(|errors| errors.back_mut()) {
+    let traceback = entry.traceback.get_or_insert_with(String::new);
+    if !traceback.is_empty() {
+      traceback.push('\n');
+    }
+    traceback.push_str(line);
+  }
+}
+
+impl Collector {
+  /// Feed one backend output line.
+  pub fn line(&mut self, app: &tauri::AppHandle, line: &str) {
+    if let Some(entry) = parse(line) {
+      if let Ok(mut errors) = ERRORS.lock() {
+        if errors.len() >= MAX_ERRORS {
+          errors.pop_front();
+        }
+        errors.push_back(entry.clone());
+      }
+      events::emit(app, "backend://error", entry);
+      self.open = true;
+      self.in_traceback = false;
+      return;
+    }
+    if !self.open {
+      return;
+    }
+    let starts_traceback = line.starts_with("Traceback (most recent call last)");
+    let continues = line.is_empty()
+      || line.starts_with(' ')
+      || line.starts_with("During handling")
+      || line.starts_with("The above exception");
+    if starts_traceback || (self.in_traceback && continues) {
+      self.in_traceback = true;
+      append_traceback(line);
+    } else if self.in_traceback {
+      // The unindented "ValueError: ..." line ends the traceback
+      append_traceback(line);
+      self.open = false;
+      self.in_traceback = false;
+    } else {
+      self.open = false;
+    }
+  }
+}
+
+/// Recent backend errors, oldest first.
+#[tauri::command]
+pub fn get_backend_errors() -> Vec<ErrorEntry> {
+  ERRORS.lock().map(|errors| errors.iter().cloned().collect()).unwrap_or_default()
+}
@@ -0,0 +1,208 @@
+//! Periodic safety snapshots of the backend's data store (see `data_store`)
+//! while it runs. Each snapshot is a `talus-<ts>` directory under
+//! `<data dir>/backups`, staged under a `.partial` name and renamed into place
+//! so a half-written copy never looks complete; the backend replaces its
+//! files atomically, so every copied file is whole. Snapshots beyond `keep`
+//! are deleted, oldest first. Each has a `talus-<ts>.json` beside it
+//! recording the app version that wrote it.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::{config, data_store, diag, events, unix_now, BackendState};
+
+pub const BACKUP_DIR: &str = "backups";
+pub const MAX_INTERVAL_MINS: u32 = 24 * 60;
+pub const MAX_KEEP: u32 = 1000;
+const TICK: Duration = Duration::from_secs(30);
+const SNAPSHOT_PREFIX: &str = "talus-";
+
+#[derive(Clone, Serialize)]
+pub struct BackupSchedule {
+  /// Minutes between snapshots; 0 means periodic backups are off.
+  interval_mins: u32,
+  keep: u32,
+}
+
+#[derive(Clone, Serialize)]
+pub struct BackupInfo {
+  path: PathBuf,
+  /// Unix seconds, from the metadata, the name or the modification time.
+  created: u64,
+  size: u64,
+  /// App version that wrote the snapshot; `None` for ones without metadata.
+  app_version: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct BackupCreated {
+  path: PathBuf,
+  size_bytes: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct BackupFailed {
+  error: String,
+}
+
+fn schedule(app: &tauri::AppHandle) -> BackupSchedule {
+  let config = config::current(app);
+  BackupSchedule {
+    interval_mins: config.backup_interval_mins,
+    keep: config.backup_keep,
+  }
+}
+
+/// The timestamp of a `talus-<ts>` snapshot directory, or `None` for anything
+/// else in the backup dir (metadata, staging copies, other backups).
+pub fn snapshot_time(path: &Path) -> Option<u64> {
+  if !path.is_dir() {
+    return None;
+  }
+  path.file_name()?.to_str()?.strip_prefix(SNAPSHOT_PREFIX)?.parse().ok()
+}
+
+fn snapshots(dir: &Path) -> Vec<PathBuf> {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return Vec::new();
+  };
+  entries
+    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+    .filter(|path| snapshot_time(path).is_some())
+    .collect()
+}
+
+/// Delete the oldest snapshots so at most `keep` remain. Only `talus-<ts>`
+/// snapshots are touched; anything else in the backup dir is left alone.
+fn rotate(dir: &Path, keep: u32) {
+  let mut found = snapshots(dir);
+  found.sort_by_key(|path| snapshot_time(path));
+  let excess = found.len().saturating_sub(keep.max(1) as usize);
+  for old in found.into_iter().take(excess) {
+    if let Err(err) = std::fs::remove_dir_all(&old) {
+      diag(&format!("Failed to remove old backup {}: {}", old.display(), err));
+    }
+    let _ = std::fs::remove_file(old.with_extension("json"));
+  }
+}
+
+/// Copy the backend's data store into a new snapshot and rotate; returns
+/// the snapshot's path. Fails when there is nothing to back up.
+pub fn snapshot(app: &tauri::AppHandle, keep: u32) -> Result<PathBuf, String> {
+  let data_dir = config::data_dir(app);
+  let entries = data_store::existing(&data_dir);
+  if entries.is_empty() {
+    return Err(format!("Nothing to back up: no backend data in {}", data_dir.display()));
+  }
+  let dir = data_dir.join(BACKUP_DIR);
+  std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+  let path = dir.join(format!("{}{}", SNAPSHOT_PREFIX, unix_now()));
+  if path.exists() {
+    return Err(format!("A backup named {} already exists", path.display()));
+  }
+  let staging = path.with_extension("partial");
+  let copied = entries.iter().try_fold(0, |total, entry| {
+    let rel = entry.strip_prefix(&data_dir).unwrap_or(entry);
+    data_store::copy_entry(entry, &staging.join(rel))
+      .map(|size| total + size)
+      .map_err(|e| format!("Failed to copy {}: {}", entry.display(), e))
+  });
+  let size_bytes = copied
+    .and_then(|size| {
+      std::fs::rename(&staging, &path)
+        .map(|_| size)
+        .map_err(|e| format!("Failed to finish {}: {}", path.display(), e))
+    })
+    .inspect_err(|_| {
+      let _ = std::fs::remove_dir_all(&staging);
+    })?;
+  let meta = serde_json::json!({ "app_version": env!("CARGO_PKG_VERSION"), "created": unix_now() });
+  if let Err(err) = std::fs::write(path.with_extension("json"), meta.to_string()) {
+    diag(&format!("Failed to write backup metadata for {}: {}", path.display(), err));
+  }
+  diag(&format!("Backup written to {} ({} bytes)", path.display(), size_bytes));
+  rotate(&dir, keep);
+  events::emit(app, "backup://created", BackupCreated { path: path.clone(), size_bytes });
+  Ok(path)
+}
+
+pub fn spawn(app: tauri::AppHandle, backend: BackendState) {
+  std::thread::spawn(move || {
+    let mut last = Instant::now();
+    loop {
+      std::thread::sleep(TICK);
+      let BackupSchedule { interval_mins, keep } = schedule(&app);
+      if interval_mins == 0 || last.elapsed() < Duration::from_secs(interval_mins as u64 * 60) {
+        continue;
+      }
+      // An adopted or remote backend may not be using our data dir
+      let mode = backend.mode.lock().ok().and_then(|mode| *mode);
+      if config::in_memory() || matches!(mode, None | Some("adopted") | Some("remote")) || !backend.is_reachable() {
+        continue;
+      }
+      last = Instant::now();
+      if let Err(error) = snapshot(&app, keep) {
+        diag(&format!("Periodic backup failed: {}", error));
+        events::emit(&app, "backup://failed", BackupFailed { error });
+      }
+    }
+  });
+}
+
+fn backup_info(path: PathBuf) -> Option<BackupInfo> {
+  let from_name = snapshot_time(&path)?;
+  let meta: Option<serde_json::Value> = std::fs::read_to_string(path.with_extension("json"))
+    .ok()
+    .and_then(|text| serde_json::from_str(&text).ok());
+  let created = meta
+    .as_ref()
+    .and_then(|meta| meta.get("created").and_then(|c| c.as_u64()))
+    .unwrap_or(from_name);
+  let size = data_store::files(&path)
+    .iter()
+    .filter_map(|file| std::fs::metadata(file).ok())
+    .map(|metadata| metadata.len())
+    .sum();
+  Some(BackupInfo {
+    created,
+    size,
+    app_version: meta.and_then(|meta| meta.get("app_version").and_then(|v| v.as_str()).map(str::to_string)),
+    path,
+  })
+}
+
+/// Snapshots in the backup directory, newest first, for a restore picker.
+#[tauri::command]
+pub fn list_backups(app: tauri::AppHandle) -> Result<Vec<BackupInfo>, String> {
+  let dir = config::data_dir(&app).join(BACKUP_DIR);
+  let entries = match std::fs::read_dir(&dir) {
+    Ok(entries) => entries,
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(err) => return Err(format!("Failed to read {}: {}", dir.display(), err)),
+  };
+  let mut backups: Vec<BackupInfo> = entries
+    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+    .filter_map(backup_info)
+    .collect();
+  backups.sort_by(|a, b| b.created.cmp(&a.created).then_with(|| b.path.cmp(&a.path)));
+  Ok(backups)
+}
+
+#[tauri::command]
+pub fn get_backup_schedule(app: tauri::AppHandle) -> BackupSchedule {
+  schedule(&app)
+}
+
+/// Persist the backup interval and how many snapshots to keep (clamped).
+/// Takes effect on the timer's next tick.
+#[tauri::command]
+pub fn set_backup_schedule(app: tauri::AppHandle, interval_mins: u32, keep: u32) -> Result<BackupSchedule, String> {
+  let interval_mins = interval_mins.min(MAX_INTERVAL_MINS);
+  let keep = keep.clamp(1, MAX_KEEP);
+  config::update(&app, |config| {
+    config.backup_interval_mins = interval_mins;
+    config.backup_keep = keep;
+  })?;
+  Ok(BackupSchedule { interval_mins, keep })
+}
@@ -0,0 +1,37 @@
+//! Build metadata embedded by build.rs, so a bug report names the exact build
+//! even when dev builds share a version number.
+
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+pub struct BuildInfo {
+  version: &'static str,
+  /// Short git hash, "-dirty" when built with uncommitted changes, or "unknown".
+  commit: &'static str,
+  /// Unix seconds when the build script ran.
+  build_time: u64,
+  /// Target triple, e.g. "x86_64-unknown-linux-gnu".
+  target: &'static str,
+  profile: &'static str,
+}
+
+pub fn current() -> BuildInfo {
+  BuildInfo {
+    version: env!("CARGO_PKG_VERSION"),
+    commit: env!("TALUS_BUILD_COMMIT"),
+    build_time: env!("TALUS_BUILD_TIME").parse().unwrap_or(0),
+    target: env!("TALUS_BUILD_TARGET"),
+    profile: if cfg!(debug_assertions) { "debug" } else { "release" },
+  }
+}
+
+/// One-line summary for the diagnostic log.
+pub fn summary() -> String {
+  let info = current();
+  format!("Talus Tally {} ({}, {}, {}, built {})", info.version, info.commit, info.target, info.profile, info.build_time)
+}
+
+#[tauri::command]
+pub fn get_build_info() -> BuildInfo {
+  current()
+}
@@ -0,0 +1,137 @@
+//! Clock skew detection. Tally records are ordered by timestamp, so a system
+//! clock that is far off (dead CMOS battery, VM resumed from snapshot) quietly
+//! scrambles event order.
+
+use serde::Serialize;
+use std::net::UdpSocket;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::{config, diag, events, http, BackendState};
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+/// Seconds between the NTP epoch (1900) and the Unix epoch (1970).
+const NTP_UNIX_OFFSET: f64 = 2_208_988_800.0;
+
+#[derive(Clone, Serialize)]
+pub struct ClockCheck {
+  /// Local clock minus backend clock, in seconds; `None` if the backend didn't report its time.
+  backend_skew_secs: Option<f64>,
+  /// Local clock minus NTP time, in seconds; `None` if no server was queried or it didn't answer.
+  ntp_skew_secs: Option<f64>,
+  threshold_secs: f64,
+  exceeded: bool,
+  errors: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct SkewWarning {
+  source: &'static str,
+  skew_secs: f64,
+  threshold_secs: f64,
+}
+
+fn now_secs() -> f64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs_f64())
+    .unwrap_or(0.0)
+}
+
+/// Compare against a remote timestamp taken mid-request, halving the round trip
+/// as the best guess for when the remote clock was read.
+fn skew_against(remote_secs: f64, sent_at: f64, rtt: Duration) -> f64 {
+  sent_at + rtt.as_secs_f64() / 2.0 - remote_secs
+}
+
+fn backend_skew(host: &str, port: u16) -> Result<f64, String> {
+  let sent_at = now_secs();
+  let started = Instant::now();
+  let response = http::get(host, port, http::HEALTH_PATH, CHECK_TIMEOUT)?;
+  let rtt = started.elapsed();
+  let time = response
+    .json()?
+    .get("time")
+    .and_then(|t| t.as_f64())
+    .ok_or_else(|| "Backend health response has no 'time' field".to_string())?;
+  Ok(skew_against(time, sent_at, rtt))
+}
+
+/// Single SNTP (RFC 4330) query; reads the server's transmit timestamp.
+fn ntp_skew(server: &str) -> Result<f64, String> {
+  let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| e.to_string())?;
+  socket.set_read_timeout(Some(CHECK_TIMEOUT)).map_err(|e| e.to_string())?;
+  let target = if server.contains(':') { server.to_string() } else { format!("{}:123", server) };
+  socket
+    .connect(&target)
+    .map_err(|e| format!("Cannot reach NTP server {}: {}", target, e))?;
+
+  let mut packet = [0u8; 48];
+  // LI = 0, version 4, mode 3 (client)
+  packet[0] = 0x23;
+  let sent_at = now_secs();
+  let started = Instant::now();
+  socket.send(&packet).map_err(|e| format!("NTP request to {} failed: {}", target, e))?;
+  let received = socket
+    .recv(&mut packet)
+    .map_err(|e| format!("No NTP reply from {}: {}", target, e))?;
+  let rtt = started.elapsed();
+  if received < 48 {
+    return Err(format!("Short NTP reply from {}", target));
+  }
+
+  let seconds = u32::from_be_bytes([packet[40], packet[41], packet[42], packet[43]]) as f64;
+  let fraction = u32::from_be_bytes([packet[44], packet[45], packet[46], packet[47]]) as f64 / 4_294_967_296.0;
+  if seconds == 0.0 {
+    return Err(format!("NTP server {} returned no time", target));
+  }
+  Ok(skew_against(seconds + fraction - NTP_UNIX_OFFSET, sent_at, rtt))
+}
+
+/// Measure how far the system clock is from the backend's and, when an NTP
+/// server is given (or configured), from NTP. Emits `clock://skew-warning`
+/// for each source whose skew exceeds the configured threshold.
+#[tauri::command(async)]
+pub fn check_clock(app: tauri::AppHandle, state: tauri::State<BackendState>, ntp_server: Option<String>) -> ClockCheck {
+  let config = config::current(&app);
+  let threshold_secs = config.clock_skew_threshold_secs as f64;
+  let mut errors = Vec::new();
+
+  let backend_skew_secs = backend_skew(&state.host(), state.port())
+    .map_err(|err| errors.push(format!("backend: {}", err)))
+    .ok();
+  let ntp_skew_secs = ntp_server
+    .or(config.ntp_server)
+    .filter(|server| !server.trim().is_empty())
+    .and_then(|server| {
+      ntp_skew(server.trim())
+        .map_err(|err| errors.push(format!("ntp: {}", err)))
+        .ok()
+    });
+
+  let mut exceeded = false;
+  for (source, skew) in [("backend", backend_skew_secs), ("ntp", ntp_skew_secs)] {
+    let Some(skew_secs) = skew else {
+      continue;
+    };
+    if skew_secs.abs() > threshold_secs {
+      exceeded = true;
+      diag(&format!("Clock skew against {} is {:.1}s (threshold {}s)", source, skew_secs, threshold_secs));
+      events::emit(
+        &app,
+        "clock://skew-warning",
+        SkewWarning {
+          source,
+          skew_secs,
+          threshold_secs,
+        },
+      );
+    }
+  }
+
+  ClockCheck {
+    backend_skew_secs,
+    ntp_skew_secs,
+    threshold_secs,
+    exceeded,
+    errors,
+  }
+}
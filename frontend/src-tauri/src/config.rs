@@ -0,0 +1,644 @@
+//! Persisted user settings, stored as JSON in the app config directory.
+//! On Linux: $XDG_CONFIG_HOME/com.talus.tally/config.json (or ~/.config/...)
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::supervisor::RestartReason;
+use crate::{config_check, diag, events, pending_restart, restart, unix_now, user_data_base, BackendState};
+
+pub const DEFAULT_PORT: u16 = 5000;
+pub const DEFAULT_HOST: &str = "127.0.0.1";
+pub const DEFAULT_LOG_BUFFER_LINES: u32 = 1000;
+
+/// Bounds for the UI poll interval; the floor keeps a weak machine from being hammered.
+pub const MIN_POLL_INTERVAL_MS: u64 = 250;
+pub const MAX_POLL_INTERVAL_MS: u64 = 60_000;
+
+/// Settings whose new value only takes effect the next time the backend starts.
+const RESTART_REQUIRED: &[&str] = &["adopt_existing_backend", "talus_env", "port", "host", "data_dir", "profiles", "active_profile", "backend_workers", "python_path"];
+
+/// Current on-disk schema version. Bump it and append to MIGRATIONS when a
+/// change needs existing files rewritten (renamed/re-typed fields).
+pub const CONFIG_VERSION: u32 = 1;
+
+/// MIGRATIONS[n] upgrades a version-n config object to version n + 1.
+const MIGRATIONS: &[fn(&mut serde_json::Map<String, serde_json::Value>)] = &[
+  // v0 -> v1: unversioned files predate the `version` field; no field changes.
+  |_| {},
+];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+  /// Schema version of this config (see CONFIG_VERSION).
+  pub version: u32,
+  /// Reuse a backend that is already answering instead of killing it on launch.
+  pub adopt_existing_backend: bool,
+  /// Override for the TALUS_ENV passed to the backend. The env var still wins.
+  pub talus_env: Option<String>,
+  /// Pause before killing the backend on exit so pending writes can flush.
+  pub exit_flush_delay_ms: u64,
+  /// How long a graceful stop waits for the backend before killing it.
+  pub shutdown_timeout_secs: u64,
+  /// Limit on backend-dependent commands, so a stalled backend can't leave a frontend `invoke` pending.
+  pub command_timeout_secs: u64,
+  /// Port the backend listens on (passed as TALUS_PORT).
+  pub port: u16,
+  /// Backend host. Anything but loopback means a remote backend we don't manage.
+  pub host: String,
+  /// Writable directory for the backend's database and user files.
+  pub data_dir: Option<PathBuf>,
+  /// Named data dir/port pairs for running isolated tallies.
+  pub profiles: Vec<Profile>,
+  /// Profile whose data dir and port override the top-level ones.
+  pub active_profile: Option<String>,
+  /// Accelerator that shows/hides the main window; empty disables it.
+  pub global_shortcut: String,
+  /// How often the frontend polls the backend for live updates.
+  pub poll_interval_ms: u64,
+  /// Lines of backend output kept in memory for `get_backend_log`.
+  pub log_buffer_lines: u32,
+  /// Backend output lines longer than this (bytes) are truncated.
+  pub max_log_line_bytes: usize,
+  /// How often `backend://heartbeat` is emitted.
+  pub heartbeat_interval_ms: u64,
+  /// Window in which forwarded `tally://update` events are coalesced.
+  pub update_batch_ms: u64,
+  /// Watch the data dir and report database changes made by other processes.
+  pub watch_data_dir: bool,
+  /// Whether the main window can be resized (off for locked-down kiosks).
+  pub resizable: bool,
+  /// Closing the last visible window hides it to the tray instead of quitting.
+  pub keep_in_tray: bool,
+  /// User-defined window layouts (built-ins live in `window`).
+  pub layout_presets: Vec<crate::window::LayoutPreset>,
+  /// Clock skew (seconds) beyond which `check_clock` warns.
+  pub clock_skew_threshold_secs: u64,
+  /// NTP server `check_clock` also compares against, e.g. "pool.ntp.org".
+  pub ntp_server: Option<String>,
+  /// Request concurrency passed to the backend as TALUS_WORKERS; `None` keeps its default.
+  pub backend_workers: Option<u32>,
+  /// Branding accent as "#rrggbb"; `None` uses the frontend's default theme.
+  pub accent_color: Option<String>,
+  /// Minutes between periodic database snapshots; 0 turns them off.
+  pub backup_interval_mins: u32,
+  /// Periodic snapshots kept before the oldest is deleted.
+  pub backup_keep: u32,
+  /// Interpreter tried before the virtualenv and system Python (set via `set_python_path`).
+  pub python_path: Option<PathBuf>,
+  /// Backend resident memory (MiB) above which the watchdog acts; 0 means no limit.
+  pub max_rss_mb: u64,
+  /// What the watchdog does when the backend exceeds `max_rss_mb`.
+  pub memory_limit_action: MemoryLimitAction,
+  /// Hottest-sensor temperature at which `system://thermal-warning` fires.
+  pub thermal_warning_celsius: f32,
+  /// Drop the backend to below-normal priority when that warning fires.
+  pub thermal_lower_priority: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryLimitAction {
+  /// Emit `backend://memory-limit` once per excursion over the limit.
+  #[default]
+  Warn,
+  /// Also restart the backend to reclaim the memory.
+  Restart,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+  pub name: String,
+  pub data_dir: PathBuf,
+  pub port: u16,
+}
+
+impl Config {
+  pub fn active_profile(&self) -> Option<&Profile> {
+    let name = self.active_profile.as_ref()?;
+    self.profiles.iter().find(|profile| &profile.name == name)
+  }
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Config {
+      version: CONFIG_VERSION,
+      adopt_existing_backend: false,
+      talus_env: None,
+      exit_flush_delay_ms: 250,
+      shutdown_timeout_secs: 5,
+      command_timeout_secs: 30,
+      port: DEFAULT_PORT,
+      host: DEFAULT_HOST.to_string(),
+      data_dir: None,
+      profiles: Vec::new(),
+      active_profile: None,
+      global_shortcut: crate::shortcut::DEFAULT_SHORTCUT.to_string(),
+      poll_interval_ms: 1000,
+      heartbeat_interval_ms: 5000,
+      update_batch_ms: 50,
+      log_buffer_lines: DEFAULT_LOG_BUFFER_LINES,
+      max_log_line_bytes: 8 * 1024,
+      watch_data_dir: false,
+      resizable: true,
+      keep_in_tray: false,
+      layout_presets: Vec::new(),
+      clock_skew_threshold_secs: 30,
+      ntp_server: None,
+      backend_workers: None,
+      accent_color: None,
+      backup_interval_mins: 0,
+      backup_keep: 10,
+      python_path: None,
+      max_rss_mb: 0,
+      memory_limit_action: MemoryLimitAction::Warn,
+      thermal_warning_celsius: 85.0,
+      thermal_lower_priority: false,
+    }
+  }
+}
+
+pub struct ConfigState(pub Mutex<Config>);
+
+/// Snapshot of the current config, falling back to defaults if the lock is poisoned.
+pub fn current(app: &tauri::AppHandle) -> Config {
+  app
+    .state::<ConfigState>()
+    .0
+    .lock()
+    .map(|config| config.clone())
+    .unwrap_or_default()
+}
+
+/// Graceful-stop timeout: TALUS_SHUTDOWN_TIMEOUT (seconds) wins over the config
+/// file. A negative, infinite or out-of-range value falls back to the config.
+pub fn shutdown_timeout(app: &tauri::AppHandle) -> Duration {
+  std::env::var("TALUS_SHUTDOWN_TIMEOUT")
+    .ok()
+    .and_then(|v| v.trim().parse::<f64>().ok())
+    .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+    .unwrap_or_else(|| Duration::from_secs(current(app).shutdown_timeout_secs))
+}
+
+/// Backend port: TALUS_PORT, then the active profile, then the config file.
+pub fn backend_port(app: &tauri::AppHandle) -> u16 {
+  let config = current(app);
+  std::env::var("TALUS_PORT")
+    .ok()
+    .and_then(|v| v.trim().parse::<u16>().ok())
+    .filter(|port| *port != 0)
+    .or_else(|| config.active_profile().map(|profile| profile.port))
+    .unwrap_or(config.port)
+}
+
+/// Interpreter to run the backend with: TALUS_PYTHON, then the config file.
+pub fn python_path(app: &tauri::AppHandle) -> Option<PathBuf> {
+  std::env::var_os("TALUS_PYTHON")
+    .filter(|v| !v.is_empty())
+    .map(PathBuf::from)
+    .or(current(app).python_path)
+}
+
+/// Parse a memory size such as "1536", "512M" or "2G" (plain numbers are MiB).
+fn parse_mib(value: &str) -> Option<u64> {
+  let value = value.trim().to_uppercase();
+  let value = value.strip_suffix("B").unwrap_or(&value).trim_end_matches('I');
+  let (digits, scale) = match value.char_indices().last()? {
+    (at, 'K') => (&value[..at], 1.0 / 1024.0),
+    (at, 'M') => (&value[..at], 1.0),
+    (at, 'G') => (&value[..at], 1024.0),
+    _ => (value, 1.0),
+  };
+  let mib = digits.trim().parse::<f64>().ok().filter(|n| n.is_finite() && *n >= 0.0)? * scale;
+  Some(mib.round() as u64)
+}
+
+/// Backend RSS limit in bytes: TALUS_MAX_RSS ("512M", "2G"; plain numbers are
+/// MiB) wins over the config file. `None` when there is no limit.
+pub fn max_rss_bytes(app: &tauri::AppHandle) -> Option<u64> {
+  std::env::var("TALUS_MAX_RSS")
+    .ok()
+    .and_then(|v| parse_mib(&v))
+    .unwrap_or_else(|| current(app).max_rss_mb)
+    .checked_mul(1024 * 1024)
+    .filter(|bytes| *bytes > 0)
+}
+
+/// TALUS_MAX_RSS_ACTION ("warn" or "restart") wins over the config file.
+pub fn memory_limit_action(app: &tauri::AppHandle) -> MemoryLimitAction {
+  match std::env::var("TALUS_MAX_RSS_ACTION").map(|v| v.trim().to_lowercase()).as_deref() {
+    Ok("warn") => MemoryLimitAction::Warn,
+    Ok("restart") => MemoryLimitAction::Restart,
+    _ => current(app).memory_limit_action,
+  }
+}
+
+/// Backend host: TALUS_HOST wins over the config file.
+pub fn backend_host(app: &tauri::AppHandle) -> String {
+  std::env::var("TALUS_HOST")
+    .ok()
+    .map(|v| v.trim().to_string())
+    .filter(|v| !v.is_empty())
+    .unwrap_or_else(|| current(app).host)
+}
+
+pub fn is_loopback(host: &str) -> bool {
+  matches!(host, "localhost" | "::1") || host.starts_with("127.")
+}
+
+/// TALUS_INMEMORY=1 runs a disposable session for demos and automated tests:
+/// the backend keeps projects in memory only and the data dir is a scratch
+/// directory under the system temp dir, left for the OS to clean up.
+pub fn in_memory() -> bool {
+  crate::env_flag("TALUS_INMEMORY")
+}
+
+/// Backend data dir: the in-memory scratch dir, TALUS_DATA_DIR, then the
+/// active profile, then the config file, then the platform default the
+/// backend itself uses.
+pub fn data_dir(app: &tauri::AppHandle) -> PathBuf {
+  if in_memory() {
+    return std::env::temp_dir().join(format!("talus-inmemory-{}", std::process::id()));
+  }
+  let config = current(app);
+  std::env::var_os("TALUS_DATA_DIR")
+    .filter(|v| !v.is_empty())
+    .map(PathBuf::from)
+    .or_else(|| config.active_profile().map(|profile| profile.data_dir.clone()))
+    .or(config.data_dir)
+    .or_else(|| user_data_base().map(|base| base.join("talus_tally")))
+    .unwrap_or_else(|| PathBuf::from("talus_tally"))
+}
+
+fn config_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+  app
+    .path()
+    .app_config_dir()
+    .map(|dir| dir.join("config.json"))
+    .map_err(|e| format!("Could not resolve config directory: {}", e))
+}
+
+/// Parse config JSON, running forward migrations from its on-disk version.
+/// Returns the config and the version it was stored as.
+fn parse_config(text: &str) -> Result<(Config, u32), String> {
+  let mut value: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+  let object = value
+    .as_object_mut()
+    .ok_or_else(|| "config is not a JSON object".to_string())?;
+  let stored_version = object.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+
+  if stored_version > CONFIG_VERSION {
+    diag(&format!(
+      "Config version {} is newer than supported {}; unknown settings will be ignored",
+      stored_version, CONFIG_VERSION
+    ));
+  }
+  for (from, migrate) in MIGRATIONS.iter().enumerate().skip(stored_version as usize) {
+    diag(&format!("Migrating config v{} -> v{}", from, from + 1));
+    migrate(object);
+  }
+  object.insert("version".to_string(), CONFIG_VERSION.max(stored_version).into());
+
+  let config = serde_json::from_value(value).map_err(|e| e.to_string())?;
+  Ok((config, stored_version))
+}
+
+fn read_config(app: &tauri::AppHandle) -> Result<Config, String> {
+  let path = config_path(app)?;
+  if !path.exists() {
+    return Ok(Config::default());
+  }
+  let text = std::fs::read_to_string(&path)
+    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+  parse_config(&text)
+    .map(|(config, _)| config)
+    .map_err(|e| format!("Invalid config {}: {}", path.display(), e))
+}
+
+/// Load the config from disk. Older versions are migrated and rewritten; a
+/// corrupt file is set aside as `config.json.corrupt-<ts>` and defaults used.
+pub fn load(app: &tauri::AppHandle) -> Config {
+  let path = match config_path(app) {
+    Ok(path) => path,
+    Err(err) => {
+      diag(&format!("{}; using default config", err));
+      return Config::default();
+    }
+  };
+  let text = match std::fs::read_to_string(&path) {
+    Ok(text) => text,
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Config::default(),
+    Err(err) => {
+      diag(&format!("Failed to read {}: {}; using default config", path.display(), err));
+      return Config::default();
+    }
+  };
+
+  match parse_config(&text) {
+    Ok((config, stored_version)) => {
+      if stored_version < CONFIG_VERSION {
+        if let Err(err) = save(app, &config) {
+          diag(&format!("Failed to write migrated config: {}", err));
+        }
+      }
+      config
+    }
+    Err(err) => {
+      let backup = PathBuf::from(format!("{}.corrupt-{}", path.display(), unix_now()));
+      diag(&format!(
+        "Config {} is corrupt ({}); moving it to {} and using defaults",
+        path.display(),
+        err,
+        backup.display()
+      ));
+      let _ = std::fs::rename(&path, &backup);
+      Config::default()
+    }
+  }
+}
+
+pub fn save(app: &tauri::AppHandle, config: &Config) -> Result<(), String> {
+  let path = config_path(app)?;
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)
+      .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+  }
+  let text = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+  std::fs::write(&path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Apply `change` to the live config and persist the result.
+pub fn update(app: &tauri::AppHandle, change: impl FnOnce(&mut Config)) -> Result<Config, String> {
+  let state = app.state::<ConfigState>();
+  let mut config = state.0.lock().map_err(|_| "Config lock poisoned".to_string())?;
+  change(&mut config);
+  save(app, &config)?;
+  Ok(config.clone())
+}
+
+/// Names of the top-level fields whose values differ between two configs.
+fn changed_fields(old: &Config, new: &Config) -> Vec<String> {
+  let old = serde_json::to_value(old).unwrap_or_default();
+  let new = serde_json::to_value(new).unwrap_or_default();
+  match (old.as_object(), new.as_object()) {
+    (Some(old), Some(new)) => new
+      .iter()
+      .filter(|(key, value)| old.get(*key) != Some(*value))
+      .map(|(key, _)| key.clone())
+      .collect(),
+    _ => Vec::new(),
+  }
+}
+
+/// Where an effective setting came from.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+  Default,
+  File,
+  Profile,
+  Env,
+}
+
+#[derive(Serialize)]
+pub struct EffectiveConfig {
+  /// The config with env var and profile overrides applied, as the app uses it.
+  config: Config,
+  /// Source of every top-level field in `config`.
+  sources: std::collections::BTreeMap<String, Source>,
+}
+
+fn env_value(name: &str) -> Option<String> {
+  std::env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// The settings actually in effect, merged from defaults, the config file, the
+/// active profile and env vars, with which of those each field came from.
+#[tauri::command]
+pub fn get_effective_config(app: tauri::AppHandle) -> EffectiveConfig {
+  let stored = current(&app);
+  let defaults = serde_json::to_value(Config::default()).unwrap_or_default();
+  let stored_value = serde_json::to_value(&stored).unwrap_or_default();
+  let mut sources: std::collections::BTreeMap<String, Source> = stored_value
+    .as_object()
+    .map(|fields| {
+      fields
+        .iter()
+        .map(|(key, value)| {
+          let source = if defaults.get(key) == Some(value) { Source::Default } else { Source::File };
+          (key.clone(), source)
+        })
+        .collect()
+    })
+    .unwrap_or_default();
+  let mut set = |field: &str, source: Source| {
+    sources.insert(field.to_string(), source);
+  };
+
+  let mut config = stored.clone();
+  if crate::env_flag("TALUS_ADOPT_BACKEND") {
+    config.adopt_existing_backend = true;
+    set("adopt_existing_backend", Source::Env);
+  }
+  if let Some(env) = env_value("TALUS_ENV") {
+    config.talus_env = Some(env);
+    set("talus_env", Source::Env);
+  }
+  if std::env::var("TALUS_SHUTDOWN_TIMEOUT").is_ok() {
+    let timeout = shutdown_timeout(&app);
+    if timeout != Duration::from_secs(stored.shutdown_timeout_secs) {
+      config.shutdown_timeout_secs = timeout.as_secs();
+      set("shutdown_timeout_secs", Source::Env);
+    }
+  }
+  if let Some(profile) = stored.active_profile() {
+    config.port = profile.port;
+    config.data_dir = Some(profile.data_dir.clone());
+    set("port", Source::Profile);
+    set("data_dir", Source::Profile);
+  }
+  if env_value("TALUS_PORT").and_then(|v| v.parse::<u16>().ok()).filter(|port| *port != 0).is_some() {
+    config.port = backend_port(&app);
+    set("port", Source::Env);
+  }
+  if env_value("TALUS_HOST").is_some() {
+    config.host = backend_host(&app);
+    set("host", Source::Env);
+  }
+  if env_value("TALUS_PYTHON").is_some() {
+    config.python_path = python_path(&app);
+    set("python_path", Source::Env);
+  }
+  if let Some(mib) = env_value("TALUS_MAX_RSS").and_then(|v| parse_mib(&v)) {
+    config.max_rss_mb = mib;
+    set("max_rss_mb", Source::Env);
+  }
+  if env_value("TALUS_MAX_RSS_ACTION").is_some() {
+    config.memory_limit_action = memory_limit_action(&app);
+    set("memory_limit_action", Source::Env);
+  }
+  if env_value("TALUS_DATA_DIR").is_some() || in_memory() {
+    set("data_dir", Source::Env);
+  }
+  // Always report the resolved dir, including the platform default.
+  config.data_dir = Some(data_dir(&app));
+
+  EffectiveConfig { config, sources }
+}
+
+#[derive(Serialize)]
+pub struct ReloadResult {
+  config: Config,
+  /// Changed settings that are already in effect.
+  applied: Vec<String>,
+  /// Changed settings that need a backend restart before they take effect.
+  restart_required: Vec<String>,
+}
+
+#[tauri::command]
+pub fn open_config_file(app: tauri::AppHandle) -> Result<String, String> {
+  let path = config_path(&app)?;
+  if !path.exists() {
+    diag(&format!("Config file missing; writing defaults to {}", path.display()));
+    save(&app, &current(&app))?;
+  }
+  app
+    .opener()
+    .open_path(path.to_string_lossy(), None::<&str>)
+    .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+  Ok(path.display().to_string())
+}
+
+/// Re-read the config file after a hand edit. Values are clamped and checked
+/// like the individual setters; live settings take effect at once, and
+/// settings listed in `restart_required` wait for the caller to restart the
+/// backend (e.g. via `restart_backend`).
+#[tauri::command(async)]
+pub fn reload_config(app: tauri::AppHandle, state: tauri::State<BackendState>) -> Result<ReloadResult, String> {
+  let new_config = read_config(&app)?;
+  replace(&app, state.inner(), new_config, "reload from disk", false)
+}
+
+#[tauri::command]
+pub fn get_poll_interval(app: tauri::AppHandle) -> u64 {
+  current(&app).poll_interval_ms
+}
+
+/// Persist a new poll interval (clamped) and tell the frontend to adjust its timer.
+#[tauri::command]
+pub fn set_poll_interval(app: tauri::AppHandle, ms: u64) -> Result<u64, String> {
+  let ms = ms.clamp(MIN_POLL_INTERVAL_MS, MAX_POLL_INTERVAL_MS);
+  update(&app, |config| config.poll_interval_ms = ms)?;
+  events::emit(&app, "config://poll-interval-changed", ms);
+  Ok(ms)
+}
+
+/// Placeholder for the platform data base dir in exported profile paths, so a
+/// profile under e.g. ~/.local/share on one machine maps to the same place on another.
+const DATA_BASE_TOKEN: &str = "$DATA_BASE";
+
+/// Write the current config to `dest` for copying to another machine. The
+/// data dir is left out; profile dirs under the platform data dir are made
+/// portable and remapped on import.
+#[tauri::command]
+pub fn export_config(app: tauri::AppHandle, dest: String) -> Result<(), String> {
+  let mut config = current(&app);
+  config.data_dir = None;
+  if let Some(base) = user_data_base() {
+    for profile in &mut config.profiles {
+      if let Ok(relative) = profile.data_dir.strip_prefix(&base) {
+        profile.data_dir = PathBuf::from(DATA_BASE_TOKEN).join(relative);
+      }
+    }
+  }
+  let text = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+  std::fs::write(&dest, text).map_err(|e| format!("Failed to write {}: {}", dest, e))?;
+  diag(&format!("Exported config to {}", dest));
+  Ok(())
+}
+
+/// Replace the config with one from `export_config`, migrating older versions.
+/// Live settings are applied at once; if any `restart_required` setting
+/// changed, the backend has already been restarted when this returns.
+#[tauri::command(async)]
+pub fn import_config(app: tauri::AppHandle, state: tauri::State<BackendState>, src: String) -> Result<ReloadResult, String> {
+  let text = std::fs::read_to_string(&src).map_err(|e| format!("Failed to read {}: {}", src, e))?;
+  let (mut imported, stored_version) = parse_config(&text).map_err(|e| format!("Invalid config {}: {}", src, e))?;
+  if stored_version > CONFIG_VERSION {
+    return Err(format!(
+      "{} is config version {}, newer than this app supports ({}); update Talus Tally first",
+      src, stored_version, CONFIG_VERSION
+    ));
+  }
+
+  imported.data_dir = current(&app).data_dir;
+  if let Some(base) = user_data_base() {
+    for profile in &mut imported.profiles {
+      if let Ok(relative) = profile.data_dir.strip_prefix(DATA_BASE_TOKEN) {
+        profile.data_dir = base.join(relative);
+      }
+    }
+  }
+  replace(&app, state.inner(), imported, &format!("import from {}", src), true)
+}
+
+/// Swap in `new` as the whole config after clamping it to the setters'
+/// bounds and validating it; nothing is written if it doesn't pass.
+pub fn replace(
+  app: &tauri::AppHandle,
+  backend: &BackendState,
+  mut new: Config,
+  origin: &str,
+  restart_now: bool,
+) -> Result<ReloadResult, String> {
+  config_check::normalize(&mut new);
+  config_check::check(app, backend, &new, origin)?;
+  replace_checked(app, backend, new, origin, restart_now)
+}
+
+/// Persist an already-validated `new` and apply live settings. When
+/// `restart_now` is set and a `restart_required` setting changed, the backend
+/// is restarted before this returns; otherwise that is left to the caller.
+/// `origin` is only for the log.
+pub fn replace_checked(
+  app: &tauri::AppHandle,
+  backend: &BackendState,
+  mut new: Config,
+  origin: &str,
+  restart_now: bool,
+) -> Result<ReloadResult, String> {
+  let previous = current(app);
+  if new.global_shortcut != previous.global_shortcut {
+    if let Err(err) = crate::shortcut::set_global_shortcut(app.clone(), new.global_shortcut.clone()) {
+      diag(&format!("Keeping shortcut '{}': {}", previous.global_shortcut, err));
+      new.global_shortcut = previous.global_shortcut.clone();
+    }
+  }
+
+  let (restart_required, applied): (Vec<String>, Vec<String>) = changed_fields(&previous, &new)
+    .into_iter()
+    .partition(|field| RESTART_REQUIRED.contains(&field.as_str()));
+  update(app, |config| *config = new.clone())?;
+  crate::window::apply_saved_settings(app);
+  if new.poll_interval_ms != previous.poll_interval_ms {
+    events::emit(app, "config://poll-interval-changed", new.poll_interval_ms);
+  }
+  if new.log_buffer_lines != previous.log_buffer_lines {
+    crate::log_buffer::resize(new.log_buffer_lines);
+  }
+  diag(&format!("Config replaced ({}): applied={:?} restart_required={:?}", origin, applied, restart_required));
+  if restart_now && !restart_required.is_empty() {
+    restart(app, backend, RestartReason::Config).map_err(pending_restart)?;
+  }
+  Ok(ReloadResult {
+    config: new,
+    applied,
+    restart_required,
+  })
+}
@@ -0,0 +1,199 @@
+//! Whole-config validation for the settings UI. `validate_config` reports
+//! every problem at once without touching any state; `apply_config` re-runs
+//! it and only persists (and restarts the backend) when nothing is wrong, so
+//! one bad field can't leave the settings half-applied.
+
+use serde::Serialize;
+use std::net::TcpListener;
+use std::ops::RangeInclusive;
+use tauri_plugin_global_shortcut::Shortcut;
+
+use crate::config::{self, Config, ReloadResult};
+use crate::{
+  backup, determine_project_root, diag, heartbeat, log_buffer, max_backend_workers, network, permissions, thermal, updates, venv, window,
+  BackendState,
+};
+
+#[derive(Clone, Serialize)]
+pub struct ValidationError {
+  /// Top-level config field, e.g. "port" or "profiles[1].data_dir".
+  field: String,
+  message: String,
+}
+
+fn error(errors: &mut Vec<ValidationError>, field: impl Into<String>, message: impl Into<String>) {
+  errors.push(ValidationError {
+    field: field.into(),
+    message: message.into(),
+  });
+}
+
+fn check_range<T: PartialOrd + std::fmt::Display>(errors: &mut Vec<ValidationError>, field: &str, value: T, range: RangeInclusive<T>) {
+  if !range.contains(&value) {
+    error(errors, field, format!("{} must be between {} and {}", value, range.start(), range.end()));
+  }
+}
+
+/// A port is fine if it's free or it's the one our backend is already on.
+fn check_port(errors: &mut Vec<ValidationError>, field: &str, port: u16, current_port: u16) {
+  if port == 0 {
+    error(errors, field, "Port must not be 0");
+  } else if port != current_port && TcpListener::bind((config::DEFAULT_HOST, port)).is_err() {
+    error(errors, field, format!("Port {} is already in use", port));
+  }
+}
+
+fn check_data_dir(errors: &mut Vec<ValidationError>, field: &str, dir: &std::path::Path) {
+  if !permissions::can_write(dir, true) {
+    error(errors, field, format!("{} is not writable and cannot be created", dir.display()));
+  }
+}
+
+fn validate(app: &tauri::AppHandle, backend: &BackendState, config: &Config) -> Vec<ValidationError> {
+  let mut errors = Vec::new();
+  let current_port = backend.port();
+
+  if config.version > config::CONFIG_VERSION {
+    error(&mut errors, "version", format!("Config version {} is newer than supported ({})", config.version, config::CONFIG_VERSION));
+  }
+  check_port(&mut errors, "port", config.port, current_port);
+  let host = config.host.trim();
+  if host.is_empty() {
+    error(&mut errors, "host", "Host must not be empty");
+  } else if !config::is_loopback(host) {
+    if let Err(err) = network::resolve(host, config.port) {
+      error(&mut errors, "host", err);
+    }
+  }
+  if let Some(dir) = &config.data_dir {
+    check_data_dir(&mut errors, "data_dir", dir);
+  }
+
+  for (index, profile) in config.profiles.iter().enumerate() {
+    if profile.name.trim().is_empty() {
+      error(&mut errors, format!("profiles[{}].name", index), "Profile name must not be empty");
+    } else if config.profiles[..index].iter().any(|other| other.name == profile.name) {
+      error(&mut errors, format!("profiles[{}].name", index), format!("Duplicate profile name {:?}", profile.name));
+    }
+    if config.active_profile.as_deref() == Some(profile.name.as_str()) {
+      check_port(&mut errors, &format!("profiles[{}].port", index), profile.port, current_port);
+      check_data_dir(&mut errors, &format!("profiles[{}].data_dir", index), &profile.data_dir);
+    } else if profile.port == 0 {
+      error(&mut errors, format!("profiles[{}].port", index), "Port must not be 0");
+    }
+  }
+  if let Some(name) = &config.active_profile {
+    if config.active_profile().is_none() {
+      error(&mut errors, "active_profile", format!("No profile named {:?}", name));
+    }
+  }
+
+  if let Some(python) = &config.python_path {
+    let validation = venv::validate_python(python, &determine_project_root(Some(app)));
+    if let Some(message) = validation.error() {
+      error(&mut errors, "python_path", message);
+    }
+  }
+
+  check_range(&mut errors, "poll_interval_ms", config.poll_interval_ms, config::MIN_POLL_INTERVAL_MS..=config::MAX_POLL_INTERVAL_MS);
+  check_range(&mut errors, "heartbeat_interval_ms", config.heartbeat_interval_ms, heartbeat::MIN_INTERVAL_MS..=heartbeat::MAX_INTERVAL_MS);
+  check_range(&mut errors, "update_batch_ms", config.update_batch_ms, 0..=updates::MAX_BATCH_MS);
+  check_range(&mut errors, "log_buffer_lines", config.log_buffer_lines, log_buffer::MIN_LINES..=log_buffer::MAX_LINES);
+  check_range(&mut errors, "max_log_line_bytes", config.max_log_line_bytes, log_buffer::MIN_LINE_BYTES..=log_buffer::MAX_LINE_BYTES);
+  check_range(&mut errors, "backup_interval_mins", config.backup_interval_mins, 0..=backup::MAX_INTERVAL_MINS);
+  check_range(&mut errors, "backup_keep", config.backup_keep, 1..=backup::MAX_KEEP);
+  check_range(&mut errors, "thermal_warning_celsius", config.thermal_warning_celsius, thermal::MIN_WARNING_CELSIUS..=thermal::MAX_WARNING_CELSIUS);
+  if config.command_timeout_secs == 0 {
+    error(&mut errors, "command_timeout_secs", "Command timeout must be at least 1 second");
+  }
+  if let Some(workers) = config.backend_workers {
+    let (max, _) = max_backend_workers();
+    check_range(&mut errors, "backend_workers", workers, 1..=max);
+  }
+  if let Some(color) = &config.accent_color {
+    if window::normalize_hex(color).is_none() {
+      error(&mut errors, "accent_color", format!("{:?} is not a hex color like #1e90ff", color));
+    }
+  }
+  let shortcut = config.global_shortcut.trim();
+  if !shortcut.is_empty() && shortcut.parse::<Shortcut>().is_err() {
+    error(&mut errors, "global_shortcut", format!("{:?} is not a valid shortcut", shortcut));
+  }
+  errors
+}
+
+/// Every problem with `config`, empty when it can be applied. Changes nothing.
+#[tauri::command(async)]
+pub fn validate_config(app: tauri::AppHandle, state: tauri::State<BackendState>, config: Config) -> Vec<ValidationError> {
+  validate(&app, state.inner(), &config)
+}
+
+/// Pull hand-edited values back into the ranges the individual setters clamp
+/// to, so a file edit can't set what the settings UI never could.
+pub fn normalize(config: &mut Config) {
+  config.poll_interval_ms = config.poll_interval_ms.clamp(config::MIN_POLL_INTERVAL_MS, config::MAX_POLL_INTERVAL_MS);
+  config.heartbeat_interval_ms = config.heartbeat_interval_ms.clamp(heartbeat::MIN_INTERVAL_MS, heartbeat::MAX_INTERVAL_MS);
+  config.update_batch_ms = config.update_batch_ms.min(updates::MAX_BATCH_MS);
+  config.log_buffer_lines = config.log_buffer_lines.clamp(log_buffer::MIN_LINES, log_buffer::MAX_LINES);
+  config.max_log_line_bytes = config.max_log_line_bytes.clamp(log_buffer::MIN_LINE_BYTES, log_buffer::MAX_LINE_BYTES);
+  config.backup_interval_mins = config.backup_interval_mins.min(backup::MAX_INTERVAL_MINS);
+  config.backup_keep = config.backup_keep.clamp(1, backup::MAX_KEEP);
+  config.thermal_warning_celsius = config.thermal_warning_celsius.clamp(thermal::MIN_WARNING_CELSIUS, thermal::MAX_WARNING_CELSIUS);
+  if let Some(color) = &config.accent_color {
+    if let Some(normalized) = window::normalize_hex(color) {
+      config.accent_color = Some(normalized);
+    }
+  }
+}
+
+/// `validate`, with every problem folded into one error for `origin`.
+pub fn check(app: &tauri::AppHandle, backend: &BackendState, config: &Config, origin: &str) -> Result<(), String> {
+  let errors = validate(app, backend, config);
+  if errors.is_empty() {
+    return Ok(());
+  }
+  let summary = errors
+    .iter()
+    .map(|e| format!("{}: {}", e.field, e.message))
+    .collect::<Vec<_>>()
+    .join("; ");
+  diag(&format!("{} rejected: {}", origin, summary));
+  Err(format!("Config not applied: {}", summary))
+}
+
+/// Validate `config` and, only if it passes, make it the whole config
+/// (restarting the backend when a `restart_required` setting changed).
+/// Out-of-range values are rejected here rather than clamped.
+#[tauri::command(async)]
+pub fn apply_config(app: tauri::AppHandle, state: tauri::State<BackendState>, config: Config) -> Result<ReloadResult, String> {
+  check(&app, state.inner(), &config, "apply_config")?;
+  config::replace_checked(&app, state.inner(), config, "apply_config", true)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn normalize_clamps_hand_edited_values_like_the_setters() {
+    let mut config = Config {
+      poll_interval_ms: 1,
+      heartbeat_interval_ms: u64::MAX,
+      backup_keep: 0,
+      accent_color: Some("ABC".to_string()),
+      ..Config::default()
+    };
+    normalize(&mut config);
+    assert_eq!(config.poll_interval_ms, config::MIN_POLL_INTERVAL_MS);
+    assert_eq!(config.heartbeat_interval_ms, heartbeat::MAX_INTERVAL_MS);
+    assert_eq!(config.backup_keep, 1);
+    assert_eq!(config.accent_color, window::normalize_hex("ABC"));
+  }
+
+  #[test]
+  fn normalize_leaves_valid_values_alone() {
+    let mut config = Config::default();
+    normalize(&mut config);
+    assert_eq!(config, Config::default());
+  }
+}
@@ -0,0 +1,94 @@
+//! Lock file in the data directory so two Talus instances (on different
+//! ports) can't run backends against the same database. The lock records the
+//! owning app's PID and process start time; a lock whose process is gone, or
+//! whose PID now belongs to a different process, is stale and taken over.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use crate::diag;
+
+pub const LOCK_FILE_NAME: &str = "talus.lock";
+
+/// Lock file we currently hold, released on shutdown or when the data dir changes.
+static HELD: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+#[derive(Serialize, Deserialize)]
+struct LockInfo {
+  pid: u32,
+  /// Process start time (unix seconds), so a reused PID isn't mistaken for the owner.
+  started: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct AlreadyInUse {
+  pub pid: u32,
+  pub lock_path: PathBuf,
+}
+
+fn start_time(pid: u32) -> Option<u64> {
+  let pid = Pid::from_u32(pid);
+  let mut system = System::new();
+  system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, ProcessRefreshKind::nothing());
+  system.process(pid).map(|process| process.start_time())
+}
+
+fn read_lock(path: &Path) -> Option<LockInfo> {
+  std::fs::read_to_string(path)
+    .ok()
+    .and_then(|text| serde_json::from_str(&text).ok())
+}
+
+/// Take the lock for `data_dir`, replacing a stale one. Fails with the owner
+/// when another live instance holds it.
+pub fn acquire(data_dir: &Path) -> Result<(), AlreadyInUse> {
+  let lock_path = data_dir.join(LOCK_FILE_NAME);
+  let own_pid = std::process::id();
+
+  if let Some(existing) = read_lock(&lock_path) {
+    if existing.pid != own_pid && start_time(existing.pid) == Some(existing.started) {
+      return Err(AlreadyInUse {
+        pid: existing.pid,
+        lock_path,
+      });
+    }
+    if existing.pid != own_pid {
+      diag(&format!("Clearing stale data lock from PID {} at {}", existing.pid, lock_path.display()));
+    }
+  }
+
+  let info = LockInfo {
+    pid: own_pid,
+    started: start_time(own_pid).unwrap_or_default(),
+  };
+  let _ = std::fs::create_dir_all(data_dir);
+  if let Ok(text) = serde_json::to_string(&info) {
+    if let Err(err) = std::fs::write(&lock_path, text) {
+      diag(&format!("Failed to write data lock {}: {}", lock_path.display(), err));
+    }
+  }
+
+  let previous = HELD.lock().ok().and_then(|mut held| held.replace(lock_path.clone()));
+  if let Some(previous) = previous.filter(|previous| *previous != lock_path) {
+    remove_if_ours(&previous);
+  }
+  Ok(())
+}
+
+fn remove_if_ours(path: &Path) {
+  if read_lock(path).is_some_and(|info| info.pid == std::process::id()) {
+    match std::fs::remove_file(path) {
+      Ok(()) => diag(&format!("Released data lock {}", path.display())),
+      Err(err) => diag(&format!("Failed to remove data lock {}: {}", path.display(), err)),
+    }
+  }
+}
+
+/// Drop the lock we hold, if any. Called on graceful shutdown.
+pub fn release() {
+  if let Some(path) = HELD.lock().ok().and_then(|mut held| held.take()) {
+    remove_if_ours(&path);
+  }
+}
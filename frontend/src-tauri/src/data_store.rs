@@ -0,0 +1,58 @@
+//! What the backend persists under the data dir (`TALUS_DATA_DIR`): its
+//! `settings.json` plus the user's templates, icons, indicators and markups.
+//! Projects are documents saved wherever the user chooses, so they are not
+//! part of it. The legacy-layout migration, backups and restores all work on
+//! this set of entries.
+
+use std::path::{Path, PathBuf};
+
+pub const SETTINGS_FILE: &str = "settings.json";
+/// Top-level entries of the store, relative to the data dir.
+pub const ENTRIES: [&str; 5] = [SETTINGS_FILE, "templates", "icons", "indicators", "markups"];
+
+/// The store's entries that exist under `root`.
+pub fn existing(root: &Path) -> Vec<PathBuf> {
+  ENTRIES.iter().map(|entry| root.join(entry)).filter(|path| path.exists()).collect()
+}
+
+/// Every file under `path` (or `path` itself for a file), depth first.
+pub fn files(path: &Path) -> Vec<PathBuf> {
+  if !path.is_dir() {
+    return if path.is_file() { vec![path.to_path_buf()] } else { Vec::new() };
+  }
+  let Ok(entries) = std::fs::read_dir(path) else {
+    return Vec::new();
+  };
+  let mut found: Vec<PathBuf> = entries.flatten().flat_map(|entry| files(&entry.path())).collect();
+  found.sort();
+  found
+}
+
+/// Copy a file or directory tree from `src` to `dst`; returns bytes copied.
+pub fn copy_entry(src: &Path, dst: &Path) -> std::io::Result<u64> {
+  let mut total = 0;
+  for file in files(src) {
+    let target = match file.strip_prefix(src) {
+      Ok(rel) if !rel.as_os_str().is_empty() => dst.join(rel),
+      _ => dst.to_path_buf(),
+    };
+    if let Some(parent) = target.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    total += std::fs::copy(&file, &target)?;
+  }
+  Ok(total)
+}
+
+/// Check that every JSON file of the store under `root` parses, so a
+/// truncated or foreign file is rejected before the backend reads it.
+pub fn validate(root: &Path) -> Result<(), String> {
+  for file in existing(root).iter().flat_map(|entry| files(entry)) {
+    if !file.extension().is_some_and(|ext| ext == "json") {
+      continue;
+    }
+    let text = std::fs::read_to_string(&file).map_err(|e| format!("Cannot read {}: {}", file.display(), e))?;
+    serde_json::from_str::<serde_json::Value>(&text).map_err(|e| format!("{} is not valid JSON: {}", file.display(), e))?;
+  }
+  Ok(())
+}
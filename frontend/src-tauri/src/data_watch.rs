@@ -0,0 +1,205 @@
+//! Optional watcher on the backend data dir. When a file of the backend's
+//! store (`settings.json` or anything under the template, icon, indicator and
+//! markup dirs; see `data_store`) changes and we didn't cause it, emit
+//! `data://external-change` so the UI can prompt for a reload instead of
+//! clobbering another process's edits. Backups and staging dirs under the
+//! data dir are not part of the store and are ignored.
+//!
+//! The filesystem can't tell us *who* wrote a file, so the frontend calls
+//! `note_local_write` around saves it routes through our backend; changes
+//! within LOCAL_WRITE_GRACE of that are treated as ours.
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::Manager;
+
+use crate::{config, data_store, diag, events};
+
+const LOCAL_WRITE_GRACE: Duration = Duration::from_secs(2);
+
+#[derive(Default)]
+pub struct DataWatchState {
+  watcher: Mutex<Option<RecommendedWatcher>>,
+  last_local_write: Arc<Mutex<Option<Instant>>>,
+}
+
+#[derive(Clone, Serialize)]
+struct ExternalChange {
+  path: String,
+  modified_ms: u64,
+}
+
+/// Whether `path` is `settings.json` or lies under another store entry of `data_dir`.
+fn is_store_file(data_dir: &Path, path: &Path) -> bool {
+  path
+    .strip_prefix(data_dir)
+    .ok()
+    .and_then(|rel| rel.components().next())
+    .and_then(|first| first.as_os_str().to_str())
+    .is_some_and(|first| data_store::ENTRIES.contains(&first))
+    && path.is_file()
+}
+
+fn mtime_ms(path: &Path) -> Option<u64> {
+  let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+  modified
+    .duration_since(SystemTime::UNIX_EPOCH)
+    .ok()
+    .map(|d| d.as_millis() as u64)
+}
+
+/// Turns watcher events into the external changes worth reporting.
+struct ChangeFilter {
+  data_dir: PathBuf,
+  last_local_write: Arc<Mutex<Option<Instant>>>,
+  /// Last mtime reported per file, since one write can raise several events.
+  seen: HashMap<PathBuf, u64>,
+}
+
+impl ChangeFilter {
+  fn new(data_dir: &Path, last_local_write: Arc<Mutex<Option<Instant>>>) -> Self {
+    ChangeFilter {
+      data_dir: data_dir.to_path_buf(),
+      last_local_write,
+      seen: HashMap::new(),
+    }
+  }
+
+  fn external(&mut self, event: &notify::Event) -> Vec<ExternalChange> {
+    if !matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_)) {
+      return Vec::new();
+    }
+    let ours = self
+      .last_local_write
+      .lock()
+      .ok()
+      .and_then(|last| *last)
+      .is_some_and(|at| at.elapsed() < LOCAL_WRITE_GRACE);
+    let mut changes = Vec::new();
+    for path in event.paths.iter().filter(|path| is_store_file(&self.data_dir, path)) {
+      let Some(modified_ms) = mtime_ms(path) else {
+        continue;
+      };
+      if self.seen.insert(path.clone(), modified_ms) == Some(modified_ms) || ours {
+        continue;
+      }
+      changes.push(ExternalChange {
+        path: path.display().to_string(),
+        modified_ms,
+      });
+    }
+    changes
+  }
+}
+
+/// (Re)point the watcher at `data_dir`, or stop it when watching is disabled.
+pub fn watch(app: &tauri::AppHandle, data_dir: &Path) {
+  let state = app.state::<DataWatchState>();
+  let Ok(mut slot) = state.watcher.lock() else {
+    return;
+  };
+  *slot = None;
+  if !config::current(app).watch_data_dir {
+    return;
+  }
+
+  let emitter = app.clone();
+  let mut changes = ChangeFilter::new(data_dir, state.last_local_write.clone());
+  let handler = move |result: notify::Result<notify::Event>| {
+    let Ok(event) = result else {
+      return;
+    };
+    for change in changes.external(&event) {
+      diag(&format!("External change detected on {}", change.path));
+      events::emit(&emitter, "data://external-change", change);
+    }
+  };
+
+  match notify::recommended_watcher(handler) {
+    Ok(mut watcher) => match watcher.watch(data_dir, RecursiveMode::Recursive) {
+      Ok(()) => {
+        diag(&format!("Watching data dir {} for external changes", data_dir.display()));
+        *slot = Some(watcher);
+      }
+      Err(err) => diag(&format!("Failed to watch {}: {}", data_dir.display(), err)),
+    },
+    Err(err) => diag(&format!("Failed to create data dir watcher: {}", err)),
+  }
+}
+
+/// Called by the frontend around saves so our own writes aren't reported as external.
+#[tauri::command]
+pub fn note_local_write(state: tauri::State<DataWatchState>) {
+  if let Ok(mut last) = state.last_local_write.lock() {
+    *last = Some(Instant::now());
+  }
+}
+
+#[tauri::command]
+pub fn set_data_watch(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+  config::update(&app, |config| config.watch_data_dir = enabled)?;
+  watch(&app, &config::data_dir(&app));
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::sync::mpsc;
+
+  fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("talus-watch-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    // Canonical, so it matches the paths the watcher reports (e.g. /private/var on macOS)
+    dir.canonicalize().unwrap()
+  }
+
+  /// Watch `dir` like `watch` does, forwarding reported changes.
+  fn watch_changes(dir: &Path) -> (RecommendedWatcher, mpsc::Receiver<ExternalChange>) {
+    let (sender, changes) = mpsc::channel();
+    let mut filter = ChangeFilter::new(dir, Arc::new(Mutex::new(None)));
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+      if let Ok(event) = result {
+        for change in filter.external(&event) {
+          let _ = sender.send(change);
+        }
+      }
+    })
+    .unwrap();
+    watcher.watch(dir, RecursiveMode::Recursive).unwrap();
+    (watcher, changes)
+  }
+
+  #[test]
+  fn writing_settings_json_reports_an_external_change() {
+    let dir = scratch("settings");
+    let (_watcher, changes) = watch_changes(&dir);
+
+    std::fs::write(dir.join(data_store::SETTINGS_FILE), r#"{"theme": "dark"}"#).unwrap();
+
+    let change = changes.recv_timeout(Duration::from_secs(5)).expect("no external change reported");
+    assert_eq!(PathBuf::from(change.path), dir.join(data_store::SETTINGS_FILE));
+    let _ = std::fs::remove_dir_all(&dir);
+  }
+
+  #[test]
+  fn only_store_files_count() {
+    let dir = scratch("filter");
+    for rel in ["settings.json", "templates/a.yaml", "backups/talus-1/settings.json", "notes.txt"] {
+      let path = dir.join(rel);
+      std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+      std::fs::write(&path, "{}").unwrap();
+    }
+
+    assert!(is_store_file(&dir, &dir.join("settings.json")));
+    assert!(is_store_file(&dir, &dir.join("templates/a.yaml")));
+    assert!(!is_store_file(&dir, &dir.join("backups/talus-1/settings.json")));
+    assert!(!is_store_file(&dir, &dir.join("notes.txt")));
+    let _ = std::fs::remove_dir_all(&dir);
+  }
+}
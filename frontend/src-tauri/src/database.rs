@@ -0,0 +1,167 @@
+//! Restoring the backend's data store (see `data_store`) from a backup: a
+//! `talus-<ts>` snapshot from `list_backups`, or any directory laid out the
+//! same way.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use crate::supervisor::RestartReason;
+use crate::{backup, config, data_store, diag, restart_around, BackendState};
+
+const STAGING_DIR: &str = ".importing";
+
+#[derive(Serialize)]
+pub struct ImportResult {
+  imported_to: PathBuf,
+  /// Snapshot of the data that was replaced, if there was any.
+  backup: Option<PathBuf>,
+}
+
+/// Reject anything that doesn't look like the backend's data store before
+/// the backend is touched: it must hold at least one store entry, and every
+/// JSON file in it must parse.
+fn check_source(src: &Path) -> Result<(), String> {
+  if !src.is_dir() {
+    return Err(format!("{} is not a backup folder", src.display()));
+  }
+  if data_store::existing(src).is_empty() {
+    return Err(format!(
+      "{} holds no Talus data (expected any of {})",
+      src.display(),
+      data_store::ENTRIES.join(", ")
+    ));
+  }
+  data_store::validate(src)
+}
+
+/// Every store file under `root` with its size, relative to `root`.
+fn listing(root: &Path) -> Vec<(PathBuf, u64)> {
+  data_store::existing(root)
+    .iter()
+    .flat_map(|entry| data_store::files(entry))
+    .filter_map(|file| {
+      let size = std::fs::metadata(&file).ok()?.len();
+      Some((file.strip_prefix(root).ok()?.to_path_buf(), size))
+    })
+    .collect()
+}
+
+/// Copy `src` into `staging` and check the copy is complete and valid. This
+/// happens before anything else, so the import no longer depends on `src`:
+/// the pre-import snapshot may rotate it away when `src` is itself the
+/// oldest backup.
+fn stage(src: &Path, staging: &Path) -> Result<(), String> {
+  let _ = std::fs::remove_dir_all(staging);
+  for entry in data_store::existing(src) {
+    let rel = entry.strip_prefix(src).unwrap_or(&entry);
+    data_store::copy_entry(&entry, &staging.join(rel)).map_err(|e| format!("Failed to copy {}: {}", entry.display(), e))?;
+  }
+  if listing(staging) != listing(src) {
+    return Err(format!("The copy of {} is incomplete", src.display()));
+  }
+  data_store::validate(staging)
+}
+
+/// Replace each store entry in `data_dir` with the staged one. Entries the
+/// backup doesn't have are removed, so the result matches it exactly.
+fn swap_in(staging: &Path, data_dir: &Path) -> Result<(), String> {
+  for entry in data_store::ENTRIES {
+    let current = data_dir.join(entry);
+    let removed = if current.is_dir() {
+      std::fs::remove_dir_all(&current)
+    } else if current.exists() {
+      std::fs::remove_file(&current)
+    } else {
+      Ok(())
+    };
+    removed.map_err(|e| format!("Failed to replace {}: {}", current.display(), e))?;
+    let staged = staging.join(entry);
+    if staged.exists() {
+      std::fs::rename(&staged, &current).map_err(|e| format!("Failed to move {} into place: {}", staged.display(), e))?;
+    }
+  }
+  Ok(())
+}
+
+/// Replace the backend's data with a backup. The backup is copied aside and
+/// checked before the backend is touched; then, under the restart guard, the
+/// backend is stopped, the current data snapshotted, the copy swapped in
+/// and the backend started again.
+#[tauri::command(async)]
+pub fn import_database(app: tauri::AppHandle, state: tauri::State<BackendState>, src: String) -> Result<ImportResult, String> {
+  let backend = state.inner();
+  let mode = backend.mode.lock().ok().and_then(|mode| *mode);
+  if backend.adopted.load(Ordering::SeqCst) || mode == Some("remote") {
+    return Err("Can't import data into a backend this app doesn't manage".to_string());
+  }
+
+  let src = PathBuf::from(src);
+  check_source(&src)?;
+
+  let data_dir = config::data_dir(&app);
+  let staging = data_dir.join(STAGING_DIR);
+  diag(&format!("Importing data {} -> {}", src.display(), data_dir.display()));
+  let result = stage(&src, &staging).and_then(|_| {
+    restart_around(&app, backend, RestartReason::Import, || {
+      let backup = if data_store::existing(&data_dir).is_empty() {
+        None
+      } else {
+        let snapshot = backup::snapshot(&app, config::current(&app).backup_keep)
+          .map_err(|e| format!("Backing up the current data failed; nothing was imported: {}", e))?;
+        Some(snapshot)
+      };
+      swap_in(&staging, &data_dir)?;
+      Ok(backup)
+    })
+  });
+  let _ = std::fs::remove_dir_all(&staging);
+
+  let backup = result?;
+  diag(&format!("Imported data; previous copy at {:?}", backup));
+  Ok(ImportResult {
+    imported_to: data_dir,
+    backup,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("talus-import-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  #[test]
+  fn staged_copy_survives_the_source_being_rotated_away() {
+    let root = scratch("rotated");
+    let src = root.join("backups").join("talus-1");
+    std::fs::create_dir_all(src.join("templates")).unwrap();
+    std::fs::write(src.join(data_store::SETTINGS_FILE), "{}").unwrap();
+    std::fs::write(src.join("templates").join("a.json"), r#"{"name": "a"}"#).unwrap();
+    let staging = root.join(STAGING_DIR);
+
+    stage(&src, &staging).unwrap();
+    std::fs::remove_dir_all(&src).unwrap();
+    swap_in(&staging, &root).unwrap();
+
+    assert_eq!(std::fs::read_to_string(root.join("templates").join("a.json")).unwrap(), r#"{"name": "a"}"#);
+    assert!(root.join(data_store::SETTINGS_FILE).is_file());
+    let _ = std::fs::remove_dir_all(&root);
+  }
+
+  #[test]
+  fn invalid_json_is_rejected_while_staging() {
+    let root = scratch("invalid");
+    let src = root.join("backup");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(src.join(data_store::SETTINGS_FILE), "{ truncated").unwrap();
+
+    assert!(stage(&src, &root.join(STAGING_DIR)).is_err());
+    let _ = std::fs::remove_dir_all(&root);
+  }
+}
@@ -0,0 +1,94 @@
+//! `talus://` deep links, e.g. `talus://open/<id>`. Links arriving while
+//! another instance runs are forwarded to it by the single-instance plugin.
+//! Each link focuses the main window and is emitted as `deeplink://open`; a
+//! link that launched the app is held until `backend://ready`, since the
+//! frontend can't open anything before then.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{Listener, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::{diag, events, main_window, BackendState};
+
+const SCHEME: &str = "talus";
+
+#[derive(Default)]
+pub struct DeepLinkState {
+  /// Links received before the backend was ready.
+  pending: Mutex<Vec<DeepLink>>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DeepLink {
+  url: String,
+  /// First path segment after the scheme, e.g. "open".
+  action: String,
+  /// The rest of the path, e.g. the tally id.
+  target: String,
+}
+
+/// `talus://open/abc` parses with "open" as the host and "/abc" as the path.
+fn parse(url: &Url) -> Option<DeepLink> {
+  if url.scheme() != SCHEME {
+    return None;
+  }
+  let action = url.host_str()?.to_string();
+  let target = url.path().trim_matches('/').to_string();
+  Some(DeepLink {
+    url: url.to_string(),
+    action,
+    target,
+  })
+}
+
+fn handle(app: &tauri::AppHandle, urls: Vec<Url>) {
+  for url in urls {
+    let Some(link) = parse(&url) else {
+      diag(&format!("Ignoring unsupported deep link {}", url));
+      continue;
+    };
+    diag(&format!("Deep link: {}", link.url));
+    if let Some(window) = main_window(app) {
+      let _ = window.unminimize();
+      let _ = window.show();
+      let _ = window.set_focus();
+    }
+    if app.state::<BackendState>().is_reachable() {
+      events::emit(app, "deeplink://open", link);
+    } else if let Ok(mut pending) = app.state::<DeepLinkState>().pending.lock() {
+      pending.push(link);
+    }
+  }
+}
+
+/// Register the scheme where that happens at runtime, pick up a link that
+/// launched the app, and start listening for more.
+pub fn init(app: &tauri::AppHandle) {
+  #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+  if let Err(err) = app.deep_link().register_all() {
+    diag(&format!("Failed to register {}:// scheme: {}", SCHEME, err));
+  }
+
+  match app.deep_link().get_current() {
+    Ok(Some(urls)) => handle(app, urls),
+    Ok(None) => {}
+    Err(err) => diag(&format!("Failed to read launch deep link: {}", err)),
+  }
+
+  let handle_app = app.clone();
+  app.deep_link().on_open_url(move |event| handle(&handle_app, event.urls()));
+
+  let flush_app = app.clone();
+  app.listen("backend://ready", move |_| {
+    let pending = flush_app
+      .state::<DeepLinkState>()
+      .pending
+      .lock()
+      .map(|mut pending| std::mem::take(&mut *pending))
+      .unwrap_or_default();
+    for link in pending {
+      events::emit(&flush_app, "deeplink://open", link);
+    }
+  });
+}
@@ -0,0 +1,306 @@
+//! Field diagnostics: checks that help explain network and environment problems
+//! on users' machines.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::backend_api::{self, CommandError};
+use crate::{
+  build_info, config, determine_project_root, diag, diagnostic_log_path, ensure_writable_dir, events, find_packaged_backend,
+  http, unix_now, venv_python_path, BackendState,
+};
+
+const LOW_DISK_WARN_BYTES: u64 = 1024 * 1024 * 1024;
+const LOW_DISK_FAIL_BYTES: u64 = 100 * 1024 * 1024;
+/// More concurrent backend connections than this suggests runaway polling or a leak.
+const HIGH_CONNECTION_COUNT: u32 = 20;
+
+#[derive(Serialize)]
+pub struct ConnectivityResult {
+  ok: bool,
+  status: Option<u16>,
+  latency_ms: Option<u64>,
+  error: Option<String>,
+}
+
+/// Probe an arbitrary URL (HEAD, falling back to GET when HEAD isn't allowed)
+/// to diagnose firewall/proxy issues. Any HTTP status below 500 counts as reachable.
+#[tauri::command(async)]
+pub fn test_connectivity(url: String, timeout_ms: u64) -> ConnectivityResult {
+  let agent: ureq::Agent = ureq::Agent::config_builder()
+    .timeout_global(Some(Duration::from_millis(timeout_ms)))
+    .http_status_as_error(false)
+    .build()
+    .into();
+
+  let started = Instant::now();
+  let result = agent.head(&url).call().and_then(|response| {
+    if response.status().as_u16() == 405 {
+      agent.get(&url).call()
+    } else {
+      Ok(response)
+    }
+  });
+  let latency_ms = started.elapsed().as_millis() as u64;
+
+  let outcome = match result {
+    Ok(response) => {
+      let status = response.status().as_u16();
+      ConnectivityResult {
+        ok: status < 500,
+        status: Some(status),
+        latency_ms: Some(latency_ms),
+        error: None,
+      }
+    }
+    Err(err) => ConnectivityResult {
+      ok: false,
+      status: None,
+      latency_ms: None,
+      error: Some(err.to_string()),
+    },
+  };
+  diag(&format!(
+    "test_connectivity {} -> ok={} status={:?} latency={:?} error={:?}",
+    url, outcome.ok, outcome.status, outcome.latency_ms, outcome.error
+  ));
+  outcome
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+  Pass,
+  Warn,
+  Fail,
+}
+
+#[derive(Serialize)]
+pub struct SelfTestCheck {
+  name: String,
+  status: CheckStatus,
+  message: String,
+}
+
+#[derive(Serialize)]
+pub struct SelfTestReport {
+  /// Worst status across all checks.
+  overall: CheckStatus,
+  checks: Vec<SelfTestCheck>,
+  /// Which build ran the test, so a pasted report identifies it exactly.
+  build: build_info::BuildInfo,
+}
+
+fn check(name: &str, status: CheckStatus, message: impl Into<String>) -> SelfTestCheck {
+  SelfTestCheck {
+    name: name.to_string(),
+    status,
+    message: message.into(),
+  }
+}
+
+/// Free space on the disk holding `path` (longest matching mount point).
+fn available_space(path: &Path) -> Option<u64> {
+  let disks = sysinfo::Disks::new_with_refreshed_list();
+  disks
+    .list()
+    .iter()
+    .filter(|disk| path.starts_with(disk.mount_point()))
+    .max_by_key(|disk| disk.mount_point().as_os_str().len())
+    .map(|disk| disk.available_space())
+}
+
+/// Ask the backend for its internal checks (DB writable, tally round-trip, version).
+fn backend_checks(host: &str, port: u16) -> Vec<SelfTestCheck> {
+  let response = match http::get(host, port, "/api/v1/self-test", Duration::from_secs(15)) {
+    Ok(response) => response,
+    Err(err) => return vec![check("backend:self-test", CheckStatus::Fail, err)],
+  };
+  if response.status == 404 {
+    return vec![check("backend:self-test", CheckStatus::Warn, "This backend version has no self-test endpoint")];
+  }
+  let checks = response
+    .json()
+    .ok()
+    .and_then(|json| json.get("checks").and_then(|c| c.as_array()).cloned());
+  let Some(checks) = checks else {
+    return vec![check(
+      "backend:self-test",
+      CheckStatus::Fail,
+      format!("Unexpected self-test response (HTTP {})", response.status),
+    )];
+  };
+  checks
+    .iter()
+    .map(|item| {
+      let field = |key: &str| item.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+      let status = match field("status") {
+        "pass" | "ok" => CheckStatus::Pass,
+        "fail" | "error" => CheckStatus::Fail,
+        _ => CheckStatus::Warn,
+      };
+      check(&format!("backend:{}", field("name")), status, field("message"))
+    })
+    .collect()
+}
+
+fn connection_count(host: &str, port: u16) -> Result<u32, String> {
+  let response = http::get(host, port, "/api/v1/metrics", Duration::from_secs(5))?;
+  if response.status == 404 {
+    return Err("This backend version has no /api/v1/metrics endpoint".to_string());
+  }
+  if !response.is_success() {
+    return Err(format!("Backend metrics failed with HTTP {}", response.status));
+  }
+  response
+    .json()?
+    .get("active_connections")
+    .and_then(|count| count.as_u64())
+    .map(|count| count as u32)
+    .ok_or_else(|| "Backend metrics response has no active_connections".to_string())
+}
+
+/// Open HTTP requests plus Socket.IO clients on the backend, to spot the
+/// frontend leaking connections or polling too hard.
+#[tauri::command(async)]
+pub fn get_backend_connection_count(app: tauri::AppHandle, state: tauri::State<BackendState>) -> Result<u32, CommandError> {
+  let (host, port) = (state.host(), state.port());
+  backend_api::with_timeout(&app, "get_backend_connection_count", move || connection_count(&host, port))
+}
+
+/// Pre-flight checklist combining the backend's own diagnostics with
+/// Rust-side checks of the port, data dir, disk space and runtime.
+#[tauri::command(async)]
+pub fn run_self_test(app: tauri::AppHandle, state: tauri::State<BackendState>) -> SelfTestReport {
+  let (host, port) = (state.host(), state.port());
+  let reachable = state.is_reachable();
+  let mut checks = vec![if reachable {
+    check("port", CheckStatus::Pass, format!("Backend answering on :{}", port))
+  } else {
+    check("port", CheckStatus::Fail, format!("Nothing answering on :{}", port))
+  }];
+
+  let data_dir = config::data_dir(&app);
+  checks.push(match ensure_writable_dir(&data_dir) {
+    Ok(()) => check("data_dir", CheckStatus::Pass, format!("{} is writable", data_dir.display())),
+    Err(err) => check("data_dir", CheckStatus::Fail, err),
+  });
+
+  checks.push(match available_space(&data_dir) {
+    Some(bytes) if bytes < LOW_DISK_FAIL_BYTES => check("disk_space", CheckStatus::Fail, format!("Only {} MB free", bytes / 1_048_576)),
+    Some(bytes) if bytes < LOW_DISK_WARN_BYTES => check("disk_space", CheckStatus::Warn, format!("{} MB free", bytes / 1_048_576)),
+    Some(bytes) => check("disk_space", CheckStatus::Pass, format!("{} MB free", bytes / 1_048_576)),
+    None => check("disk_space", CheckStatus::Warn, "Could not determine free disk space"),
+  });
+
+  let project_root = determine_project_root(Some(&app));
+  let venv_python = venv_python_path(&project_root);
+  checks.push(if let Some(binary) = find_packaged_backend(Some(&app), &project_root) {
+    check("runtime", CheckStatus::Pass, format!("Packaged backend at {}", binary.display()))
+  } else if venv_python.exists() {
+    check("runtime", CheckStatus::Pass, format!("Virtualenv python at {}", venv_python.display()))
+  } else {
+    check("runtime", CheckStatus::Warn, "No packaged backend or virtualenv; relying on system python")
+  });
+
+  if reachable {
+    let count_host = host.clone();
+    checks.push(match backend_api::with_timeout(&app, "connection_count", move || connection_count(&count_host, port)) {
+      Ok(count) if count > HIGH_CONNECTION_COUNT => check(
+        "connections",
+        CheckStatus::Warn,
+        format!("{} active backend connections; the UI may be polling too hard or leaking requests", count),
+      ),
+      Ok(count) => check("connections", CheckStatus::Pass, format!("{} active backend connections", count)),
+      Err(err) => check("connections", CheckStatus::Warn, err.to_string()),
+    });
+    checks.extend(
+      backend_api::with_timeout(&app, "backend_self_test", move || Ok(backend_checks(&host, port)))
+        .unwrap_or_else(|err| vec![check("backend:self-test", CheckStatus::Fail, err.to_string())]),
+    );
+  }
+
+  let overall = checks.iter().map(|c| c.status).max().unwrap_or(CheckStatus::Pass);
+  diag(&format!("Self-test finished: overall={:?}", overall));
+  SelfTestReport { overall, checks, build: build_info::current() }
+}
+
+/// Archive the diagnostic log as `backend-launch-<ts>.log` so a repro starts
+/// with a clean file. `diag` opens the log per line, so the next message
+/// simply creates a fresh one.
+#[tauri::command]
+pub fn rotate_logs() -> Result<PathBuf, String> {
+  let log_path = diagnostic_log_path().ok_or_else(|| "Could not resolve the diagnostic log location".to_string())?;
+  if !log_path.exists() {
+    return Err(format!("No log to rotate at {}", log_path.display()));
+  }
+  let archived = log_path.with_file_name(format!("backend-launch-{}.log", unix_now()));
+  std::fs::rename(&log_path, &archived)
+    .map_err(|e| format!("Failed to archive {} to {}: {}", log_path.display(), archived.display(), e))?;
+  diag(&format!("Log rotated; previous log archived at {}", archived.display()));
+  Ok(archived)
+}
+
+const MAX_BENCHMARK_SAMPLES: u32 = 1000;
+
+#[derive(Serialize)]
+pub struct LatencyStats {
+  samples: u32,
+  min_ms: f64,
+  p50_ms: f64,
+  p95_ms: f64,
+  max_ms: f64,
+}
+
+#[derive(Clone, Serialize)]
+struct BenchmarkProgress {
+  done: u32,
+  total: u32,
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+  let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+  sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Time `samples` round trips of a throwaway write+delete through the
+/// backend's save path, emitting `benchmark://progress` after each one.
+#[tauri::command(async)]
+pub fn benchmark_tally_write(app: tauri::AppHandle, state: tauri::State<BackendState>, samples: u32) -> Result<LatencyStats, CommandError> {
+  if !(1..=MAX_BENCHMARK_SAMPLES).contains(&samples) {
+    return Err(format!("Samples must be between 1 and {}", MAX_BENCHMARK_SAMPLES).into());
+  }
+  let (host, port) = (state.host(), state.port());
+  let mut timings = Vec::with_capacity(samples as usize);
+  for done in 1..=samples {
+    let started = Instant::now();
+    let host = host.clone();
+    let response = backend_api::with_timeout(&app, "benchmark_tally_write", move || {
+      http::post_json(&host, port, "/api/v1/bench/write", &serde_json::json!({}), Duration::from_secs(10))
+    })?;
+    if response.status == 404 {
+      return Err("This backend version has no /api/v1/bench/write endpoint".to_string().into());
+    }
+    if !response.is_success() {
+      return Err(format!("Benchmark write failed with HTTP {}: {}", response.status, response.body.trim()).into());
+    }
+    timings.push(started.elapsed().as_secs_f64() * 1000.0);
+    events::emit(&app, "benchmark://progress", BenchmarkProgress { done, total: samples });
+  }
+
+  timings.sort_by(|a, b| a.total_cmp(b));
+  let stats = LatencyStats {
+    samples,
+    min_ms: timings[0],
+    p50_ms: percentile(&timings, 50.0),
+    p95_ms: percentile(&timings, 95.0),
+    max_ms: timings[timings.len() - 1],
+  };
+  diag(&format!(
+    "Write benchmark ({} samples): min {:.1}ms p50 {:.1}ms p95 {:.1}ms max {:.1}ms",
+    samples, stats.min_ms, stats.p50_ms, stats.p95_ms, stats.max_ms
+  ));
+  Ok(stats)
+}
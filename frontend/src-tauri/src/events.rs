@@ -0,0 +1,49 @@
+//! Backend lifecycle events. Every payload carries a monotonically increasing
+//! `seq` and a millisecond timestamp so the frontend can spot missed or
+//! reordered events and resync via `get_backend_info`. The last payload of each
+//! event is kept so a UI that subscribes late can catch up via `get_last_events`.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::Ordering;
+use tauri::Emitter;
+
+use crate::{diag, ipc_trace, unix_now_ms, BackendState};
+
+#[derive(Clone, Serialize)]
+struct Sequenced<T: Serialize> {
+  seq: u64,
+  timestamp_ms: u64,
+  #[serde(flatten)]
+  payload: T,
+}
+
+pub fn emit_lifecycle<T: Serialize + Clone>(app: &tauri::AppHandle, backend: &BackendState, event: &str, payload: T) {
+  let seq = backend.event_seq.fetch_add(1, Ordering::SeqCst) + 1;
+  let sequenced = Sequenced {
+    seq,
+    timestamp_ms: unix_now_ms(),
+    payload,
+  };
+  if let (Ok(value), Ok(mut last)) = (serde_json::to_value(&sequenced), backend.last_events.lock()) {
+    last.insert(event.to_string(), value);
+  }
+  emit(app, event, sequenced);
+}
+
+/// Emit `event`, logging it when IPC tracing is on. All events go through
+/// here, so a failed emit (serialization error, webview gone) is always
+/// logged rather than leaving "the frontend never got it" to guesswork.
+pub fn emit<R: tauri::Runtime, T: Serialize + Clone>(emitter: &impl Emitter<R>, event: &str, payload: T) {
+  ipc_trace::event(event, &payload);
+  if let Err(err) = emitter.emit(event, payload) {
+    diag(&format!("Failed to emit {}: {}", event, err));
+  }
+}
+
+/// Last payload of every lifecycle event emitted so far, keyed by event name.
+/// Compare `seq` values to tell which of e.g. ready/start-failed is current.
+#[tauri::command]
+pub fn get_last_events(state: tauri::State<BackendState>) -> BTreeMap<String, serde_json::Value> {
+  state.last_events.lock().map(|last| last.clone()).unwrap_or_default()
+}
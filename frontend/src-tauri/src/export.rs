@@ -0,0 +1,299 @@
+//! Streams backend exports straight to disk so large tallies don't have to be
+//! buffered in the webview, reporting progress along the way.
+
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use crate::backend_api::{self, CommandError};
+use crate::{diag, events, http, unix_now_ms, BackendState};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// One export at a time; `cancel` is checked between chunks.
+#[derive(Default)]
+pub struct ExportState {
+  running: AtomicBool,
+  cancel: AtomicBool,
+}
+
+#[derive(Clone, Serialize)]
+struct ExportProgress {
+  bytes_written: u64,
+  /// Content-Length from the backend, when it sent one.
+  total_estimate: Option<u64>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ExportComplete {
+  path: PathBuf,
+  size: u64,
+}
+
+/// Clears `running` however the export ends.
+struct RunningGuard<'a>(&'a AtomicBool);
+
+impl Drop for RunningGuard<'_> {
+  fn drop(&mut self) {
+    self.0.store(false, Ordering::SeqCst);
+  }
+}
+
+/// Render `options` (the backend's export request body: template_id, root_node_id, ...)
+/// for `session_id` and write the result to `destination`. Data lands in a `.part`
+/// file that is renamed into place only once the download finishes, so a cancelled
+/// or failed export never leaves a truncated file behind. Waiting for the response,
+/// and for each chunk of it, is bounded by `command_timeout_secs`; the download as
+/// a whole is not, so a large export may take as long as it keeps making progress.
+#[tauri::command(async)]
+pub fn export_tally(
+  app: tauri::AppHandle,
+  state: tauri::State<BackendState>,
+  export: tauri::State<ExportState>,
+  session_id: String,
+  options: serde_json::Value,
+  destination: PathBuf,
+) -> Result<ExportComplete, CommandError> {
+  if export.running.swap(true, Ordering::SeqCst) {
+    return Err("An export is already in progress".to_string().into());
+  }
+  let _running = RunningGuard(&export.running);
+  export.cancel.store(false, Ordering::SeqCst);
+
+  let url = format!("http://{}:{}/api/export/{}/download", state.host(), state.port(), session_id);
+  let timeout = backend_api::command_timeout(&app);
+  let agent: ureq::Agent = ureq::Agent::config_builder()
+    .timeout_connect(Some(Duration::from_secs(5)))
+    .timeout_recv_response(Some(timeout))
+    .http_status_as_error(false)
+    .build()
+    .into();
+  let response = backend_api::with_timeout_of("export_tally", timeout, move || {
+    agent
+      .post(&url)
+      .header("Content-Type", "application/json")
+      .send(options.to_string())
+      .map_err(|e| format!("Export request failed: {}", e))
+  })?;
+  let status = response.status().as_u16();
+  let body = response.into_body();
+  let total_estimate = body.content_length();
+  let mut reader = StallTimeout::new(body.into_reader(), timeout);
+  if !(200..300).contains(&status) {
+    let mut detail = String::new();
+    let _ = reader.read_to_string(&mut detail);
+    return Err(format!("Backend export failed with HTTP {}: {}", status, detail).into());
+  }
+
+  let partial = destination.with_extension(match destination.extension() {
+    Some(ext) => format!("{}.part", ext.to_string_lossy()),
+    None => "part".to_string(),
+  });
+  let result = stream_to_file(&app, &export, reader, &partial, total_estimate).and_then(|size| {
+    std::fs::rename(&partial, &destination)
+      .map(|_| size)
+      .map_err(|e| format!("Failed to move export into {}: {}", destination.display(), e).into())
+  });
+  let size = match result {
+    Ok(size) => size,
+    Err(err) => {
+      let _ = std::fs::remove_file(&partial);
+      diag(&format!("Export to {} did not complete: {}", destination.display(), err));
+      return Err(err);
+    }
+  };
+
+  diag(&format!("Exported {} bytes to {}", size, destination.display()));
+  let complete = ExportComplete { path: destination, size };
+  events::emit(&app, "export://complete", complete.clone());
+  Ok(complete)
+}
+
+/// Write a JSON copy of `session_id`'s current tallies to `destination`
+/// for sharing, detached from the live project so nothing can be edited
+/// through it. Stamps the time and app version (the backend ships with each
+/// release, so this also identifies the backend build) for provenance.
+#[tauri::command(async)]
+pub fn export_snapshot(
+  app: tauri::AppHandle,
+  state: tauri::State<BackendState>,
+  session_id: String,
+  destination: PathBuf,
+) -> Result<ExportComplete, CommandError> {
+  let path = format!("/api/export/{}/snapshot", session_id);
+  let (host, port) = (state.host(), state.port());
+  let response = backend_api::with_timeout(&app, "export_snapshot", move || http::get(&host, port, &path, SNAPSHOT_TIMEOUT))?;
+  match response.status {
+    404 if !response.body.contains("SESSION_NOT_FOUND") => {
+      return Err("This backend version does not support snapshots".to_string().into())
+    }
+    _ if response.is_success() => {}
+    status => return Err(format!("Backend snapshot failed with HTTP {}: {}", status, response.body.trim()).into()),
+  }
+  let snapshot = serde_json::json!({
+    "read_only": true,
+    "exported_at_ms": unix_now_ms(),
+    "app_version": app.package_info().version.to_string(),
+    "backend_mode": state.mode.lock().ok().and_then(|mode| *mode),
+    "tallies": response.json()?,
+  });
+  let text = serde_json::to_string_pretty(&snapshot).map_err(|e| e.to_string())?;
+
+  let partial = destination.with_extension("part");
+  std::fs::write(&partial, &text)
+    .and_then(|_| std::fs::rename(&partial, &destination))
+    .map_err(|e| {
+      let _ = std::fs::remove_file(&partial);
+      format!("Failed to write snapshot to {}: {}", destination.display(), e)
+    })?;
+  diag(&format!("Snapshot of session {} written to {}", session_id, destination.display()));
+  Ok(ExportComplete {
+    path: destination,
+    size: text.len() as u64,
+  })
+}
+
+/// Reads `inner` on a thread of its own so a backend that stops sending
+/// mid-body fails the next read with `TimedOut` after `timeout`, instead of
+/// blocking the export forever. A stuck reader thread ends when the
+/// connection finally closes; its chunks are dropped.
+struct StallTimeout {
+  chunks: Receiver<std::io::Result<Vec<u8>>>,
+  pending: Vec<u8>,
+  offset: usize,
+  timeout: Duration,
+}
+
+impl StallTimeout {
+  fn new(mut inner: impl Read + Send + 'static, timeout: Duration) -> Self {
+    let (send, chunks) = mpsc::sync_channel(1);
+    std::thread::spawn(move || loop {
+      let mut chunk = vec![0u8; CHUNK_SIZE];
+      let result = inner.read(&mut chunk).map(|read| {
+        chunk.truncate(read);
+        chunk
+      });
+      let last = !matches!(&result, Ok(chunk) if !chunk.is_empty());
+      if send.send(result).is_err() || last {
+        break;
+      }
+    });
+    StallTimeout {
+      chunks,
+      pending: Vec::new(),
+      offset: 0,
+      timeout,
+    }
+  }
+}
+
+impl Read for StallTimeout {
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    if self.offset == self.pending.len() {
+      self.pending = match self.chunks.recv_timeout(self.timeout) {
+        Ok(chunk) => chunk?,
+        Err(RecvTimeoutError::Timeout) => {
+          let message = format!("no data from the backend for {}s", self.timeout.as_secs());
+          return Err(std::io::Error::new(std::io::ErrorKind::TimedOut, message));
+        }
+        Err(RecvTimeoutError::Disconnected) => {
+          return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "export reader stopped"));
+        }
+      };
+      self.offset = 0;
+    }
+    let read = buf.len().min(self.pending.len() - self.offset);
+    buf[..read].copy_from_slice(&self.pending[self.offset..self.offset + read]);
+    self.offset += read;
+    Ok(read)
+  }
+}
+
+fn stream_to_file(
+  app: &tauri::AppHandle,
+  export: &ExportState,
+  mut reader: StallTimeout,
+  path: &Path,
+  total_estimate: Option<u64>,
+) -> Result<u64, CommandError> {
+  let mut file = std::fs::File::create(path).map_err(|e| format!("Cannot create {}: {}", path.display(), e))?;
+  let mut buffer = vec![0u8; CHUNK_SIZE];
+  let mut bytes_written = 0u64;
+  let mut last_progress = Instant::now();
+  loop {
+    if export.cancel.load(Ordering::SeqCst) {
+      return Err("Export cancelled".to_string().into());
+    }
+    let read = reader.read(&mut buffer).map_err(|e| match e.kind() {
+      std::io::ErrorKind::TimedOut => backend_api::timed_out("export_tally", reader.timeout),
+      _ => format!("Export download failed: {}", e).into(),
+    })?;
+    if read == 0 {
+      break;
+    }
+    file
+      .write_all(&buffer[..read])
+      .map_err(|e| format!("Failed writing {}: {}", path.display(), e))?;
+    bytes_written += read as u64;
+    if last_progress.elapsed() >= PROGRESS_INTERVAL {
+      last_progress = Instant::now();
+      events::emit(app, "export://progress", ExportProgress { bytes_written, total_estimate });
+    }
+  }
+  file.sync_all().map_err(|e| format!("Failed flushing {}: {}", path.display(), e))?;
+  events::emit(app, "export://progress", ExportProgress { bytes_written, total_estimate });
+  Ok(bytes_written)
+}
+
+/// Abort the running export; `export_tally` then fails with "Export cancelled".
+/// Returns false when nothing was running.
+#[tauri::command]
+pub fn cancel_export(export: tauri::State<ExportState>) -> bool {
+  if !export.running.load(Ordering::SeqCst) {
+    return false;
+  }
+  export.cancel.store(true, Ordering::SeqCst);
+  diag("Export cancellation requested");
+  true
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Sends `data`, then blocks until dropped like a backend that stalls mid-body.
+  struct Stalling(std::io::Cursor<Vec<u8>>);
+
+  impl Read for Stalling {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+      match self.0.read(buf)? {
+        0 => loop {
+          std::thread::park();
+        },
+        read => Ok(read),
+      }
+    }
+  }
+
+  #[test]
+  fn a_stalled_body_times_out_after_what_was_sent() {
+    let mut reader = StallTimeout::new(Stalling(std::io::Cursor::new(b"partial".to_vec())), Duration::from_millis(100));
+    let mut received = Vec::new();
+    let err = reader.read_to_end(&mut received).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    assert_eq!(received, b"partial");
+  }
+
+  #[test]
+  fn a_complete_body_is_passed_through_whole() {
+    let data: Vec<u8> = (0..3 * CHUNK_SIZE + 17).map(|i| i as u8).collect();
+    let mut reader = StallTimeout::new(std::io::Cursor::new(data.clone()), Duration::from_secs(5));
+    let mut received = Vec::new();
+    reader.read_to_end(&mut received).unwrap();
+    assert_eq!(received, data);
+  }
+}
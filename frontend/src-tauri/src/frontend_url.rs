@@ -0,0 +1,91 @@
+//! Pointing the main window at a live frontend dev server (e.g. Vite)
+//! instead of the bundled assets, for frontend contributors working against
+//! a packaged build. Only with TALUS_DEV=1: TALUS_FRONTEND_URL applies at
+//! launch and `set_frontend_url` switches at runtime. The server must answer
+//! before the window is moved, so a typo can't leave it blank.
+//!
+//! The capability letting a dev server's pages call into the app lives
+//! outside `capabilities/`, which Tauri loads into every build, and is only
+//! added at launch under TALUS_DEV.
+
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Manager, Url};
+
+use crate::{backend_reachable, diag, env_flag, http, main_window};
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+const DEV_SERVER_CAPABILITY: &str = include_str!("../dev-capabilities/dev-server.json");
+
+/// URL of the bundled frontend, remembered on the first switch away from it.
+#[derive(Default)]
+pub struct FrontendUrlState(Mutex<Option<Url>>);
+
+/// Parse `url` and make sure something answers there.
+fn check(url: &str) -> Result<Url, String> {
+  let parsed = Url::parse(url.trim()).map_err(|e| format!("Invalid frontend URL {:?}: {}", url, e))?;
+  if !matches!(parsed.scheme(), "http" | "https") {
+    return Err(format!("Frontend URL must be http or https, not {}", parsed.scheme()));
+  }
+  let host = parsed.host_str().ok_or_else(|| format!("Frontend URL {} has no host", parsed))?;
+  let port = parsed.port_or_known_default().unwrap_or(80);
+  let answering = if parsed.scheme() == "http" {
+    // Any HTTP status means a server is there; only a failed request is fatal
+    http::get(host, port, parsed.path(), CHECK_TIMEOUT).is_ok()
+  } else {
+    backend_reachable(host, port)
+  };
+  if !answering {
+    return Err(format!("Nothing is answering at {}; is the dev server running?", parsed));
+  }
+  Ok(parsed)
+}
+
+fn navigate(app: &tauri::AppHandle, url: Url) -> Result<(), String> {
+  let window = main_window(app).ok_or_else(|| "Main window not found".to_string())?;
+  let state = app.state::<FrontendUrlState>();
+  if let (Ok(mut embedded), Ok(current)) = (state.0.lock(), window.url()) {
+    embedded.get_or_insert(current);
+  }
+  diag(&format!("Loading frontend from {}", url));
+  window.navigate(url).map_err(|e| format!("Failed to load frontend: {}", e))
+}
+
+/// With TALUS_DEV=1, allow dev server origins to use the app's commands and
+/// apply TALUS_FRONTEND_URL. Without it the URL is ignored; an unreachable
+/// URL is logged and the bundled frontend kept.
+pub fn apply_env(app: &tauri::AppHandle) {
+  if env_flag("TALUS_DEV") {
+    if let Err(err) = app.add_capability(DEV_SERVER_CAPABILITY) {
+      diag(&format!("Failed to allow frontend dev servers: {}", err));
+    }
+  }
+  let Some(url) = std::env::var("TALUS_FRONTEND_URL").ok().filter(|url| !url.trim().is_empty()) else {
+    return;
+  };
+  if !env_flag("TALUS_DEV") {
+    diag("TALUS_FRONTEND_URL ignored; it requires TALUS_DEV=1");
+    return;
+  }
+  if let Err(err) = check(&url).and_then(|url| navigate(app, url)) {
+    diag(&format!("{}; keeping the bundled frontend", err));
+  }
+}
+
+/// Load the main window from `url` (a dev server), or with no URL go back to
+/// the bundled frontend. Requires TALUS_DEV=1. Returns the URL now loaded.
+#[tauri::command(async)]
+pub fn set_frontend_url(app: tauri::AppHandle, state: tauri::State<FrontendUrlState>, url: Option<String>) -> Result<String, String> {
+  if !env_flag("TALUS_DEV") {
+    return Err("set_frontend_url requires TALUS_DEV=1".to_string());
+  }
+  let target = match url.filter(|url| !url.trim().is_empty()) {
+    Some(url) => check(&url)?,
+    None => match state.0.lock().ok().and_then(|embedded| embedded.clone()) {
+      Some(embedded) => embedded,
+      None => return Err("Already showing the bundled frontend".to_string()),
+    },
+  };
+  navigate(&app, target.clone())?;
+  Ok(target.to_string())
+}
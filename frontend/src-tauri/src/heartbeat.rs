@@ -0,0 +1,70 @@
+//! Periodic `backend://heartbeat` carrying the backend's health, so the
+//! frontend can react to state changes without running its own timer. A drop
+//! in `backend_uptime_secs` means the backend restarted. Heartbeats pause
+//! while every window is hidden (e.g. minimized to tray).
+
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::{config, events, BackendState};
+
+pub const MIN_INTERVAL_MS: u64 = 1000;
+pub const MAX_INTERVAL_MS: u64 = 60_000;
+
+#[derive(Clone, Serialize)]
+struct BackendHealth {
+  reachable: bool,
+  mode: Option<&'static str>,
+  pid: Option<u32>,
+  port: u16,
+  paused: bool,
+  /// `None` while no backend is running.
+  backend_uptime_secs: Option<u64>,
+}
+
+fn health(backend: &BackendState) -> BackendHealth {
+  BackendHealth {
+    reachable: backend.is_reachable(),
+    mode: backend.mode.lock().ok().and_then(|mode| *mode),
+    pid: backend.process.lock().ok().and_then(|proc| proc.as_ref().map(|child| child.id())),
+    port: backend.port(),
+    paused: backend.paused.load(Ordering::SeqCst),
+    backend_uptime_secs: backend
+      .backend_since
+      .lock()
+      .ok()
+      .and_then(|since| since.map(|since| since.elapsed().as_secs())),
+  }
+}
+
+fn any_window_visible(app: &tauri::AppHandle) -> bool {
+  app
+    .webview_windows()
+    .values()
+    .any(|window| window.is_visible().unwrap_or(false))
+}
+
+pub fn spawn(app: tauri::AppHandle, backend: BackendState) {
+  std::thread::spawn(move || loop {
+    let interval = config::current(&app).heartbeat_interval_ms.clamp(MIN_INTERVAL_MS, MAX_INTERVAL_MS);
+    std::thread::sleep(Duration::from_millis(interval));
+    if any_window_visible(&app) {
+      events::emit(&app, "backend://heartbeat", health(&backend));
+    }
+  });
+}
+
+#[tauri::command]
+pub fn get_heartbeat_interval(app: tauri::AppHandle) -> u64 {
+  config::current(&app).heartbeat_interval_ms
+}
+
+/// Persist a new heartbeat interval (clamped); the next beat picks it up.
+#[tauri::command]
+pub fn set_heartbeat_interval(app: tauri::AppHandle, ms: u64) -> Result<u64, String> {
+  let ms = ms.clamp(MIN_INTERVAL_MS, MAX_INTERVAL_MS);
+  config::update(&app, |config| config.heartbeat_interval_ms = ms)?;
+  Ok(ms)
+}
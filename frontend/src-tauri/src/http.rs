@@ -0,0 +1,116 @@
+//! Minimal HTTP client for the handful of small JSON requests we make to the
+//! backend, avoiding a full HTTP crate. Requests are sent as HTTP/1.0 with
+//! `Connection: close` so responses are never chunked and end at EOF.
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// Backend health endpoint, returns `{"status": "ok"}`, or `"needs-setup"`
+/// on a fresh install until `run_backend_setup` has run.
+pub const HEALTH_PATH: &str = "/api/v1/health";
+pub const NEEDS_SETUP: &str = "needs-setup";
+
+pub struct Response {
+  pub status: u16,
+  pub body: String,
+}
+
+impl Response {
+  pub fn is_success(&self) -> bool {
+    (200..300).contains(&self.status)
+  }
+
+  pub fn json(&self) -> Result<serde_json::Value, String> {
+    serde_json::from_str(&self.body).map_err(|e| format!("Invalid JSON from backend: {}", e))
+  }
+}
+
+pub fn get(host: &str, port: u16, path: &str, timeout: Duration) -> Result<Response, String> {
+  request(host, port, "GET", path, None, timeout)
+}
+
+pub fn post_json(host: &str, port: u16, path: &str, body: &serde_json::Value, timeout: Duration) -> Result<Response, String> {
+  request(host, port, "POST", path, Some(body.to_string()), timeout)
+}
+
+fn request(host: &str, port: u16, method: &str, path: &str, body: Option<String>, timeout: Duration) -> Result<Response, String> {
+  let addr = (host, port)
+    .to_socket_addrs()
+    .map_err(|e| format!("Cannot resolve {}:{}: {}", host, port, e))?
+    .next()
+    .ok_or_else(|| format!("No address for {}:{}", host, port))?;
+  let mut stream = TcpStream::connect_timeout(&addr, timeout)
+    .map_err(|e| format!("Connect to {} failed: {}", addr, e))?;
+  stream.set_read_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+  stream.set_write_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+
+  let body = body.unwrap_or_default();
+  let head = format!(
+    "{} {} HTTP/1.0\r\nHost: {}:{}\r\nConnection: close\r\nAccept: application/json\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+    method,
+    path,
+    host,
+    port,
+    body.len()
+  );
+  stream
+    .write_all(head.as_bytes())
+    .and_then(|_| stream.write_all(body.as_bytes()))
+    .map_err(|e| format!("{} {} failed: {}", method, path, e))?;
+
+  let mut raw = Vec::new();
+  stream
+    .read_to_end(&mut raw)
+    .map_err(|e| format!("{} {} failed reading response: {}", method, path, e))?;
+  parse_response(&raw)
+}
+
+fn parse_response(raw: &[u8]) -> Result<Response, String> {
+  let text = String::from_utf8_lossy(raw);
+  let (head, body) = text.split_once("\r\n\r\n").unwrap_or((&text, ""));
+  let status = head
+    .lines()
+    .next()
+    .and_then(|line| line.split_whitespace().nth(1))
+    .and_then(|code| code.parse::<u16>().ok())
+    .ok_or_else(|| "Malformed HTTP response from backend".to_string())?;
+  Ok(Response {
+    status,
+    body: body.to_string(),
+  })
+}
+
+/// Status reported by the Talus health endpoint on `host:port`, if it answers.
+pub fn health_status(host: &str, port: u16, timeout: Duration) -> Option<String> {
+  get(host, port, HEALTH_PATH, timeout)
+    .ok()
+    .filter(Response::is_success)
+    .and_then(|response| response.json().ok())
+    .and_then(|json| json.get("status").and_then(|s| s.as_str()).map(str::to_string))
+}
+
+/// True when `host:port` answers the Talus health endpoint as a working backend.
+pub fn is_talus_backend(host: &str, port: u16, timeout: Duration) -> bool {
+  health_status(host, port, timeout).is_some_and(|status| status == "ok" || status == NEEDS_SETUP)
+}
+
+/// Health probe over the backend's Unix domain socket (TALUS_HEALTH_SOCKET):
+/// the backend writes one `{"status": "ok", ...}` line per connection.
+#[cfg(unix)]
+pub fn socket_health(path: &std::path::Path, timeout: Duration) -> bool {
+  let Ok(mut stream) = std::os::unix::net::UnixStream::connect(path) else {
+    return false;
+  };
+  let _ = stream.set_read_timeout(Some(timeout));
+  let mut line = String::new();
+  let _ = stream.read_to_string(&mut line);
+  serde_json::from_str::<serde_json::Value>(line.trim())
+    .map(|json| json.get("status").and_then(|s| s.as_str()) == Some("ok"))
+    .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+pub fn socket_health(_path: &std::path::Path, _timeout: Duration) -> bool {
+  false
+}
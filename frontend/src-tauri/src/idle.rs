@@ -0,0 +1,114 @@
+//! Auto-pause for shared kiosks. The frontend reports user activity with
+//! `report_activity`; once none arrives for the session's idle timeout the
+//! backend is paused (`backend://idle-paused`) and the next activity resumes
+//! it (`backend://idle-resumed`). A backend the user paused by hand is left
+//! paused. The timeout lasts for this session only and is off by default.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::{backend_api, diag, events, BackendState};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct IdleState {
+  /// Seconds without activity before pausing; 0 turns auto-pause off.
+  timeout_secs: AtomicU32,
+  last_activity: Mutex<Instant>,
+  /// The current pause is ours, so activity may lift it.
+  idle_paused: AtomicBool,
+}
+
+impl Default for IdleState {
+  fn default() -> Self {
+    IdleState {
+      timeout_secs: AtomicU32::new(0),
+      last_activity: Mutex::new(Instant::now()),
+      idle_paused: AtomicBool::new(false),
+    }
+  }
+}
+
+impl IdleState {
+  fn idle_for(&self) -> Duration {
+    self.last_activity.lock().map(|last| last.elapsed()).unwrap_or_default()
+  }
+
+  fn touch(&self) {
+    if let Ok(mut last) = self.last_activity.lock() {
+      *last = Instant::now();
+    }
+  }
+}
+
+#[derive(Clone, Serialize)]
+struct IdlePaused {
+  idle_secs: u64,
+  timeout_secs: u32,
+}
+
+#[derive(Clone, Serialize)]
+struct IdleResumed {
+  idle_secs: u64,
+}
+
+pub fn spawn(app: tauri::AppHandle, backend: BackendState) {
+  std::thread::spawn(move || loop {
+    std::thread::sleep(CHECK_INTERVAL);
+    let idle = app.state::<IdleState>();
+    let timeout_secs = idle.timeout_secs.load(Ordering::SeqCst);
+    // A restarted (or manually resumed) backend isn't paused any more, so
+    // our pause no longer stands
+    if idle.idle_paused.load(Ordering::SeqCst) && !backend.paused.load(Ordering::SeqCst) {
+      idle.idle_paused.store(false, Ordering::SeqCst);
+      idle.touch();
+    }
+    if timeout_secs == 0 || idle.idle_paused.load(Ordering::SeqCst) || backend.paused.load(Ordering::SeqCst) {
+      continue;
+    }
+    let idle_for = idle.idle_for();
+    if idle_for < Duration::from_secs(timeout_secs.into()) {
+      continue;
+    }
+    diag(&format!("No activity for {}s (timeout {}s); pausing backend", idle_for.as_secs(), timeout_secs));
+    match backend_api::set_paused(&app, &backend, true) {
+      Ok(()) => {
+        idle.idle_paused.store(true, Ordering::SeqCst);
+        events::emit_lifecycle(&app, &backend, "backend://idle-paused", IdlePaused { idle_secs: idle_for.as_secs(), timeout_secs });
+      }
+      // Try again after a full timeout rather than every tick
+      Err(err) => {
+        diag(&format!("Idle pause failed: {}", err));
+        idle.touch();
+      }
+    }
+  });
+}
+
+/// Pause the backend after `secs` without reported activity; 0 turns it off.
+#[tauri::command]
+pub fn set_idle_timeout(state: tauri::State<IdleState>, secs: u32) {
+  state.timeout_secs.store(secs, Ordering::SeqCst);
+  state.touch();
+  diag(&format!("Idle timeout set to {}s", secs));
+}
+
+/// Record user activity (the frontend calls this on input, throttled), and
+/// resume the backend if it was paused for being idle.
+#[tauri::command(async)]
+pub fn report_activity(app: tauri::AppHandle, state: tauri::State<IdleState>, backend: tauri::State<BackendState>) -> Result<(), backend_api::CommandError> {
+  let idle_secs = state.idle_for().as_secs();
+  state.touch();
+  if !state.idle_paused.load(Ordering::SeqCst) {
+    return Ok(());
+  }
+  // Only drop our pause once the backend has actually resumed
+  backend_api::set_paused(&app, backend.inner(), false)?;
+  state.idle_paused.store(false, Ordering::SeqCst);
+  diag(&format!("Activity after {}s idle; backend resumed", idle_secs));
+  events::emit_lifecycle(&app, backend.inner(), "backend://idle-resumed", IdleResumed { idle_secs });
+  Ok(())
+}
@@ -0,0 +1,140 @@
+//! Checking the packaged backend against the SHA-256 manifest written when
+//! resources are staged (scripts/prepare_tauri_resources.py), so a corrupted
+//! or tampered install is reported as such instead of as a mysterious crash.
+//! The manifest also tells a backend binary that antivirus quarantined (listed
+//! but gone) apart from a build that never had one.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use tauri::Manager;
+
+use crate::{backend_binary_name, determine_project_root, diag, find_packaged_backend};
+
+/// `sha256sum` format: "<hex>  <path relative to the backend dir>" per line.
+const MANIFEST_NAME: &str = "checksums.sha256";
+
+#[derive(Serialize)]
+pub struct IntegrityResult {
+  ok: bool,
+  manifest: PathBuf,
+  checked: usize,
+  /// Files whose contents don't match the manifest.
+  mismatched: Vec<PathBuf>,
+  /// Files listed in the manifest that are gone.
+  missing: Vec<PathBuf>,
+  message: String,
+}
+
+fn sha256_file(path: &Path) -> std::io::Result<String> {
+  let mut file = std::fs::File::open(path)?;
+  let mut hasher = Sha256::new();
+  let mut buffer = vec![0u8; 64 * 1024];
+  loop {
+    let read = file.read(&mut buffer)?;
+    if read == 0 {
+      break;
+    }
+    hasher.update(&buffer[..read]);
+  }
+  Ok(hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Only plain relative paths, so a doctored manifest can't point outside the bundle.
+fn is_contained(relative: &Path) -> bool {
+  relative.components().all(|component| matches!(component, Component::Normal(_)))
+}
+
+#[derive(Clone, Serialize)]
+pub struct BinaryQuarantined {
+  /// Where the manifest says the backend binary should be.
+  pub expected_path: PathBuf,
+  manifest: PathBuf,
+  pub guidance: String,
+}
+
+/// When no packaged backend was found, check whether a bundle manifest still
+/// lists it: the install shipped the binary and something (usually antivirus)
+/// removed it afterwards.
+pub fn quarantined_binary(app: &tauri::AppHandle, project_root: &Path) -> Option<BinaryQuarantined> {
+  let mut roots = vec![project_root.to_path_buf()];
+  if let Ok(resource_dir) = app.path().resource_dir() {
+    roots.insert(0, resource_dir);
+  }
+  let binary = backend_binary_name();
+  roots
+    .iter()
+    .flat_map(|root| ["talus-tally-backend", "resources/talus-tally-backend"].map(|sub| root.join(sub)))
+    .find_map(|bundle_dir| {
+      let manifest = bundle_dir.join(MANIFEST_NAME);
+      let text = std::fs::read_to_string(&manifest).ok()?;
+      let listed = text
+        .lines()
+        .filter_map(|line| line.split_once("  "))
+        .any(|(_, relative)| relative.trim() == binary);
+      let expected_path = bundle_dir.join(binary);
+      (listed && !expected_path.exists()).then(|| BinaryQuarantined {
+        guidance: format!(
+          "{} is part of this install but has been removed, most likely quarantined by antivirus. \
+           Restore it from quarantine and add an exclusion for {} (in Windows Security: Virus & threat protection \
+           > Protection history), or reinstall Talus Tally.",
+          binary,
+          bundle_dir.display()
+        ),
+        expected_path,
+        manifest,
+      })
+    })
+}
+
+#[tauri::command(async)]
+pub fn verify_bundle_integrity(app: tauri::AppHandle) -> Result<IntegrityResult, String> {
+  let binary = find_packaged_backend(Some(&app), &determine_project_root(Some(&app)))
+    .ok_or_else(|| "No packaged backend to verify (running from source?)".to_string())?;
+  let bundle_dir = binary.parent().unwrap_or(Path::new("."));
+  let manifest = bundle_dir.join(MANIFEST_NAME);
+  let text = std::fs::read_to_string(&manifest)
+    .map_err(|e| format!("No checksum manifest at {} ({}); this build can't be verified", manifest.display(), e))?;
+
+  let mut checked = 0;
+  let mut mismatched = Vec::new();
+  let mut missing = Vec::new();
+  for line in text.lines().filter(|line| !line.trim().is_empty()) {
+    let Some((expected, relative)) = line.split_once("  ") else {
+      return Err(format!("Malformed line in {}: {}", manifest.display(), line));
+    };
+    let relative = PathBuf::from(relative.trim());
+    if !is_contained(&relative) {
+      return Err(format!("Manifest entry escapes the bundle: {}", relative.display()));
+    }
+    checked += 1;
+    match sha256_file(&bundle_dir.join(&relative)) {
+      Ok(actual) if actual.eq_ignore_ascii_case(expected.trim()) => {}
+      Ok(_) => mismatched.push(relative),
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => missing.push(relative),
+      Err(err) => return Err(format!("Failed to read {}: {}", relative.display(), err)),
+    }
+  }
+
+  let ok = mismatched.is_empty() && missing.is_empty();
+  let message = if ok {
+    format!("All {} backend files match the manifest", checked)
+  } else {
+    format!(
+      "Installation is corrupted ({} changed, {} missing); please reinstall Talus Tally",
+      mismatched.len(),
+      missing.len()
+    )
+  };
+  diag(&format!("Bundle integrity: {}", message));
+  Ok(IntegrityResult {
+    ok,
+    manifest,
+    checked,
+    mismatched,
+    missing,
+    message,
+  })
+}
@@ -0,0 +1,106 @@
+//! Opt-in tracing of IPC traffic to the diagnostic log: every invoked command
+//! with its (sanitized) arguments and every event we emit. Only available in
+//! debug builds or with TALUS_DEV=1, since arguments can contain user data.
+//! Command results travel back through Tauri's resolver and aren't visible
+//! here; a command that no-ops still shows up as invoked. TALUS_IPC_TRACE=1
+//! turns tracing on from launch.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::ipc::{Invoke, InvokeBody};
+
+use crate::{diag, env_flag};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// Most recently invoked command, traced or not, for stall reports.
+static LAST_COMMAND: Mutex<Option<String>> = Mutex::new(None);
+
+/// Argument keys whose values are never logged.
+const SENSITIVE_KEYS: [&str; 5] = ["password", "token", "secret", "auth", "key"];
+const MAX_VALUE_CHARS: usize = 200;
+
+fn allowed() -> bool {
+  cfg!(debug_assertions) || env_flag("TALUS_DEV")
+}
+
+pub fn init() {
+  if allowed() && env_flag("TALUS_IPC_TRACE") {
+    ENABLED.store(true, Ordering::SeqCst);
+    diag("IPC tracing enabled via TALUS_IPC_TRACE");
+  }
+}
+
+fn enabled() -> bool {
+  ENABLED.load(Ordering::Relaxed)
+}
+
+fn sanitize(value: &serde_json::Value) -> serde_json::Value {
+  match value {
+    serde_json::Value::Object(fields) => fields
+      .iter()
+      .map(|(key, value)| {
+        let lower = key.to_lowercase();
+        let value = if SENSITIVE_KEYS.iter().any(|sensitive| lower.contains(sensitive)) {
+          serde_json::Value::String("<redacted>".to_string())
+        } else {
+          sanitize(value)
+        };
+        (key.clone(), value)
+      })
+      .collect(),
+    serde_json::Value::Array(items) => items.iter().map(sanitize).collect(),
+    serde_json::Value::String(text) if text.chars().count() > MAX_VALUE_CHARS => {
+      serde_json::Value::String(format!("{}...", text.chars().take(MAX_VALUE_CHARS).collect::<String>()))
+    }
+    other => other.clone(),
+  }
+}
+
+/// Wrap the app's command handler so each invoke is traced before dispatch.
+pub fn traced<R: tauri::Runtime>(
+  handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+  move |invoke| {
+    if let Ok(mut last) = LAST_COMMAND.lock() {
+      *last = Some(invoke.message.command().to_string());
+    }
+    trace_invoke(&invoke);
+    handler(invoke)
+  }
+}
+
+pub fn last_command() -> Option<String> {
+  LAST_COMMAND.lock().ok().and_then(|last| last.clone())
+}
+
+fn trace_invoke<R: tauri::Runtime>(invoke: &Invoke<R>) {
+  if !enabled() {
+    return;
+  }
+  let args = match invoke.message.payload() {
+    InvokeBody::Json(value) => sanitize(value).to_string(),
+    InvokeBody::Raw(bytes) => format!("<{} raw bytes>", bytes.len()),
+  };
+  diag(&format!("[ipc] invoke {} {}", invoke.message.command(), args));
+}
+
+pub fn event<T: Serialize>(name: &str, payload: &T) {
+  if !enabled() {
+    return;
+  }
+  let payload = serde_json::to_value(payload)
+    .map(|value| sanitize(&value).to_string())
+    .unwrap_or_else(|_| "<unserializable>".to_string());
+  diag(&format!("[ipc] emit {} {}", name, payload));
+}
+
+#[tauri::command]
+pub fn set_ipc_trace(enabled: bool) -> Result<(), String> {
+  if !allowed() {
+    return Err("IPC tracing requires a debug build or TALUS_DEV=1".to_string());
+  }
+  ENABLED.store(enabled, Ordering::SeqCst);
+  diag(&format!("IPC tracing {}", if enabled { "enabled" } else { "disabled" }));
+  Ok(())
+}
@@ -0,0 +1,94 @@
+//! Keeping the machine and display awake during a live event. The inhibition
+//! is held by a helper process for as long as it runs, and released by
+//! killing it: `systemd-inhibit` on Linux, `caffeinate` on macOS, and a
+//! PowerShell loop holding `SetThreadExecutionState` on Windows.
+
+use std::process::{Child, Command};
+use std::sync::Mutex;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use crate::diag;
+
+#[derive(Default)]
+pub struct KeepAwakeState(Mutex<Option<Child>>);
+
+#[cfg(target_os = "linux")]
+fn inhibitor() -> Command {
+  let mut command = Command::new("systemd-inhibit");
+  command.args([
+    "--what=idle:sleep",
+    "--who=Talus Tally",
+    "--why=Live tally in progress",
+    "--mode=block",
+    "sleep",
+    "infinity",
+  ]);
+  command
+}
+
+#[cfg(target_os = "macos")]
+fn inhibitor() -> Command {
+  // -w also ends the assertion if we die without releasing it
+  let mut command = Command::new("caffeinate");
+  command.args(["-d", "-i", "-s", "-w", &std::process::id().to_string()]);
+  command
+}
+
+#[cfg(target_os = "windows")]
+fn inhibitor() -> Command {
+  // ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED, held while the thread lives
+  let script = "Add-Type -Name Power -Namespace Talus -MemberDefinition \
+    '[DllImport(\"kernel32.dll\")] public static extern uint SetThreadExecutionState(uint flags);'; \
+    [Talus.Power]::SetThreadExecutionState(0x80000003) | Out-Null; \
+    while ($true) { Start-Sleep -Seconds 60 }";
+  let mut command = Command::new("powershell");
+  command
+    .args(["-NoProfile", "-NonInteractive", "-Command", script])
+    .creation_flags(0x08000000);
+  command
+}
+
+fn release(slot: &mut Option<Child>) {
+  if let Some(mut child) = slot.take() {
+    let _ = child.kill();
+    let _ = child.wait();
+    diag("Keep-awake released");
+  }
+}
+
+/// Stop inhibiting sleep; called on app exit.
+pub fn release_all(state: &KeepAwakeState) {
+  if let Ok(mut slot) = state.0.lock() {
+    release(&mut slot);
+  }
+}
+
+/// Prevent (or allow again) screen dimming and system sleep.
+#[tauri::command]
+pub fn set_keep_awake(state: tauri::State<KeepAwakeState>, enabled: bool) -> Result<(), String> {
+  let mut slot = state.0.lock().map_err(|_| "Keep-awake lock poisoned".to_string())?;
+  if !enabled {
+    release(&mut slot);
+    return Ok(());
+  }
+  // A helper that exited (e.g. killed externally) no longer holds anything
+  if slot.as_mut().is_some_and(|child| matches!(child.try_wait(), Ok(None))) {
+    return Ok(());
+  }
+  let child = inhibitor()
+    .spawn()
+    .map_err(|e| format!("Could not inhibit sleep on this system: {}", e))?;
+  diag(&format!("Keep-awake enabled (helper pid={})", child.id()));
+  *slot = Some(child);
+  Ok(())
+}
+
+#[tauri::command]
+pub fn get_keep_awake(state: tauri::State<KeepAwakeState>) -> bool {
+  state
+    .0
+    .lock()
+    .is_ok_and(|mut slot| slot.as_mut().is_some_and(|child| matches!(child.try_wait(), Ok(None))))
+}
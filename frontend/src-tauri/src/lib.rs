@@ -1,10 +1,64 @@
+use std::collections::BTreeMap;
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Child};
-use std::net::TcpStream;
+use std::process::{Command, Child, Stdio};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU16, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use tauri::{Emitter, Manager};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tauri_plugin_autostart::{MacosLauncher, ManagerExt};
+
+mod backend_api;
+mod backend_errors;
+mod backup;
+mod build_info;
+mod clock;
+mod config;
+mod config_check;
+mod data_lock;
+mod data_store;
+mod data_watch;
+mod database;
+mod deeplink;
+mod diagnostics;
+mod events;
+mod export;
+mod frontend_url;
+mod heartbeat;
+mod http;
+mod idle;
+mod integrity;
+mod ipc_trace;
+mod keep_awake;
+mod log_buffer;
+mod log_follow;
+mod main_thread;
+mod migration;
+mod monitors;
+mod network;
+mod permissions;
+mod port_owner;
+mod priority;
+mod profiler;
+mod profiles;
+mod sandbox;
+mod schema;
+mod shortcut;
+mod stack_dump;
+mod startup;
+mod startup_history;
+mod supervisor;
+mod thermal;
+mod tray;
+mod updates;
+mod venv;
+mod webview;
+mod window;
+mod workers;
+
+use config::ConfigState;
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
@@ -14,7 +68,17 @@ use std::os::windows::process::CommandExt;
 /// On Linux:   $XDG_DATA_HOME/com.talus.tally/backend-launch.log  (or ~/.local/share/...)
 /// On macOS:   ~/Library/Application Support/com.talus.tally/backend-launch.log
 fn diagnostic_log_path() -> Option<PathBuf> {
-  let base = if cfg!(target_os = "windows") {
+  diagnostic_dir().map(|d| d.join("backend-launch.log"))
+}
+
+/// Directory holding the diagnostic log and other small launch records.
+fn diagnostic_dir() -> Option<PathBuf> {
+  user_data_base().map(|b| b.join("com.talus.tally"))
+}
+
+/// Per-user data root: %LOCALAPPDATA%, ~/Library/Application Support, or $XDG_DATA_HOME.
+fn user_data_base() -> Option<PathBuf> {
+  if cfg!(target_os = "windows") {
     std::env::var("LOCALAPPDATA").ok().map(PathBuf::from)
   } else if cfg!(target_os = "macos") {
     dirs_next().map(|h| h.join("Library").join("Application Support"))
@@ -23,8 +87,7 @@ fn diagnostic_log_path() -> Option<PathBuf> {
       .ok()
       .map(PathBuf::from)
       .or_else(|| dirs_next().map(|h| h.join(".local").join("share")))
-  };
-  base.map(|b| b.join("com.talus.tally").join("backend-launch.log"))
+  }
 }
 
 /// Simple home-dir helper (avoids adding a crate dependency).
@@ -35,6 +98,30 @@ fn dirs_next() -> Option<PathBuf> {
     .map(PathBuf::from)
 }
 
+/// Read a boolean-ish environment flag ("1", "true", "yes").
+fn env_flag(name: &str) -> bool {
+  std::env::var(name)
+    .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+    .unwrap_or(false)
+}
+
+/// Log level from TALUS_LOG_LEVEL (off/error/warn/info/debug/trace), defaulting
+/// to Info in debug builds and Warn in release. Invalid values fall back to the default.
+fn log_level() -> log::LevelFilter {
+  let default = if cfg!(debug_assertions) {
+    log::LevelFilter::Info
+  } else {
+    log::LevelFilter::Warn
+  };
+  match std::env::var("TALUS_LOG_LEVEL") {
+    Ok(value) => value.trim().parse().unwrap_or_else(|_| {
+      diag(&format!("Invalid TALUS_LOG_LEVEL '{}'; using {}", value, default));
+      default
+    }),
+    Err(_) => default,
+  }
+}
+
 /// Append a timestamped line to the diagnostic log file.
 fn diag(msg: &str) {
   // Always print to stdout/stderr for dev builds
@@ -45,20 +132,100 @@ fn diag(msg: &str) {
       let _ = std::fs::create_dir_all(parent);
     }
     if let Ok(mut f) = OpenOptions::new().create(true).append(true).open(&log_path) {
-      let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0);
-      let _ = writeln!(f, "[{}] {}", now, msg);
+      let _ = writeln!(f, "[{}] {}", unix_now(), msg);
     }
   }
 }
 
-fn terminate_backend_process(state: &Arc<Mutex<Option<Child>>>, reason: &str) {
+/// Seconds since the Unix epoch (0 if the clock is before 1970).
+fn unix_now() -> u64 {
+  unix_now_ms() / 1000
+}
+
+fn unix_now_ms() -> u64 {
+  std::time::SystemTime::now()
+    .duration_since(std::time::UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or(0)
+}
+
+/// Upper bound for OS cleanup tools (kill/taskkill) before we move on.
+const CLEANUP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Run a short-lived OS tool with a bounded wait. If it hasn't returned by
+/// `timeout` it is killed and we carry on rather than blocking startup.
+fn run_with_timeout(command: &mut Command, label: &str, timeout: Duration) {
+  let mut child = match command
+    .stdin(Stdio::null())
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .spawn()
+  {
+    Ok(child) => child,
+    Err(err) => {
+      diag(&format!("Cleanup command {} failed to start: {}", label, err));
+      return;
+    }
+  };
+
+  let deadline = Instant::now() + timeout;
+  loop {
+    match child.try_wait() {
+      Ok(Some(_)) => return,
+      Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(25)),
+      Ok(None) => {
+        diag(&format!("Cleanup command {} timed out after {:?}; continuing", label, timeout));
+        let _ = child.kill();
+        let _ = child.wait();
+        return;
+      }
+      Err(err) => {
+        diag(&format!("Failed waiting for cleanup command {}: {}", label, err));
+        return;
+      }
+    }
+  }
+}
+
+/// Ask the backend to exit on its own (SIGTERM / taskkill without /F).
+fn request_graceful_exit(child: &Child) {
+  let pid = child.id().to_string();
+
+  #[cfg(unix)]
+  {
+    run_with_timeout(Command::new("kill").args(["-TERM", &pid]), "kill -TERM", CLEANUP_TIMEOUT);
+  }
+
+  #[cfg(target_os = "windows")]
+  {
+    let mut taskkill_cmd = Command::new("taskkill");
+    taskkill_cmd
+      .args(["/PID", &pid])
+      .creation_flags(0x08000000);
+    run_with_timeout(&mut taskkill_cmd, "taskkill /PID", CLEANUP_TIMEOUT);
+  }
+}
+
+/// Stop the backend gracefully, escalating to a hard kill once `timeout` elapses.
+fn terminate_backend_process(state: &Arc<Mutex<Option<Child>>>, reason: &str, timeout: Duration) {
   diag(&format!("Terminating backend process: {}", reason));
   if let Ok(mut proc) = state.lock() {
     if let Some(mut child) = proc.take() {
       let pid = child.id();
+      diag(&format!("Asking backend child pid={} to exit (timeout {:?})", pid, timeout));
+      request_graceful_exit(&child);
+      let deadline = Instant::now() + timeout;
+      loop {
+        match child.try_wait() {
+          Ok(Some(status)) => {
+            diag(&format!("Backend child pid={} exited gracefully with status {}", pid, status));
+            return;
+          }
+          Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+          _ => break,
+        }
+      }
+
       diag(&format!("Killing backend child pid={}", pid));
       let _ = child.kill();
       match child.wait() {
@@ -73,83 +240,510 @@ fn terminate_backend_process(state: &Arc<Mutex<Option<Child>>>, reason: &str) {
   }
 }
 
-struct BackendState(Arc<Mutex<Option<Child>>>);
+/// Kill a backend left over from a previous run, i.e. whatever still listens
+/// on our port. Matching by process name (`pkill -f "python.*backend.app"`,
+/// `taskkill /IM python.exe`) would also take down the sandbox backend and
+/// any unrelated Python process.
+fn kill_stray_backend(port: u16) {
+  match port_owner::kill_listener(port) {
+    Ok(pid) => diag(&format!("Killed leftover backend pid={} on port {}", pid, port)),
+    Err(err) => diag(&format!("No leftover backend to kill: {}", err)),
+  }
+}
+
+/// Backend process handle plus whether the running backend was adopted
+/// (already running when we launched) rather than spawned by us.
+#[derive(Clone)]
+struct BackendState {
+  process: Arc<Mutex<Option<Child>>>,
+  adopted: Arc<AtomicBool>,
+  /// Host and port the current backend was launched on (or adopted at).
+  host: Arc<Mutex<String>>,
+  port: Arc<AtomicU16>,
+  /// Address a remote host resolved to; health checks use it until refresh_backend_host.
+  resolved_addr: Arc<Mutex<Option<SocketAddr>>>,
+  /// Port used instead of the configured one after it was found blocked; 0 if none.
+  port_override: Arc<AtomicU16>,
+  /// How the current backend was launched: "packaged", "custom", "venv", "system" or "adopted".
+  mode: Arc<Mutex<Option<&'static str>>>,
+  /// Sequence number of the last lifecycle event emitted.
+  event_seq: Arc<AtomicU64>,
+  /// Whether the backend has been paused (tallies rejected) via pause_backend.
+  paused: Arc<AtomicBool>,
+  /// Most recent payload of each lifecycle event, for UIs that subscribe late.
+  last_events: Arc<Mutex<BTreeMap<String, serde_json::Value>>>,
+  /// Profile being collected when launched with TALUS_PROFILE.
+  profile: Arc<Mutex<Option<profiler::ProfileRun>>>,
+  /// Unix domain socket the spawned backend answers health checks on, if any.
+  health_socket: Arc<Mutex<Option<PathBuf>>>,
+  /// Why the spawn fell back from the packaged binary to venv/system Python.
+  fallback_reason: Arc<Mutex<Option<String>>>,
+  /// Spawn attempts made by the latest start_backend, for explaining a failed start.
+  spawn_attempts: Arc<Mutex<Vec<SpawnAttempt>>>,
+  /// Feature flags reported by the running backend, fetched on first use.
+  capabilities: Arc<Mutex<Option<Vec<String>>>>,
+  /// Set while start_backend runs, so the watchdog leaves a booting backend alone.
+  starting: Arc<AtomicBool>,
+  /// Automatic restarts since the backend was last stable (see supervisor).
+  recovery_attempts: Arc<AtomicU32>,
+  /// The supervisor gave up after too many failed recoveries.
+  recovery_exhausted: Arc<AtomicBool>,
+  /// When this app instance started.
+  app_started: Instant,
+  /// When the current backend was spawned or adopted; `None` while none is running.
+  backend_since: Arc<Mutex<Option<Instant>>>,
+  /// Backend restarts this session, whatever the reason.
+  restart_count: Arc<AtomicU32>,
+  /// Unexpected backend exits this session.
+  crash_count: Arc<AtomicU32>,
+  /// Stage the running start_backend is in and since when (see startup).
+  startup_stage: Arc<Mutex<Option<(startup::Stage, Instant)>>>,
+  /// The running startup overran a stage (or the user gave up on it).
+  startup_aborted: Arc<AtomicBool>,
+  /// Set while restart() runs, so a second restart can't spawn a duplicate backend.
+  restart_in_progress: Arc<AtomicBool>,
+}
+
+impl BackendState {
+  fn host(&self) -> String {
+    self
+      .host
+      .lock()
+      .map(|host| host.clone())
+      .unwrap_or_else(|_| config::DEFAULT_HOST.to_string())
+  }
+
+  fn port(&self) -> u16 {
+    self.port.load(Ordering::SeqCst)
+  }
+
+  /// Prefers the health socket when the backend has one, since loopback TCP
+  /// can be firewalled; falls back to a TCP connect.
+  fn is_reachable(&self) -> bool {
+    if self.health_socket_ok() {
+      return true;
+    }
+    match self.resolved_addr.lock().ok().and_then(|addr| *addr) {
+      Some(addr) => addr_reachable(&addr),
+      None => backend_reachable(&self.host(), self.port()),
+    }
+  }
+
+  fn health_socket_ok(&self) -> bool {
+    let socket = self.health_socket.lock().ok().and_then(|socket| socket.clone());
+    socket.is_some_and(|path| path.exists() && http::socket_health(&path, Duration::from_secs(2)))
+  }
+}
+
+#[derive(Clone, serde::Serialize)]
+struct ConfirmKillAdopted {
+  port: u16,
+}
+
+/// How long to wait after spawning for the backend to start answering.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Ports scanned for a backend that ignored TALUS_PORT (older versions bind 5000).
+const PORT_SCAN_RANGE: std::ops::RangeInclusive<u16> = 5000..=5010;
+
+#[derive(Clone, serde::Serialize)]
+struct PortMismatch {
+  expected_port: u16,
+  actual_port: u16,
+  warning: String,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct BackendReady {
+  port: u16,
+  mode: &'static str,
+  duration_ms: u64,
+}
+
+/// One candidate start_backend considered. `result` serializes as
+/// `{"Ok": null}` or `{"Err": "..."}`.
+#[derive(Clone, serde::Serialize)]
+struct SpawnAttempt {
+  path: PathBuf,
+  mode: &'static str,
+  result: Result<(), String>,
+}
+
+fn record_attempt(backend: &BackendState, path: impl Into<PathBuf>, mode: &'static str, result: Result<(), String>) {
+  if let Ok(mut attempts) = backend.spawn_attempts.lock() {
+    attempts.push(SpawnAttempt { path: path.into(), mode, result });
+  }
+}
+
+/// Mark the spawned (last) attempt as failed once it never became ready.
+fn fail_last_attempt(backend: &BackendState, error: &str) {
+  if let Some(attempt) = backend.spawn_attempts.lock().ok().as_mut().and_then(|attempts| attempts.last_mut()) {
+    attempt.result = Err(error.to_string());
+  }
+}
+
+#[derive(Clone, serde::Serialize)]
+struct StartFailed {
+  error: String,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct PortChanged {
+  old_port: u16,
+  new_port: u16,
+}
+
+/// Argument the login autostart entry passes so boot launches don't pop a window.
+const AUTOSTART_FLAG: &str = "--minimized";
+
+/// Launch without spawning the backend so settings can be fixed when it crash-loops.
+const SAFE_MODE_FLAG: &str = "--safe-mode";
+
+/// Set when a stuck startup dropped this instance into safe mode.
+static SAFE_MODE_ENTERED: AtomicBool = AtomicBool::new(false);
+
+fn safe_mode() -> bool {
+  SAFE_MODE_ENTERED.load(Ordering::SeqCst) || std::env::args().any(|arg| arg == SAFE_MODE_FLAG)
+}
+
+/// Switch this instance to safe mode without relaunching: the backend stays
+/// down until the user starts it (restart_backend).
+fn enter_safe_mode(app: &tauri::AppHandle, backend: &BackendState, reason: String) {
+  SAFE_MODE_ENTERED.store(true, Ordering::SeqCst);
+  events::emit_lifecycle(app, backend, "backend://safe-mode", SafeMode { reason: Some(reason) });
+}
+
+/// Start a new instance in safe mode and exit this one.
+fn relaunch_in_safe_mode(app: &tauri::AppHandle) {
+  match std::env::current_exe().and_then(|exe| Command::new(exe).arg(SAFE_MODE_FLAG).spawn()) {
+    Ok(_) => {
+      diag("Relaunching in safe mode");
+      app.exit(0);
+    }
+    Err(err) => diag(&format!("Failed to relaunch in safe mode: {}", err)),
+  }
+}
+
+#[derive(Clone, serde::Serialize)]
+struct SafeMode {
+  /// Why safe mode was entered; `None` when launched with --safe-mode.
+  reason: Option<String>,
+}
+
+/// Emitted to the window when the user tries to close it before the frontend
+/// has confirmed (close stays prevented). The frontend answers with
+/// `close_window`, `exit_app` or `force_close_window`. Deliberately not in the
+/// `tauri://` namespace so it can't collide with Tauri's own close event.
+const CLOSE_REQUESTED_EVENT: &str = "app://close-requested";
+
 struct CloseState(Arc<Mutex<bool>>);
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-  let backend_process: Arc<Mutex<Option<Child>>> = Arc::new(Mutex::new(None));
-  let backend_process_clone = backend_process.clone();
-  let backend_process_state = backend_process.clone();
-  let backend_process_runloop = backend_process.clone();
+  let backend = BackendState {
+    process: Arc::new(Mutex::new(None)),
+    adopted: Arc::new(AtomicBool::new(false)),
+    host: Arc::new(Mutex::new(config::DEFAULT_HOST.to_string())),
+    port: Arc::new(AtomicU16::new(config::DEFAULT_PORT)),
+    resolved_addr: Arc::new(Mutex::new(None)),
+    port_override: Arc::new(AtomicU16::new(0)),
+    mode: Arc::new(Mutex::new(None)),
+    event_seq: Arc::new(AtomicU64::new(0)),
+    paused: Arc::new(AtomicBool::new(false)),
+    last_events: Arc::new(Mutex::new(BTreeMap::new())),
+    profile: Arc::new(Mutex::new(None)),
+    health_socket: Arc::new(Mutex::new(None)),
+    fallback_reason: Arc::new(Mutex::new(None)),
+    spawn_attempts: Arc::new(Mutex::new(Vec::new())),
+    capabilities: Arc::new(Mutex::new(None)),
+    starting: Arc::new(AtomicBool::new(false)),
+    recovery_attempts: Arc::new(AtomicU32::new(0)),
+    recovery_exhausted: Arc::new(AtomicBool::new(false)),
+    app_started: Instant::now(),
+    backend_since: Arc::new(Mutex::new(None)),
+    restart_count: Arc::new(AtomicU32::new(0)),
+    crash_count: Arc::new(AtomicU32::new(0)),
+    startup_stage: Arc::new(Mutex::new(None)),
+    startup_aborted: Arc::new(AtomicBool::new(false)),
+    restart_in_progress: Arc::new(AtomicBool::new(false)),
+  };
+  let backend_clone = backend.clone();
+  let backend_runloop = backend.clone();
   let close_allowed: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
   let close_allowed_state = close_allowed.clone();
   let close_allowed_window = close_allowed.clone();
 
   tauri::Builder::default()
+    // Must be first: a second launch (e.g. from a deep link) hands its args to us and exits.
+    .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+      if let Some(window) = main_window(app) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+      }
+    }))
+    .plugin(tauri_plugin_deep_link::init())
     .plugin(tauri_plugin_dialog::init())
     .plugin(tauri_plugin_fs::init())
     .plugin(tauri_plugin_opener::init())
-    .manage(BackendState(backend_process_state))
+    .plugin(shortcut::plugin())
+    .plugin(tauri_plugin_notification::init())
+    .plugin(tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, Some(vec![AUTOSTART_FLAG])))
+    .manage(backend)
     .manage(CloseState(close_allowed_state))
+    .manage(data_watch::DataWatchState::default())
+    .manage(window::ZoomState::default())
+    .manage(export::ExportState::default())
+    .manage(tray::TrayState::default())
+    .manage(webview::WebviewProbeState::default())
+    .manage(deeplink::DeepLinkState::default())
+    .manage(keep_awake::KeepAwakeState::default())
+    .manage(sandbox::SandboxState::default())
+    .manage(idle::IdleState::default())
+    .manage(updates::UpdateState::default())
+    .manage(frontend_url::FrontendUrlState::default())
     .setup(move |app| {
-      if cfg!(debug_assertions) {
-        app.handle().plugin(
-          tauri_plugin_log::Builder::default()
-            .level(log::LevelFilter::Info)
-            .build(),
-        )?;
+      ipc_trace::init();
+      app.handle().plugin(
+        tauri_plugin_log::Builder::default()
+          .level(log_level())
+          .build(),
+      )?;
+
+
+      app.manage(ConfigState(Mutex::new(config::load(app.handle()))));
+      log_buffer::init(app.handle());
+      shortcut::register_configured(app.handle());
+      window::apply_saved_settings(app.handle());
+      frontend_url::apply_env(app.handle());
+      monitors::watch(app.handle());
+      // Desktops without a tray host (some Linux setups) just run without the icon.
+      if let Err(err) = tray::create(app.handle()) {
+        diag(&format!("Failed to create tray icon: {}", err));
       }
 
+      deeplink::init(app.handle());
+
+      if std::env::args().any(|arg| arg == window::RESET_WINDOW_FLAG) {
+        diag("Launched with --reset-window; resetting main window");
+        if let Err(err) = window::reset_main_window(app.handle()) {
+          diag(&format!("Failed to reset main window: {}", err));
+        }
+      }
+
+      if std::env::args().any(|arg| arg == AUTOSTART_FLAG) {
+        diag("Launched with --minimized (autostart); starting minimized");
+        if let Some(window) = main_window(app.handle()) {
+          let _ = window.minimize();
+        }
+      }
+
+      if safe_mode() {
+        diag("Launched with --safe-mode; not starting the backend");
+        events::emit_lifecycle(app.handle(), &backend_clone, "backend://safe-mode", SafeMode { reason: None });
+        return Ok(());
+      }
+
+      if !config::in_memory() {
+        migration::migrate_legacy_data(app.handle());
+      }
 
       // Start Python backend on app launch
       let app_handle = app.handle().clone();
-      let backend_process_setup = backend_process_clone.clone();
+      let backend_setup = backend_clone.clone();
       std::thread::spawn(move || {
-        start_backend(backend_process_setup, app_handle);
+        start_backend(backend_setup, app_handle);
       });
+      supervisor::spawn(app.handle().clone(), backend_clone.clone());
+      startup::spawn_monitor(app.handle().clone(), backend_clone.clone());
+      heartbeat::spawn(app.handle().clone(), backend_clone.clone());
+      idle::spawn(app.handle().clone(), backend_clone.clone());
+      thermal::spawn(app.handle().clone(), backend_clone.clone());
+      backup::spawn(app.handle().clone(), backend_clone.clone());
+      main_thread::spawn(app.handle().clone());
+      webview::spawn_crash_watch(app.handle().clone());
 
       Ok(())
     })
-    .invoke_handler(tauri::generate_handler![backend_status, minimize_window, maximize_window, close_window, exit_app, force_close_window])
+    .invoke_handler(ipc_trace::traced(tauri::generate_handler![
+      backend_status,
+      get_backend_port,
+      get_backend_info,
+      get_session_stats,
+      build_info::get_build_info,
+      is_port_available,
+      wait_for_backend,
+      is_packaged,
+      is_safe_mode,
+      is_inmemory,
+      restart_in_safe_mode,
+      minimize_window,
+      maximize_window,
+      show_window,
+      hide_window,
+      get_autostart,
+      set_autostart,
+      clear_app_cache,
+      close_window,
+      exit_app,
+      force_close_window,
+      force_kill_adopted,
+      stop_backend,
+      restart_backend,
+      set_backend_workers,
+      get_backend_workers,
+      config::open_config_file,
+      config::reload_config,
+      config::get_poll_interval,
+      config::set_poll_interval,
+      heartbeat::get_heartbeat_interval,
+      heartbeat::set_heartbeat_interval,
+      config::get_effective_config,
+      config::export_config,
+      config::import_config,
+      config_check::validate_config,
+      config_check::apply_config,
+      startup_history::get_startup_history,
+      profiles::list_profiles,
+      profiles::save_profile,
+      profiles::delete_profile,
+      profiles::switch_profile,
+      venv::repair_venv,
+      venv::set_python_path,
+      data_watch::note_local_write,
+      data_watch::set_data_watch,
+      diagnostics::test_connectivity,
+      diagnostics::run_self_test,
+      diagnostics::rotate_logs,
+      log_follow::follow_log_file,
+      log_follow::unfollow_log_file,
+      log_buffer::get_backend_log,
+      backend_errors::get_backend_errors,
+      log_buffer::get_log_buffer_size,
+      log_buffer::set_log_buffer_size,
+      diagnostics::get_backend_connection_count,
+      diagnostics::benchmark_tally_write,
+      integrity::verify_bundle_integrity,
+      database::import_database,
+      schema::check_schema_compatibility,
+      schema::run_schema_migration,
+      priority::set_backend_priority,
+      thermal::get_thermal_state,
+      keep_awake::set_keep_awake,
+      keep_awake::get_keep_awake,
+      sandbox::start_sandbox_backend,
+      sandbox::discard_sandbox,
+      backup::get_backup_schedule,
+      backup::set_backup_schedule,
+      backup::list_backups,
+      main_thread::get_main_thread_stalls,
+      backend_api::pause_backend,
+      backend_api::resume_backend,
+      idle::set_idle_timeout,
+      idle::report_activity,
+      backend_api::proxy_request,
+      backend_api::trim_backend_memory,
+      backend_api::get_backend_capabilities,
+      backend_api::run_backend_setup,
+      backend_api::simulate_backend_crash,
+      workers::list_backend_workers,
+      workers::kill_backend_worker,
+      window::set_resizable,
+      window::set_zoom,
+      window::reset_window_state,
+      window::get_window_capabilities,
+      webview::check_webview_health,
+      webview::report_webview_probe,
+      webview::report_webview_alive,
+      webview::recover_webview,
+      frontend_url::set_frontend_url,
+      window::set_accent_color,
+      window::get_accent_color,
+      window::list_layout_presets,
+      window::save_layout_preset,
+      window::apply_layout_preset,
+      window::delete_layout_preset,
+      clock::check_clock,
+      export::export_tally,
+      export::cancel_export,
+      export::export_snapshot,
+      updates::set_update_transport,
+      updates::get_update_transport,
+      updates::get_update_batch_window,
+      updates::set_update_batch_window,
+      tray::minimize_all_to_tray,
+      tray::get_keep_in_tray,
+      tray::set_keep_in_tray,
+      permissions::check_data_permissions,
+      permissions::fix_data_permissions,
+      permissions::check_writable_paths,
+      events::get_last_events,
+      ipc_trace::set_ipc_trace,
+      monitors::list_monitors,
+      monitors::move_window_to_monitor,
+      network::get_network_info,
+      network::refresh_backend_host,
+      supervisor::reset_recovery,
+      supervisor::reconnect_backend,
+      startup::abort_startup,
+      shortcut::set_global_shortcut,
+      toggle_devtools,
+    ]))
     .on_window_event(move |window, event| {
-      match event {
-        tauri::WindowEvent::CloseRequested { api, .. } => {
-          let allow_close = close_allowed_window
-            .lock()
-            .map(|flag| *flag)
-            .unwrap_or(false);
+      if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+        let allow_close = close_allowed_window
+          .lock()
+          .map(|flag| *flag)
+          .unwrap_or(false);
 
-          if allow_close {
+        if allow_close {
+          if take_over_last_close(window) {
+            api.prevent_close();
+          } else {
             diag("Close requested while close_allowed=true; allowing close to proceed");
-            return;
           }
-
-          diag("Close requested while close_allowed=false; preventing close and notifying frontend");
-          api.prevent_close();
-          let _ = window.emit("talus://close-requested", ());
+          return;
         }
-        _ => {}
+
+        diag("Close requested while close_allowed=false; preventing close and notifying frontend");
+        api.prevent_close();
+        events::emit(window, CLOSE_REQUESTED_EVENT, ());
       }
     })
     .build(tauri::generate_context!())
     .expect("error while building tauri application")
-    .run(move |_app_handle, event| {
+    .run(move |app_handle, event| {
       match event {
         tauri::RunEvent::Exit => {
-          terminate_backend_process(&backend_process_runloop, "run-event exit");
+          terminate_backend_process(&backend_runloop.process, "run-event exit", config::shutdown_timeout(app_handle));
+          profiler::finish(app_handle, &backend_runloop);
+          data_lock::release();
+          keep_awake::release_all(&app_handle.state::<keep_awake::KeepAwakeState>());
+          sandbox::discard_all(&app_handle.state::<sandbox::SandboxState>());
+        }
+        tauri::RunEvent::ExitRequested { code: None, api, .. } if webview::take_recreating() => {
+          // The last window closed only because recover_webview is rebuilding it
+          api.prevent_exit();
         }
         tauri::RunEvent::ExitRequested { .. } => {
-          terminate_backend_process(&backend_process_runloop, "run-event exit requested");
+          terminate_backend_process(&backend_runloop.process, "run-event exit requested", config::shutdown_timeout(app_handle));
+          profiler::finish(app_handle, &backend_runloop);
+          data_lock::release();
+          keep_awake::release_all(&app_handle.state::<keep_awake::KeepAwakeState>());
+          sandbox::discard_all(&app_handle.state::<sandbox::SandboxState>());
         }
         _ => {}
       }
     });
 }
 
-fn start_backend(backend_process: Arc<Mutex<Option<Child>>>, app_handle: tauri::AppHandle) {
+fn start_backend(backend: BackendState, app_handle: tauri::AppHandle) {
+  let _starting = supervisor::Starting::new(&backend.starting);
+  let _stages = startup::Tracker::new(&backend);
   diag("=== Backend launch sequence starting ===");
-  let talus_env = std::env::var("TALUS_ENV").unwrap_or_else(|_| {
+  diag(&build_info::summary());
+  let settings = config::current(&app_handle);
+  let talus_env = std::env::var("TALUS_ENV").ok().or(settings.talus_env).unwrap_or_else(|| {
     if cfg!(debug_assertions) {
       "development".to_string()
     } else {
@@ -158,56 +752,165 @@ fn start_backend(backend_process: Arc<Mutex<Option<Child>>>, app_handle: tauri::
   });
   diag(&format!("TALUS_ENV for backend launch: {}", talus_env));
 
-  // Kill any existing backend process first to ensure clean state
-  diag("Checking for existing backend processes...");
-  #[cfg(target_os = "linux")]
-  {
-    let _ = Command::new("pkill")
-      .args(&["-f", "python.*backend.app"])
-      .output();
-    diag("Killed any existing backend processes (Linux)");
+  let port = match backend.port_override.load(Ordering::SeqCst) {
+    0 => config::backend_port(&app_handle),
+    port => port,
+  };
+  let previous_port = backend.port.swap(port, Ordering::SeqCst);
+  diag(&format!("Backend port: {}", port));
+  if previous_port != port {
+    diag(&format!("Backend port changed {} -> {}; notifying frontend", previous_port, port));
+    events::emit_lifecycle(&app_handle, &backend, "backend://port-changed", PortChanged { old_port: previous_port, new_port: port });
   }
 
-  #[cfg(target_os = "macos")]
-  {
-    let _ = Command::new("pkill")
-      .args(&["-f", "python.*backend.app"])
-      .output();
-    diag("Killed any existing backend processes (macOS)");
+  let host = config::backend_host(&app_handle);
+  if let Ok(mut current) = backend.host.lock() {
+    *current = host.clone();
+  }
+  if let Ok(mut resolved) = backend.resolved_addr.lock() {
+    *resolved = None;
+  }
+  if let Ok(mut socket) = backend.health_socket.lock() {
+    *socket = None;
+  }
+  if let Ok(mut reason) = backend.fallback_reason.lock() {
+    *reason = None;
+  }
+  if let Ok(mut capabilities) = backend.capabilities.lock() {
+    *capabilities = None;
   }
 
-  #[cfg(target_os = "windows")]
-  {
-    for image in ["python.exe", "talus-tally-backend.exe"] {
-      let mut taskkill_cmd = Command::new("taskkill");
-      taskkill_cmd
-        .args(&["/F", "/IM", image])
-        .creation_flags(0x08000000);
-      let _ = taskkill_cmd.output();
+  // We don't manage processes on another machine; just report whether it answers
+  if !config::is_loopback(&host) {
+    diag(&format!("Remote backend configured at {}:{}; not spawning or killing", host, port));
+    set_mode(&backend, Some("remote"));
+    let addr = match network::resolve(&host, port) {
+      Ok(addr) => addr,
+      Err(error) => {
+        diag(&error);
+        events::emit_lifecycle(&app_handle, &backend, "backend://start-failed", StartFailed { error });
+        return;
+      }
+    };
+    if let Ok(mut resolved) = backend.resolved_addr.lock() {
+      *resolved = Some(addr);
+    }
+    if addr_reachable(&addr) {
+      events::emit_lifecycle(&app_handle, &backend, "backend://ready", BackendReady { port, mode: "remote", duration_ms: 0 });
+    } else {
+      let error = format!("Remote backend {}:{} is not reachable", host, port);
+      diag(&error);
+      events::emit_lifecycle(&app_handle, &backend, "backend://start-failed", StartFailed { error });
     }
-    diag("Killed any existing backend processes (Windows)");
+    return;
+  }
+
+  // With TALUS_ADOPT_BACKEND (or the config setting) on, reuse a backend that is already answering
+  // instead of killing it. We did not spawn it, so we must not kill it on quit.
+  let adopt_existing = env_flag("TALUS_ADOPT_BACKEND") || settings.adopt_existing_backend;
+  if adopt_existing && backend_reachable(&host, port) {
+    diag(&format!("Existing backend is answering on :{}; adopting it instead of spawning", port));
+    backend.adopted.store(true, Ordering::SeqCst);
+    set_mode(&backend, Some("adopted"));
+    return;
+  }
+
+  // A second instance on the same data dir would corrupt the database
+  if let Err(in_use) = data_lock::acquire(&config::data_dir(&app_handle)) {
+    let error = format!(
+      "Data directory is in use by another Talus instance (PID {}, lock {})",
+      in_use.pid,
+      in_use.lock_path.display()
+    );
+    diag(&error);
+    events::emit(&app_handle, "data://already-in-use", in_use);
+    events::emit_lifecycle(&app_handle, &backend, "backend://start-failed", StartFailed { error });
+    return;
   }
 
+  // Kill any existing backend process first to ensure clean state
+  startup::enter(&app_handle, &backend, startup::Stage::Cleanup);
+  diag("Checking for existing backend processes...");
+  kill_stray_backend(port);
+
   // Wait for port to be released
-  std::thread::sleep(std::time::Duration::from_millis(1000));
+  std::thread::sleep(Duration::from_millis(1000));
+  if startup::aborted(&backend) {
+    return;
+  }
+  wait_for_port_release(&app_handle, &backend, port);
+  if startup::aborted(&backend) {
+    return;
+  }
+  startup::enter(&app_handle, &backend, startup::Stage::Spawn);
 
   // Determine project root - handle both development and installed locations
   let project_root = determine_project_root(Some(&app_handle));
   diag(&format!("Project root: {}", project_root.display()));
 
+  // The code root may be read-only (/opt/talus-tally); all writes go to the data dir
+  let data_dir = config::data_dir(&app_handle);
+  if let Err(error) = ensure_writable_dir(&data_dir) {
+    diag(&format!("FAILED to prepare data directory: {}", error));
+    events::emit_lifecycle(&app_handle, &backend, "backend://start-failed", StartFailed { error });
+    return;
+  }
+  diag(&format!("Data directory: {}", data_dir.display()));
+  data_watch::watch(&app_handle, &data_dir);
+
+  let health_socket = health_socket_path(&data_dir);
+  if let Some(path) = &health_socket {
+    let _ = std::fs::remove_file(path);
+    diag(&format!("Health socket: {}", path.display()));
+  }
+  let health_socket_env = health_socket.clone().unwrap_or_default();
+  let in_memory_env = if config::in_memory() { "1" } else { "0" };
+  let workers_env = settings.backend_workers.map(|n| ("TALUS_WORKERS", n.to_string()));
+  let stack_dump_env = stack_dump::dump_file().unwrap_or_default();
+  if let Some(parent) = stack_dump_env.parent() {
+    let _ = std::fs::create_dir_all(parent);
+  }
+  if config::in_memory() {
+    diag("TALUS_INMEMORY is set; backend data will not be saved");
+  }
+
   let packaged_backend = find_packaged_backend(Some(&app_handle), &project_root);
   diag(&format!("Packaged backend: {:?}", packaged_backend.as_ref().map(|p| p.display().to_string())));
+  // Falling back to a system Python the install never needed would only hide this
+  if packaged_backend.is_none() {
+    if let Some(quarantined) = integrity::quarantined_binary(&app_handle, &project_root) {
+      let error = format!("Backend binary missing from the install; {}", quarantined.guidance);
+      diag(&error);
+      record_attempt(&backend, &quarantined.expected_path, "packaged", Err("quarantined".to_string()));
+      events::emit_lifecycle(&app_handle, &backend, "backend://binary-quarantined", quarantined);
+      events::emit_lifecycle(&app_handle, &backend, "backend://start-failed", StartFailed { error });
+      return;
+    }
+  }
 
-  // Platform-aware venv python path
-  let venv_python = if cfg!(target_os = "windows") {
-    project_root.join(".venv").join("Scripts").join("python.exe")
-  } else {
-    project_root.join(".venv").join("bin").join("python3")
-  };
+  let custom_python = config::python_path(&app_handle);
+  diag(&format!("Configured python: {:?}", custom_python.as_ref().map(|p| p.display().to_string())));
+
+  let venv_python = venv_python_path(&project_root);
   diag(&format!("Venv python candidate: {} (exists={})", venv_python.display(), venv_python.exists()));
 
-  let spawn_result = if let Some(binary_path) = packaged_backend {
+  if let Ok(mut attempts) = backend.spawn_attempts.lock() {
+    attempts.clear();
+  }
+  if packaged_backend.is_none() {
+    record_attempt(&backend, backend_binary_name(), "packaged", Err("not found".to_string()));
+    if custom_python.is_none() && !venv_python.exists() {
+      record_attempt(&backend, &venv_python, "venv", Err("not found".to_string()));
+    }
+  }
+
+  let profiler = profiler::requested();
+  let profile_output = profiler.map(|p| profiler::output_path(&data_dir, p));
+  let python_args = profiler::python_args(profiler, profile_output.as_deref().unwrap_or(Path::new("")));
+
+  let (mode, fallback_reason, spawn_path, spawn_result) = if let Some(binary_path) = packaged_backend {
     diag(&format!("Starting packaged backend binary at {}", binary_path.display()));
+    let binary_path = long_path(&binary_path);
     let working_dir = binary_path.parent().unwrap_or(&project_root);
     diag(&format!("Working directory: {}", working_dir.display()));
 
@@ -215,6 +918,13 @@ fn start_backend(backend_process: Arc<Mutex<Option<Child>>>, app_handle: tauri::
     command
       .env("TALUS_DAEMON", "1")
       .env("TALUS_ENV", &talus_env)
+      .env("TALUS_PORT", port.to_string())
+      .env("TALUS_DATA_DIR", &data_dir)
+      .env("TALUS_HEALTH_SOCKET", &health_socket_env)
+      .env("TALUS_INMEMORY", in_memory_env)
+      .envs(workers_env.clone())
+      .env("TALUS_STACK_DUMP", &stack_dump_env)
+      .stderr(Stdio::piped())
       .current_dir(working_dir);
 
     #[cfg(target_os = "windows")]
@@ -223,43 +933,416 @@ fn start_backend(backend_process: Arc<Mutex<Option<Child>>>, app_handle: tauri::
       command.creation_flags(0x08000000);
     }
 
-    command.spawn()
+    ("packaged", None, binary_path.clone(), command.spawn())
+  } else if let Some(python) = custom_python {
+    diag(&format!("Starting backend via configured Python at {}", python.display()));
+    let reason = format!(
+      "Used configured Python {} because no packaged backend ({}) was found under {}",
+      python.display(),
+      backend_binary_name(),
+      project_root.display()
+    );
+    let spawn_result = Command::new(long_path(&python))
+      .args(&python_args)
+      .env("TALUS_DAEMON", "1")
+      .env("TALUS_ENV", &talus_env)
+      .env("TALUS_PORT", port.to_string())
+      .env("TALUS_DATA_DIR", &data_dir)
+      .env("TALUS_HEALTH_SOCKET", &health_socket_env)
+      .env("TALUS_INMEMORY", in_memory_env)
+      .envs(workers_env.clone())
+      .env("TALUS_STACK_DUMP", &stack_dump_env)
+      .stderr(Stdio::piped())
+      .current_dir(&project_root)
+      .spawn();
+    ("custom", Some(reason), python, spawn_result)
   } else if venv_python.exists() {
     diag(&format!("Starting backend via virtualenv Python at {}", venv_python.display()));
-    Command::new(&venv_python)
-      .args(["-m", "backend.app"])
+    let reason = format!(
+      "Used virtualenv Python because no packaged backend ({}) was found under {}",
+      backend_binary_name(),
+      project_root.display()
+    );
+    let spawn_result = Command::new(long_path(&venv_python))
+      .args(&python_args)
       .env("TALUS_DAEMON", "1")
       .env("TALUS_ENV", &talus_env)
+      .env("TALUS_PORT", port.to_string())
+      .env("TALUS_DATA_DIR", &data_dir)
+      .env("TALUS_HEALTH_SOCKET", &health_socket_env)
+      .env("TALUS_INMEMORY", in_memory_env)
+      .envs(workers_env.clone())
+      .env("TALUS_STACK_DUMP", &stack_dump_env)
+      .stderr(Stdio::piped())
       .current_dir(&project_root)
-      .spawn()
+      .spawn();
+    ("venv", Some(reason), venv_python.clone(), spawn_result)
   } else {
     // Platform-aware system python fallback
     let python_cmd = if cfg!(target_os = "windows") { "python" } else { "python3" };
     diag(&format!("Virtualenv not found, falling back to system {}", python_cmd));
-    Command::new(python_cmd)
-      .args(["-m", "backend.app"])
+    let reason = format!(
+      "Used system {} because no packaged backend was found and {} does not exist",
+      python_cmd,
+      venv_python.display()
+    );
+    let spawn_result = Command::new(python_cmd)
+      .args(&python_args)
       .env("TALUS_DAEMON", "1")
       .env("TALUS_ENV", &talus_env)
+      .env("TALUS_PORT", port.to_string())
+      .env("TALUS_DATA_DIR", &data_dir)
+      .env("TALUS_HEALTH_SOCKET", &health_socket_env)
+      .env("TALUS_INMEMORY", in_memory_env)
+      .envs(workers_env.clone())
+      .env("TALUS_STACK_DUMP", &stack_dump_env)
+      .stderr(Stdio::piped())
       .current_dir(&project_root)
-      .spawn()
+      .spawn();
+    ("system", Some(reason), PathBuf::from(python_cmd), spawn_result)
   };
 
+  record_attempt(&backend, spawn_path, mode, spawn_result.as_ref().map(|_| ()).map_err(|e| e.to_string()));
   match spawn_result {
-    Ok(child) => {
-      if let Ok(mut proc) = backend_process.lock() {
+    Ok(mut child) if startup::aborted(&backend) => {
+      diag("Startup was aborted while spawning; killing the new backend");
+      let _ = child.kill();
+      let _ = child.wait();
+    }
+    Ok(mut child) => {
+      let spawned_at = Instant::now();
+      if let Some(stderr) = child.stderr.take() {
+        log_buffer::watch_stderr(&app_handle, stderr);
+      }
+      if let (Some(profiler), Some(output)) = (profiler, profile_output) {
+        if mode == "packaged" && profiler == profiler::Profiler::CProfile {
+          diag("TALUS_PROFILE=cprofile can't wrap the packaged backend binary; use py-spy instead");
+        } else {
+          profiler::begin(&backend, profiler, output, child.id());
+        }
+      }
+      if let Ok(mut proc) = backend.process.lock() {
         *proc = Some(child);
         diag("Backend started successfully");
       }
+      set_mode(&backend, Some(mode));
+      if let Some(reason) = &fallback_reason {
+        diag(&format!("Spawn fallback: {}", reason));
+      }
+      if let Ok(mut stored) = backend.fallback_reason.lock() {
+        *stored = fallback_reason;
+      }
+      backend.paused.store(false, Ordering::SeqCst);
+      if let Ok(mut socket) = backend.health_socket.lock() {
+        *socket = health_socket;
+      }
+      await_backend_ready(&backend, &app_handle, port, mode, spawned_at);
     }
     Err(e) => {
       diag(&format!("FAILED to start Python backend: {}", e));
       diag(&format!("  Project root: {}", project_root.display()));
       diag(&format!("  Venv python: {} (exists={})", venv_python.display(), venv_python.exists()));
+      events::emit_lifecycle(&app_handle, &backend, "backend://start-failed", StartFailed { error: e.to_string() });
+    }
+  }
+}
+
+/// Unix sockets have a ~104-byte path limit (sun_path), so the socket is
+/// skipped for deep data dirs and health checks stay on TCP.
+const MAX_SOCKET_PATH: usize = 100;
+
+/// Where the backend should listen for socket health checks, or `None` where
+/// Unix domain sockets aren't available or the path would be too long.
+fn health_socket_path(data_dir: &Path) -> Option<PathBuf> {
+  if !cfg!(unix) {
+    return None;
+  }
+  let path = data_dir.join("backend-health.sock");
+  (path.as_os_str().len() <= MAX_SOCKET_PATH).then_some(path)
+}
+
+/// Create `dir` if needed and prove we can write into it.
+fn ensure_writable_dir(dir: &Path) -> Result<(), String> {
+  std::fs::create_dir_all(dir)
+    .map_err(|e| format!("Cannot create data directory {}: {}", dir.display(), e))?;
+  let probe = dir.join(".talus-write-test");
+  std::fs::write(&probe, b"ok")
+    .map_err(|e| format!("Data directory {} is not writable: {}", dir.display(), e))?;
+  let _ = std::fs::remove_file(&probe);
+  Ok(())
+}
+
+/// Poll until the freshly spawned backend answers, warm it up, then emit
+/// `backend://ready` and record the startup duration. Emits `backend://start-failed` if the
+/// process exits first or STARTUP_TIMEOUT passes.
+fn await_backend_ready(backend: &BackendState, app_handle: &tauri::AppHandle, port: u16, mode: &'static str, spawned_at: Instant) {
+  startup::enter(app_handle, backend, startup::Stage::Health);
+  while spawned_at.elapsed() < STARTUP_TIMEOUT {
+    if startup::aborted(backend) {
+      return;
+    }
+    if backend.health_socket_ok() || backend_reachable(config::DEFAULT_HOST, port) {
+      mark_ready(backend, app_handle, port, mode, spawned_at);
+      return;
     }
+
+    let exit_status = backend
+      .process
+      .lock()
+      .ok()
+      .and_then(|mut proc| proc.as_mut().and_then(|child| child.try_wait().ok().flatten()));
+    if let Some(status) = exit_status {
+      let error = format!("Backend exited during startup with status {}", status);
+      diag(&error);
+      fail_last_attempt(backend, &error);
+      events::emit_lifecycle(app_handle, backend, "backend://start-failed", StartFailed { error });
+      return;
+    }
+
+    std::thread::sleep(Duration::from_millis(100));
+  }
+
+  if startup::aborted(backend) {
+    return;
+  }
+  // An older backend may have ignored TALUS_PORT and bound somewhere else
+  if let Some(actual_port) = find_backend_on_other_port(port) {
+    let warning = format!(
+      "Backend is answering on :{} instead of the expected :{}; it may be an older version that ignores TALUS_PORT",
+      actual_port, port
+    );
+    diag(&warning);
+    backend.port.store(actual_port, Ordering::SeqCst);
+    events::emit_lifecycle(app_handle, backend, "backend://port-mismatch", PortMismatch { expected_port: port, actual_port, warning });
+    events::emit_lifecycle(app_handle, backend, "backend://port-changed", PortChanged { old_port: port, new_port: actual_port });
+    mark_ready(backend, app_handle, actual_port, mode, spawned_at);
+    return;
   }
+
+  let error = format!("Backend did not answer on :{} within {:?}", port, STARTUP_TIMEOUT);
+  diag(&error);
+  fail_last_attempt(backend, &error);
+  if retry_on_free_port(app_handle, backend, port) {
+    return;
+  }
+  events::emit_lifecycle(app_handle, backend, "backend://start-failed", StartFailed { error });
 }
 
+/// Everything that follows the backend answering on `port`, whichever way it
+/// was found: warm-up, the startup history entry, `backend://ready` and the
+/// first-run setup check.
+fn mark_ready(backend: &BackendState, app_handle: &tauri::AppHandle, port: u16, mode: &'static str, spawned_at: Instant) {
+  startup::enter(app_handle, backend, startup::Stage::Warmup);
+  warm_up(port);
+  if startup::aborted(backend) {
+    return;
+  }
+  let duration_ms = spawned_at.elapsed().as_millis() as u64;
+  diag(&format!("Backend ready on :{} after {}ms (mode={})", port, duration_ms, mode));
+  startup_history::record(mode, duration_ms);
+  events::emit_lifecycle(app_handle, backend, "backend://ready", BackendReady { port, mode, duration_ms });
+  backend_api::check_needs_setup(app_handle, backend);
+}
+
+/// How long the warm-up request may take before we report ready anyway.
+const WARMUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Issue one representative request so the first user action doesn't pay for
+/// lazy imports and template scanning. Failures are logged, not fatal: the
+/// backend is already answering. TALUS_SKIP_WARMUP=1 skips it.
+fn warm_up(port: u16) {
+  if env_flag("TALUS_SKIP_WARMUP") {
+    return;
+  }
+  let started = Instant::now();
+  match http::get(config::DEFAULT_HOST, port, "/api/v1/warmup", WARMUP_TIMEOUT) {
+    Ok(response) if response.is_success() => diag(&format!("Backend warm-up finished in {}ms", started.elapsed().as_millis())),
+    Ok(response) if response.status == 404 => diag("Backend has no /api/v1/warmup endpoint; skipping warm-up"),
+    Ok(response) => diag(&format!("Backend warm-up returned HTTP {}", response.status)),
+    Err(err) => diag(&format!("Backend warm-up failed: {}", err)),
+  }
+}
+
+#[derive(Clone, serde::Serialize)]
+struct PortBlocked {
+  blocked_port: u16,
+  new_port: u16,
+}
+
+/// Longest we wait for a killed backend's port to come free.
+const PORT_RELEASE_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, serde::Serialize)]
+struct WaitingForPort {
+  port: u16,
+  timeout_ms: u64,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct PortReleased {
+  port: u16,
+  waited_ms: u64,
+}
+
+/// After a hard kill (notably on Windows) the port can stay unbindable for
+/// tens of seconds with nothing listening on it, and a backend started then
+/// fails to bind. When the port can't be bound and nothing answers there,
+/// emit `backend://waiting-for-port` and wait (up to PORT_RELEASE_TIMEOUT)
+/// for the OS to release it, then `backend://port-released`. A port held by
+/// a live listener is left to the normal startup path.
+fn wait_for_port_release(app_handle: &tauri::AppHandle, backend: &BackendState, port: u16) {
+  let bindable = || TcpListener::bind((config::DEFAULT_HOST, port)).is_ok();
+  if bindable() || backend_reachable(config::DEFAULT_HOST, port) {
+    return;
+  }
+  startup::enter(app_handle, backend, startup::Stage::Port);
+  diag(&format!("Port {} is not yet released by a previous backend; waiting up to {:?}", port, PORT_RELEASE_TIMEOUT));
+  events::emit_lifecycle(
+    app_handle,
+    backend,
+    "backend://waiting-for-port",
+    WaitingForPort { port, timeout_ms: PORT_RELEASE_TIMEOUT.as_millis() as u64 },
+  );
+  let started = Instant::now();
+  while started.elapsed() < PORT_RELEASE_TIMEOUT && !startup::aborted(backend) {
+    std::thread::sleep(Duration::from_millis(250));
+    if bindable() {
+      let waited_ms = started.elapsed().as_millis() as u64;
+      diag(&format!("Port {} released after {}ms", port, waited_ms));
+      events::emit_lifecycle(app_handle, backend, "backend://port-released", PortReleased { port, waited_ms });
+      return;
+    }
+  }
+  diag(&format!("Port {} still not released after {:?}; starting anyway", port, PORT_RELEASE_TIMEOUT));
+}
+
+/// A loopback port the OS will let us bind right now.
+fn free_port() -> Option<u16> {
+  TcpListener::bind((config::DEFAULT_HOST, 0))
+    .and_then(|listener| listener.local_addr())
+    .map(|addr| addr.port())
+    .ok()
+}
+
+#[derive(serde::Serialize)]
+struct PortAvailability {
+  available: bool,
+  /// When taken: whether a Talus backend (ours or another instance's) answers there.
+  in_use_by_backend: bool,
+}
+
+/// Check a port the user is about to save by briefly binding it on loopback.
+#[tauri::command(async)]
+fn is_port_available(port: u16) -> PortAvailability {
+  let available = port != 0 && TcpListener::bind((config::DEFAULT_HOST, port)).is_ok();
+  PortAvailability {
+    available,
+    in_use_by_backend: !available && port != 0 && http::is_talus_backend(config::DEFAULT_HOST, port, Duration::from_millis(500)),
+  }
+}
+
+/// Endpoint security can block loopback on specific ports: the backend runs
+/// but never answers. Move to a free port for the rest of the session (once)
+/// and emit `backend://port-blocked`. Returns true when the retry was started.
+fn retry_on_free_port(app_handle: &tauri::AppHandle, backend: &BackendState, port: u16) -> bool {
+  let running = backend
+    .process
+    .lock()
+    .ok()
+    .is_some_and(|mut proc| proc.as_mut().is_some_and(|child| matches!(child.try_wait(), Ok(None))));
+  if !running || backend.port_override.load(Ordering::SeqCst) != 0 {
+    return false;
+  }
+  let Some(new_port) = free_port() else {
+    return false;
+  };
+  diag(&format!("Backend is running but :{} never answered; port looks blocked, retrying on :{}", port, new_port));
+  backend.port_override.store(new_port, Ordering::SeqCst);
+  events::emit_lifecycle(app_handle, backend, "backend://port-blocked", PortBlocked { blocked_port: port, new_port });
+  terminate_backend_process(&backend.process, "port blocked", config::shutdown_timeout(app_handle));
+  start_backend(backend.clone(), app_handle.clone());
+  true
+}
+
+/// Scan PORT_SCAN_RANGE (except `expected`) for a port answering the Talus health check.
+fn find_backend_on_other_port(expected: u16) -> Option<u16> {
+  diag(&format!("Scanning ports {:?} for a backend that ignored TALUS_PORT={}", PORT_SCAN_RANGE, expected));
+  PORT_SCAN_RANGE
+    .filter(|candidate| *candidate != expected)
+    .find(|candidate| http::is_talus_backend(config::DEFAULT_HOST, *candidate, Duration::from_millis(300)))
+}
+
+fn set_mode(backend: &BackendState, mode: Option<&'static str>) {
+  if let Ok(mut current) = backend.mode.lock() {
+    *current = mode;
+  }
+  if let Ok(mut since) = backend.backend_since.lock() {
+    *since = mode.map(|_| Instant::now());
+  }
+}
+
+/// System install locations (deb package, manual /opt install).
+const INSTALL_PREFIXES: [&str; 2] = ["/usr/lib/Talus Tally", "/opt/talus-tally"];
+
+/// True for release builds running from an installed layout (bundle resources,
+/// AppImage, macOS .app, or a system install dir) rather than a source checkout.
+fn is_packaged_build(app: &tauri::AppHandle) -> bool {
+  if cfg!(debug_assertions) {
+    return false;
+  }
+  if std::env::var_os("APPDIR").is_some() || backend_from_resource_dir(app).is_some() {
+    return true;
+  }
+  std::env::current_exe()
+    .map(|exe| {
+      INSTALL_PREFIXES.iter().any(|prefix| exe.starts_with(prefix))
+        || exe.to_string_lossy().contains(".app/Contents/MacOS")
+    })
+    .unwrap_or(false)
+}
+
+/// Platform-aware venv python path
+fn venv_python_path(project_root: &Path) -> PathBuf {
+  if cfg!(target_os = "windows") {
+    project_root.join(".venv").join("Scripts").join("python.exe")
+  } else {
+    project_root.join(".venv").join("bin").join("python3")
+  }
+}
+
+/// Length past which Windows path APIs start failing without the `\\?\` prefix
+/// (MAX_PATH is 260, but directories are capped at 248 to leave room for a file name).
+const WINDOWS_LONG_PATH: usize = 248;
+
+/// Add the `\\?\` extended-length prefix to absolute Windows paths that are
+/// too long (`\\?\UNC\` for network shares). Prefixed paths bypass
+/// normalization, so `/` is rewritten to `\` first. Other platforms and short
+/// paths are returned unchanged.
+fn long_path(path: &Path) -> PathBuf {
+  if !cfg!(target_os = "windows") || !path.is_absolute() {
+    return path.to_path_buf();
+  }
+  PathBuf::from(extended_length(&path.to_string_lossy()))
+}
+
+/// The text rewrite behind `long_path`, independent of the platform we run on.
+fn extended_length(text: &str) -> String {
+  if text.starts_with(r"\\?\") || text.len() < WINDOWS_LONG_PATH {
+    return text.to_string();
+  }
+  let normalized = text.replace('/', r"\");
+  match normalized.strip_prefix(r"\\") {
+    Some(unc) => format!(r"\\?\UNC\{}", unc),
+    None => format!(r"\\?\{}", normalized),
+  }
+}
+
+/// Where the backend code (or packaged binary) lives, in long-path-safe form.
 fn determine_project_root(app_handle: Option<&tauri::AppHandle>) -> PathBuf {
+  long_path(&locate_project_root(app_handle))
+}
+
+fn locate_project_root(app_handle: Option<&tauri::AppHandle>) -> PathBuf {
   if let Some(handle) = app_handle {
     if let Ok(resource_dir) = handle.path().resource_dir() {
       diag(&format!("resource_dir() = {}", resource_dir.display()));
@@ -277,12 +1360,17 @@ fn determine_project_root(app_handle: Option<&tauri::AppHandle>) -> PathBuf {
     }
   }
 
+  // AppImage mounts at a new path on every run and exports it as $APPDIR
+  if let Some(root) = appimage_root() {
+    return root;
+  }
+
   if let Ok(exe_path) = std::env::current_exe() {
     let exe_dir = exe_path.parent().unwrap_or_else(|| Path::new("."));
     diag(&format!("exe_dir = {}", exe_dir.display()));
 
     // Installed package layout (Linux deb: /usr/lib/Talus Tally/)
-    for prefix in ["/usr/lib/Talus Tally"] {
+    for prefix in INSTALL_PREFIXES {
       if exe_dir.starts_with(prefix) {
         return PathBuf::from(prefix);
       }
@@ -303,6 +1391,32 @@ fn determine_project_root(app_handle: Option<&tauri::AppHandle>) -> PathBuf {
   fallback
 }
 
+/// Root of the bundled resources when running from an AppImage.
+/// Tauri's AppImage keeps them under $APPDIR/usr/lib/<productName>.
+fn appimage_root() -> Option<PathBuf> {
+  if !cfg!(target_os = "linux") {
+    return None;
+  }
+  let appdir = PathBuf::from(std::env::var_os("APPDIR")?);
+  diag(&format!("APPDIR = {}", appdir.display()));
+
+  [appdir.join("usr").join("lib").join("Talus Tally"), appdir]
+    .into_iter()
+    .find(|root| bundled_backend_in(root).is_some())
+}
+
+/// Probe both bundled layouts (flat and config-path-preserving) under `root`.
+fn bundled_backend_in(root: &Path) -> Option<PathBuf> {
+  for sub in ["talus-tally-backend", "resources/talus-tally-backend"] {
+    let candidate = root.join(sub).join(backend_binary_name());
+    diag(&format!("  bundle probe: {} (exists={})", candidate.display(), candidate.exists()));
+    if candidate.exists() {
+      return Some(candidate);
+    }
+  }
+  None
+}
+
 fn find_packaged_backend(app_handle: Option<&tauri::AppHandle>, project_root: &Path) -> Option<PathBuf> {
   if let Some(handle) = app_handle {
     if let Some(path) = backend_from_resource_dir(handle) {
@@ -310,6 +1424,12 @@ fn find_packaged_backend(app_handle: Option<&tauri::AppHandle>, project_root: &P
     }
   }
 
+  if std::env::var_os("APPDIR").is_some() {
+    if let Some(path) = bundled_backend_in(project_root) {
+      return Some(path);
+    }
+  }
+
   let dev_candidate = project_root
     .join("frontend")
     .join("src-tauri")
@@ -362,15 +1482,147 @@ fn backend_binary_name() -> &'static str {
   }
 }
 
+/// Simple health check - try to open a TCP connection to the backend
+fn backend_reachable(host: &str, port: u16) -> bool {
+  (host, port)
+    .to_socket_addrs()
+    .ok()
+    .and_then(|mut addrs| addrs.next())
+    .map(|addr| addr_reachable(&addr))
+    .unwrap_or(false)
+}
+
+fn addr_reachable(addr: &SocketAddr) -> bool {
+  TcpStream::connect_timeout(addr, Duration::from_secs(2)).is_ok()
+}
+
+/// Single-shot by default for the status poll. Restart flows pass `retries`
+/// so a backend that is milliseconds from ready isn't reported as down; the
+/// delay doubles after each failed attempt (default 100ms). Reports `false`
+/// rather than hanging once the command timeout passes.
+#[tauri::command(async)]
+fn backend_status(app: tauri::AppHandle, state: tauri::State<BackendState>, retries: Option<u32>, retry_delay_ms: Option<u64>) -> bool {
+  let backend = state.inner().clone();
+  let retries = retries.unwrap_or(0);
+  let mut delay = Duration::from_millis(retry_delay_ms.unwrap_or(100));
+  backend_api::with_timeout(&app, "backend_status", move || {
+    for attempt in 0..=retries {
+      if backend.is_reachable() {
+        return Ok(true);
+      }
+      if attempt < retries {
+        std::thread::sleep(delay);
+        delay *= 2;
+      }
+    }
+    Ok(false)
+  })
+  .unwrap_or(false)
+}
+
+/// Resolve once the backend answers, or with `false` after `timeout_ms`.
+/// Re-reads the port each tick so it follows a restart onto a new port.
+#[tauri::command(async)]
+fn wait_for_backend(state: tauri::State<BackendState>, timeout_ms: u64) -> bool {
+  let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+  loop {
+    if state.is_reachable() {
+      return true;
+    }
+    if Instant::now() >= deadline {
+      diag(&format!("wait_for_backend: not reachable within {}ms", timeout_ms));
+      return false;
+    }
+    std::thread::sleep(Duration::from_millis(100));
+  }
+}
+
+#[derive(serde::Serialize)]
+struct BackendInfo {
+  running: bool,
+  host: String,
+  port: u16,
+  pid: Option<u32>,
+  mode: Option<&'static str>,
+  /// Why `mode` isn't "packaged", e.g. "Used system python3 because .venv/bin/python3 does not exist".
+  fallback_reason: Option<String>,
+  adopted: bool,
+  paused: bool,
+  /// Sequence number of the latest lifecycle event, for gap detection.
+  last_event_seq: u64,
+  /// What the latest start tried, in order: e.g. packaged (not found), venv (spawn error).
+  spawn_attempts: Vec<SpawnAttempt>,
+}
+
+#[tauri::command(async)]
+fn get_backend_info(state: tauri::State<BackendState>) -> BackendInfo {
+  BackendInfo {
+    running: state.is_reachable(),
+    host: state.host(),
+    port: state.port(),
+    pid: state.process.lock().ok().and_then(|proc| proc.as_ref().map(|child| child.id())),
+    mode: state.mode.lock().ok().and_then(|mode| *mode),
+    fallback_reason: state.fallback_reason.lock().ok().and_then(|reason| reason.clone()),
+    adopted: state.adopted.load(Ordering::SeqCst),
+    paused: state.paused.load(Ordering::SeqCst),
+    last_event_seq: state.event_seq.load(Ordering::SeqCst),
+    spawn_attempts: state.spawn_attempts.lock().map(|attempts| attempts.clone()).unwrap_or_default(),
+  }
+}
+
+#[derive(serde::Serialize)]
+struct SessionStats {
+  app_uptime_secs: u64,
+  /// `None` while no backend is running.
+  backend_uptime_secs: Option<u64>,
+  backend_restart_count: u32,
+  crash_count: u32,
+}
+
+/// Uptime and restart counters for this session, for a status bar.
 #[tauri::command]
-fn backend_status() -> bool {
-  // Simple health check - try to reach backend on :5000
-  match TcpStream::connect("127.0.0.1:5000") {
-    Ok(_) => true,
-    Err(_) => false,
+fn get_session_stats(state: tauri::State<BackendState>) -> SessionStats {
+  SessionStats {
+    app_uptime_secs: state.app_started.elapsed().as_secs(),
+    backend_uptime_secs: state
+      .backend_since
+      .lock()
+      .ok()
+      .and_then(|since| since.map(|since| since.elapsed().as_secs())),
+    backend_restart_count: state.restart_count.load(Ordering::SeqCst),
+    crash_count: state.crash_count.load(Ordering::SeqCst),
   }
 }
 
+/// Whether this session runs with TALUS_INMEMORY, so the UI can warn that
+/// data will not be saved.
+#[tauri::command]
+fn is_inmemory() -> bool {
+  config::in_memory()
+}
+
+#[tauri::command]
+fn is_packaged(app: tauri::AppHandle) -> bool {
+  is_packaged_build(&app)
+}
+
+/// Whether this instance was launched with --safe-mode or fell into it after
+/// a stuck startup. The backend is then only started on request (restart_backend).
+#[tauri::command]
+fn is_safe_mode() -> bool {
+  safe_mode()
+}
+
+#[tauri::command]
+fn restart_in_safe_mode(app: tauri::AppHandle) {
+  relaunch_in_safe_mode(&app);
+}
+
+#[tauri::command]
+fn get_backend_port(state: tauri::State<BackendState>) -> u16 {
+  state.port.load(Ordering::SeqCst)
+}
+
 #[tauri::command]
 fn minimize_window(window: tauri::Window) {
   let _ = window.minimize();
@@ -385,6 +1637,99 @@ fn maximize_window(window: tauri::Window) {
   };
 }
 
+fn main_window(app: &tauri::AppHandle) -> Option<tauri::WebviewWindow> {
+  app.get_webview_window("main")
+}
+
+fn show_main_window(app: &tauri::AppHandle) {
+  if let Some(window) = main_window(app) {
+    let _ = window.show();
+    let _ = window.unminimize();
+    let _ = window.set_focus();
+  }
+}
+
+fn hide_main_window(app: &tauri::AppHandle) {
+  if let Some(window) = main_window(app) {
+    let _ = window.hide();
+  }
+}
+
+fn toggle_main_window(app: &tauri::AppHandle) {
+  let visible = main_window(app)
+    .and_then(|window| window.is_visible().ok())
+    .unwrap_or(false);
+  if visible {
+    hide_main_window(app);
+  } else {
+    show_main_window(app);
+  }
+}
+
+#[tauri::command]
+fn show_window(app: tauri::AppHandle) {
+  show_main_window(&app);
+}
+
+#[tauri::command]
+fn hide_window(app: tauri::AppHandle) {
+  hide_main_window(&app);
+}
+
+#[tauri::command]
+fn get_autostart(app: tauri::AppHandle) -> Result<bool, String> {
+  app.autolaunch().is_enabled().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn set_autostart(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+  let autolaunch = app.autolaunch();
+  let result = if enabled { autolaunch.enable() } else { autolaunch.disable() };
+  diag(&format!("set_autostart({}) -> {:?}", enabled, result));
+  result.map_err(|e| e.to_string())
+}
+
+#[derive(serde::Serialize)]
+struct CacheClearSummary {
+  /// Webview cache, cookies and localStorage.
+  browsing_data_cleared: bool,
+  /// Path of the deleted frontend-state file, if there was one.
+  state_file_deleted: Option<String>,
+  /// Always true: the backend database is never touched by this command.
+  tally_data_untouched: bool,
+  reloaded: bool,
+}
+
+/// Reset the webview's cache/cookies/localStorage (and optionally the saved
+/// frontend-state file), then reload. Tally data in the backend DB is untouched.
+#[tauri::command]
+fn clear_app_cache(app: tauri::AppHandle, window: tauri::WebviewWindow, delete_state_file: Option<bool>) -> Result<CacheClearSummary, String> {
+  window
+    .clear_all_browsing_data()
+    .map_err(|e| format!("Failed to clear webview data: {}", e))?;
+
+  let mut state_file_deleted = None;
+  if delete_state_file.unwrap_or(false) {
+    if let Ok(dir) = app.path().app_local_data_dir() {
+      let state_file = dir.join("frontend-state.json");
+      if state_file.exists() {
+        std::fs::remove_file(&state_file)
+          .map_err(|e| format!("Failed to delete {}: {}", state_file.display(), e))?;
+        state_file_deleted = Some(state_file.display().to_string());
+      }
+    }
+  }
+
+  let reloaded = window.reload().is_ok();
+  diag(&format!("clear_app_cache: state_file_deleted={:?} reloaded={}", state_file_deleted, reloaded));
+  Ok(CacheClearSummary {
+    browsing_data_cleared: true,
+    state_file_deleted,
+    tally_data_untouched: true,
+    reloaded,
+  })
+}
+
 #[tauri::command]
 fn close_window(window: tauri::Window, close_state: tauri::State<CloseState>) {
   if let Ok(mut allowed) = close_state.0.lock() {
@@ -393,25 +1738,402 @@ fn close_window(window: tauri::Window, close_state: tauri::State<CloseState>) {
   let _ = window.close();
 }
 
-#[tauri::command]
+/// The one place that decides what closing a window means. Other visible
+/// windows (e.g. a report) keep the app running, so the window just closes.
+/// Closing the last visible one hides it to the tray with keep-in-tray on,
+/// and otherwise stops the backend gracefully and quits, even if hidden
+/// windows remain. Returns true when it handled the close itself.
+fn take_over_last_close(window: &tauri::Window) -> bool {
+  let app = window.app_handle().clone();
+  let others_visible = app
+    .webview_windows()
+    .values()
+    .any(|other| other.label() != window.label() && other.is_visible().unwrap_or(false));
+  if others_visible {
+    return false;
+  }
+  if config::current(&app).keep_in_tray && app.tray_by_id("main").is_some() {
+    tray::hide_to_tray(&app, window);
+    return true;
+  }
+  diag(&format!("Last visible window '{}' closed; stopping backend and quitting", window.label()));
+  // Off the event loop: the graceful stop can take seconds
+  std::thread::spawn(move || {
+    let backend = app.state::<BackendState>();
+    let timeout = quit_timeout(&app, backend.inner(), "last window closed");
+    terminate_backend_process(&backend.process, "last window closed", timeout);
+    profiler::finish(&app, backend.inner());
+    set_mode(backend.inner(), None);
+    app.exit(0);
+  });
+  true
+}
+
+/// Graceful-stop timeout for quitting. Zero when the backend already stopped
+/// answering (e.g. it crashed), since a graceful stop could only run out the clock.
+fn quit_timeout(app: &tauri::AppHandle, backend: &BackendState, caller: &str) -> Duration {
+  if backend.is_reachable() {
+    config::shutdown_timeout(app)
+  } else {
+    diag(&format!("{}: backend unreachable; skipping graceful flush", caller));
+    Duration::ZERO
+  }
+}
+
+/// Upper bound on how long exit_app spends flushing and stopping the backend.
+const MAX_EXIT_WAIT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, serde::Serialize)]
+struct ShuttingDown {
+  /// The most the shutdown will take before the app exits regardless.
+  timeout_ms: u64,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct ShutdownComplete {
+  duration_ms: u64,
+}
+
+/// Runs off the main thread so the UI can show a shutdown overlay between
+/// `app://shutting-down` and `app://shutdown-complete`.
+#[tauri::command(async)]
 fn exit_app(window: tauri::Window, app: tauri::AppHandle, state: tauri::State<BackendState>, close_state: tauri::State<CloseState>) {
+  // Never silently kill a backend we did not spawn; the frontend must confirm
+  // via force_kill_adopted (or quit with force_close_window to leave it running).
+  if state.adopted.load(Ordering::SeqCst) {
+    diag("exit_app: backend was adopted; asking frontend to confirm before killing it");
+    let port = state.port.load(Ordering::SeqCst);
+    events::emit_lifecycle(&app, state.inner(), "backend://confirm-kill-adopted", ConfirmKillAdopted { port });
+    return;
+  }
   if let Ok(mut allowed) = close_state.0.lock() {
     *allowed = true;
   }
+  let started = Instant::now();
+  let timeout = quit_timeout(&app, state.inner(), "exit_app");
+  let flush_delay = if timeout.is_zero() {
+    Duration::ZERO
+  } else {
+    Duration::from_millis(config::current(&app).exit_flush_delay_ms).min(MAX_EXIT_WAIT)
+  };
+  let timeout = timeout.min(MAX_EXIT_WAIT - flush_delay);
+  events::emit(&app, "app://shutting-down", ShuttingDown { timeout_ms: (flush_delay + timeout).as_millis() as u64 });
+
   // Brief pause to allow any pending filesystem writes to flush before termination
-  std::thread::sleep(std::time::Duration::from_millis(250));
-  terminate_backend_process(&state.0, "exit_app command");
+  std::thread::sleep(flush_delay);
+  terminate_backend_process(&state.process, "exit_app command", timeout);
+  profiler::finish(&app, state.inner());
+  data_lock::release();
+  keep_awake::release_all(&app.state::<keep_awake::KeepAwakeState>());
+  sandbox::discard_all(&app.state::<sandbox::SandboxState>());
+
+  let duration_ms = started.elapsed().as_millis() as u64;
+  diag(&format!("exit_app: shutdown finished in {}ms", duration_ms));
+  events::emit(&app, "app://shutdown-complete", ShutdownComplete { duration_ms });
   let _ = window.close();
   app.exit(0);
 }
 
 #[tauri::command]
-fn force_close_window(_window: tauri::Window, _app: tauri::AppHandle, state: tauri::State<BackendState>, close_state: tauri::State<CloseState>) {
+fn force_close_window(_window: tauri::Window, app: tauri::AppHandle, state: tauri::State<BackendState>, close_state: tauri::State<CloseState>) {
   println!("✓ [FORCE CLOSE] Called, killing backend and exiting");
   if let Ok(mut allowed) = close_state.0.lock() {
     *allowed = true;
   }
-  terminate_backend_process(&state.0, "force_close_window command");
+  let timeout = quit_timeout(&app, state.inner(), "force_close_window");
+  terminate_backend_process(&state.process, "force_close_window command", timeout);
+  profiler::finish(&app, state.inner());
   println!("✓ [FORCE CLOSE] Backend killed, exiting with code 0");
   std::process::exit(0);
 }
+
+#[tauri::command(async)]
+fn stop_backend(app: tauri::AppHandle, state: tauri::State<BackendState>) {
+  terminate_backend_process(&state.process, "stop_backend command", config::shutdown_timeout(&app));
+  profiler::finish(&app, state.inner());
+  set_mode(state.inner(), None);
+}
+
+/// Holds a run-once-at-a-time flag set until dropped, so it is cleared even
+/// if the work panics.
+struct InProgress<'a>(&'a AtomicBool);
+
+impl Drop for InProgress<'_> {
+  fn drop(&mut self) {
+    self.0.store(false, Ordering::SeqCst);
+  }
+}
+
+/// Set `flag`, or return `None` if it is already set by someone else.
+fn begin(flag: &AtomicBool) -> Option<InProgress<'_>> {
+  (!flag.swap(true, Ordering::SeqCst)).then_some(InProgress(flag))
+}
+
+const RESTART_IN_PROGRESS: &str = "Backend restart already in progress";
+
+/// For commands that saved a setting and then couldn't restart to apply it.
+fn pending_restart(err: String) -> String {
+  format!("{}; the new setting is saved and applies on the next restart", err)
+}
+
+/// Stop the backend, run `while_stopped` (e.g. swapping in restored data),
+/// then start it again, all under the restart guard so nothing else can
+/// spawn a backend meanwhile. The backend is started again even if
+/// `while_stopped` fails; its result is returned. Errors without touching
+/// anything when another restart is still running.
+fn restart_around<T>(
+  app: &tauri::AppHandle,
+  backend: &BackendState,
+  reason: supervisor::RestartReason,
+  while_stopped: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+  let stop = || {
+    supervisor::notify_restarting(app, backend, reason);
+    backend.restart_count.fetch_add(1, Ordering::SeqCst);
+    terminate_backend_process(&backend.process, &format!("restart ({:?})", reason), config::shutdown_timeout(app));
+    profiler::finish(app, backend);
+  };
+  cycle(&backend.restart_in_progress, stop, while_stopped, || start_backend(backend.clone(), app.clone()))
+    .inspect_err(|err| diag(&format!("Restart ({:?}) not run: {}", reason, err)))
+}
+
+/// The restart sequence with stopping and starting passed in: nothing runs
+/// unless `guard` can be taken, so two overlapping restarts can't both stop
+/// and spawn.
+fn cycle<T>(
+  guard: &AtomicBool,
+  stop: impl FnOnce(),
+  while_stopped: impl FnOnce() -> Result<T, String>,
+  start: impl FnOnce(),
+) -> Result<T, String> {
+  let Some(_restarting) = begin(guard) else {
+    return Err(RESTART_IN_PROGRESS.to_string());
+  };
+  stop();
+  let result = while_stopped();
+  start();
+  result
+}
+
+/// Stop the backend and launch it again, picking up port/config changes.
+/// Errors without doing anything if another restart is still running.
+fn restart(app: &tauri::AppHandle, backend: &BackendState, reason: supervisor::RestartReason) -> Result<(), String> {
+  restart_around(app, backend, reason, || Ok(()))
+}
+
+/// Errors rather than racing when a restart (e.g. from a double click) is already running.
+#[tauri::command(async)]
+fn restart_backend(app: tauri::AppHandle, state: tauri::State<BackendState>) -> Result<(), String> {
+  restart(&app, state.inner(), supervisor::RestartReason::User)
+}
+
+/// Upper bound for TALUS_WORKERS regardless of core count.
+const MAX_BACKEND_WORKERS: u32 = 64;
+
+/// Allowed TALUS_WORKERS on this machine: up to 4 per CPU (I/O-bound
+/// requests), capped at MAX_BACKEND_WORKERS. Returns (max, cpus).
+fn max_backend_workers() -> (u32, u32) {
+  let cpus = std::thread::available_parallelism().map(|n| n.get() as u32).unwrap_or(1);
+  ((cpus * 4).min(MAX_BACKEND_WORKERS), cpus)
+}
+
+/// Persist the backend's request concurrency and restart it to apply.
+#[tauri::command(async)]
+fn set_backend_workers(app: tauri::AppHandle, state: tauri::State<BackendState>, n: u32) -> Result<(), String> {
+  let (max, cpus) = max_backend_workers();
+  if !(1..=max).contains(&n) {
+    return Err(format!("Worker count must be between 1 and {} on this machine ({} CPUs)", max, cpus));
+  }
+  config::update(&app, |config| config.backend_workers = Some(n))?;
+  diag(&format!("Backend workers set to {}; restarting backend", n));
+  restart(&app, state.inner(), supervisor::RestartReason::Config).map_err(pending_restart)
+}
+
+/// The configured worker count, or `None` when the backend uses its default.
+#[tauri::command]
+fn get_backend_workers(app: tauri::AppHandle) -> Option<u32> {
+  config::current(&app).backend_workers
+}
+
+#[tauri::command]
+fn force_kill_adopted(state: tauri::State<BackendState>) {
+  if state.adopted.swap(false, Ordering::SeqCst) {
+    let port = state.port.load(Ordering::SeqCst);
+    diag(&format!("force_kill_adopted: user confirmed; killing the backend adopted on port {}", port));
+    if let Err(err) = port_owner::kill_listener(port) {
+      diag(&format!("force_kill_adopted: {}", err));
+    }
+    set_mode(state.inner(), None);
+  } else {
+    diag("force_kill_adopted: no adopted backend to kill");
+  }
+}
+
+/// Open or close the webview devtools. Only honored when TALUS_DEVTOOLS=1 so
+/// release users can't open them by accident. Returns whether they are now open.
+#[tauri::command]
+fn toggle_devtools(window: tauri::WebviewWindow) -> Result<bool, String> {
+  if !env_flag("TALUS_DEVTOOLS") {
+    return Err("Developer tools are disabled; relaunch with TALUS_DEVTOOLS=1".to_string());
+  }
+  if window.is_devtools_open() {
+    window.close_devtools();
+    Ok(false)
+  } else {
+    window.open_devtools();
+    Ok(true)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// An absolute drive path of at least `len` characters.
+  fn deep_path(root: &str, len: usize) -> String {
+    let mut path = root.to_string();
+    while path.len() < len {
+      path.push_str(r"\nested-project-folder");
+    }
+    path
+  }
+
+  #[test]
+  fn short_paths_are_left_alone() {
+    let path = r"C:\Users\me\Talus Tally";
+    assert_eq!(extended_length(path), path);
+  }
+
+  #[test]
+  fn long_drive_paths_get_the_extended_prefix() {
+    let path = deep_path(r"C:\Users\me", 400);
+    assert_eq!(extended_length(&path), format!(r"\\?\{}", path));
+  }
+
+  #[test]
+  fn long_paths_have_forward_slashes_rewritten() {
+    let path = deep_path("C:/Users/me", 300);
+    let extended = extended_length(&path);
+    assert!(extended.starts_with(r"\\?\C:\Users\me"));
+    assert!(!extended.contains('/'));
+  }
+
+  #[test]
+  fn long_unc_paths_get_the_unc_prefix() {
+    let path = deep_path(r"\\fileserver\share", 300);
+    assert_eq!(extended_length(&path), format!(r"\\?\UNC\{}", &path[2..]));
+  }
+
+  #[test]
+  fn prefixed_paths_are_not_prefixed_twice() {
+    let path = format!(r"\\?\{}", deep_path(r"C:\Users\me", 300));
+    assert_eq!(extended_length(&path), path);
+  }
+
+  #[test]
+  fn paths_just_under_the_limit_are_left_alone() {
+    let path = format!(r"C:\{}", "a".repeat(WINDOWS_LONG_PATH - 4));
+    assert_eq!(path.len(), WINDOWS_LONG_PATH - 1);
+    assert_eq!(extended_length(&path), path);
+  }
+
+  #[test]
+  fn close_requested_is_not_in_the_tauri_namespace() {
+    assert!(!CLOSE_REQUESTED_EVENT.starts_with("tauri://"));
+  }
+
+  #[test]
+  fn frontend_listens_for_the_close_requested_event() {
+    let app = include_str!("../../src/App.tsx");
+    assert!(app.contains(&format!("listen('{}'", CLOSE_REQUESTED_EVENT)));
+    assert!(!app.contains("listen('tauri://close-requested'"));
+  }
+
+  /// Stand-in for a spawned backend process.
+  #[cfg(unix)]
+  fn spawn_backend() -> Child {
+    Command::new("sleep").arg("30").spawn().unwrap()
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn overlapping_restarts_leave_exactly_one_backend() {
+    use std::sync::Barrier;
+
+    let process = Arc::new(Mutex::new(Some(spawn_backend())));
+    // Backends a start replaced while they were still running, i.e. duplicates
+    let orphans: Arc<Mutex<Vec<Child>>> = Arc::default();
+    let guard = Arc::new(AtomicBool::new(false));
+    let barrier = Arc::new(Barrier::new(2));
+    let restarts: Vec<_> = (0..2)
+      .map(|_| {
+        let (process, orphans, guard, barrier) = (process.clone(), orphans.clone(), guard.clone(), barrier.clone());
+        std::thread::spawn(move || {
+          barrier.wait();
+          let stop = || {
+            if let Some(mut child) = process.lock().unwrap().take() {
+              let _ = child.kill();
+              let _ = child.wait();
+            }
+            std::thread::sleep(Duration::from_millis(100));
+          };
+          let start = || {
+            let previous = process.lock().unwrap().replace(spawn_backend());
+            orphans.lock().unwrap().extend(previous);
+          };
+          cycle(&guard, stop, || Ok(()), start)
+        })
+      })
+      .collect();
+    let results: Vec<Result<(), String>> = restarts.into_iter().map(|restart| restart.join().unwrap()).collect();
+
+    let mut backends: Vec<Child> = orphans.lock().unwrap().drain(..).collect();
+    backends.extend(process.lock().unwrap().take());
+    let running = backends.iter_mut().filter_map(|child| child.try_wait().ok()).filter(Option::is_none).count();
+    for child in &mut backends {
+      let _ = child.kill();
+      let _ = child.wait();
+    }
+    assert_eq!(running, 1);
+    assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+    assert!(results.contains(&Err(RESTART_IN_PROGRESS.to_string())));
+  }
+
+  #[test]
+  fn a_finished_restart_allows_the_next_one() {
+    let flag = AtomicBool::new(false);
+    drop(begin(&flag).expect("first restart"));
+    assert!(!flag.load(Ordering::SeqCst));
+    assert!(begin(&flag).is_some());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn stray_cleanup_spares_the_sandbox_backend() {
+    let port = TcpListener::bind(("127.0.0.1", 0)).unwrap().local_addr().unwrap().port();
+    let mut stray = Command::new("python3")
+      .args(["-m", "http.server", &port.to_string(), "--bind", "127.0.0.1"])
+      .stdout(Stdio::null())
+      .stderr(Stdio::null())
+      .spawn()
+      .unwrap();
+    // Matches the old `pkill -f "python.*backend.app"` but listens elsewhere
+    let mut sandbox = Command::new("python3").args(["-c", "import time; time.sleep(30)", "backend.app"]).spawn().unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while TcpStream::connect(("127.0.0.1", port)).is_err() {
+      assert!(Instant::now() < deadline, "stray backend never listened on {}", port);
+      std::thread::sleep(Duration::from_millis(50));
+    }
+
+    kill_stray_backend(port);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while stray.try_wait().unwrap().is_none() {
+      assert!(Instant::now() < deadline, "stray backend on {} survived", port);
+      std::thread::sleep(Duration::from_millis(50));
+    }
+    assert!(sandbox.try_wait().unwrap().is_none(), "sandbox backend was killed");
+    let _ = sandbox.kill();
+    let _ = sandbox.wait();
+  }
+}
@@ -0,0 +1,124 @@
+//! In-memory ring buffer of the backend's recent output (stderr lines), so a
+//! diagnostics view can show history without reading log files. The size is
+//! a config setting and can be changed at runtime. Lines longer than
+//! `max_log_line_bytes` are cut with a "[truncated]" marker as they are read,
+//! so a runaway log statement can't flood memory or event payloads.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::{backend_errors, config, diag};
+
+/// Bounds for the buffer size; the ceiling keeps memory use in check.
+pub const MIN_LINES: u32 = 100;
+pub const MAX_LINES: u32 = 100_000;
+/// Bounds for the per-line limit.
+pub const MIN_LINE_BYTES: usize = 256;
+pub const MAX_LINE_BYTES: usize = 1024 * 1024;
+const TRUNCATED_MARKER: &str = " [truncated]";
+
+static LINES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static CAPACITY: AtomicUsize = AtomicUsize::new(config::DEFAULT_LOG_BUFFER_LINES as usize);
+
+pub fn init(app: &tauri::AppHandle) {
+  resize(config::current(app).log_buffer_lines.clamp(MIN_LINES, MAX_LINES));
+}
+
+/// Read one line, keeping at most `max` bytes and discarding the rest without
+/// buffering it. `None` at end of stream.
+fn read_line_capped(reader: &mut impl BufRead, max: usize) -> std::io::Result<Option<String>> {
+  let mut line = Vec::new();
+  let mut truncated = false;
+  let mut read_any = false;
+  loop {
+    let chunk = reader.fill_buf()?;
+    if chunk.is_empty() {
+      break;
+    }
+    read_any = true;
+    let (part, done) = match chunk.iter().position(|&b| b == b'\n') {
+      Some(end) => (&chunk[..end], Some(end + 1)),
+      None => (chunk, None),
+    };
+    let room = max.saturating_sub(line.len());
+    truncated |= part.len() > room;
+    line.extend_from_slice(&part[..part.len().min(room)]);
+    let consumed = done.unwrap_or(chunk.len());
+    reader.consume(consumed);
+    if done.is_some() {
+      break;
+    }
+  }
+  if !read_any {
+    return Ok(None);
+  }
+  let mut line = String::from_utf8_lossy(&line).trim_end_matches('\r').to_string();
+  if truncated {
+    line.push_str(TRUNCATED_MARKER);
+  }
+  Ok(Some(line))
+}
+
+/// Echo the backend's stderr into our own and the buffer, and collect its
+/// errors.
+pub fn watch_stderr(app: &tauri::AppHandle, stderr: impl Read + Send + 'static) {
+  let max = config::current(app).max_log_line_bytes.clamp(MIN_LINE_BYTES, MAX_LINE_BYTES);
+  let app = app.clone();
+  std::thread::spawn(move || {
+    let mut reader = BufReader::new(stderr);
+    let mut errors = backend_errors::Collector::default();
+    while let Ok(Some(line)) = read_line_capped(&mut reader, max) {
+      eprintln!("{}", line);
+      push(&line);
+      errors.line(&app, &line);
+    }
+  });
+}
+
+/// Append a line, dropping the oldest once the buffer is full.
+fn push(line: &str) {
+  if let Ok(mut lines) = LINES.lock() {
+    let capacity = CAPACITY.load(Ordering::Relaxed);
+    while lines.len() >= capacity {
+      lines.pop_front();
+    }
+    lines.push_back(line.to_string());
+  }
+}
+
+/// Change the capacity, keeping the most recent lines.
+pub fn resize(lines: u32) {
+  let capacity = lines as usize;
+  CAPACITY.store(capacity, Ordering::Relaxed);
+  if let Ok(mut buffer) = LINES.lock() {
+    let excess = buffer.len().saturating_sub(capacity);
+    buffer.drain(..excess);
+    buffer.shrink_to(capacity);
+  }
+}
+
+/// The most recent `limit` lines (default all), oldest first.
+#[tauri::command]
+pub fn get_backend_log(limit: Option<u32>) -> Vec<String> {
+  let lines = LINES.lock().map(|lines| lines.clone()).unwrap_or_default();
+  let skip = limit.map_or(0, |limit| lines.len().saturating_sub(limit as usize));
+  lines.into_iter().skip(skip).collect()
+}
+
+#[tauri::command]
+pub fn get_log_buffer_size(app: tauri::AppHandle) -> u32 {
+  config::current(&app).log_buffer_lines
+}
+
+/// Resize the buffer (clamped to MIN_LINES..=MAX_LINES) and persist it.
+/// Returns the size applied.
+#[tauri::command]
+pub fn set_log_buffer_size(app: tauri::AppHandle, lines: u32) -> Result<u32, String> {
+  let lines = lines.clamp(MIN_LINES, MAX_LINES);
+  config::update(&app, |config| config.log_buffer_lines = lines)?;
+  resize(lines);
+  diag(&format!("Backend log buffer resized to {} lines", lines));
+  Ok(lines)
+}
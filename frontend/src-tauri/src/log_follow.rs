@@ -0,0 +1,108 @@
+//! `tail -F` for the on-disk diagnostic log (`backend-launch.log`): new
+//! lines are emitted as `backend://log` from the end of the file onward.
+//! When the file is replaced (`rotate_logs`, an external logrotate) or
+//! truncated, the new file is followed from its start.
+
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::{diag, diagnostic_log_path, events};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+const EVENT: &str = "backend://log";
+
+static FOLLOWING: AtomicBool = AtomicBool::new(false);
+/// Bumped per follow so a follower stopped and restarted quickly can't run twice.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Serialize)]
+struct LogLine {
+  line: String,
+}
+
+/// Identifies the file behind a path, to notice it being replaced.
+type FileId = (u64, u64);
+
+#[cfg(unix)]
+fn identity(metadata: &std::fs::Metadata) -> Option<FileId> {
+  use std::os::unix::fs::MetadataExt;
+  Some((metadata.dev(), metadata.ino()))
+}
+
+/// Windows has no stable id through std; a replaced file shows up as
+/// shorter than what we've read, or as a different creation time.
+#[cfg(not(unix))]
+fn identity(metadata: &std::fs::Metadata) -> Option<FileId> {
+  let created = metadata.created().ok()?.duration_since(std::time::UNIX_EPOCH).ok()?;
+  Some((created.as_secs(), created.subsec_nanos() as u64))
+}
+
+fn open(path: &Path, from_end: bool) -> Option<(BufReader<File>, Option<FileId>)> {
+  let mut file = File::open(path).ok()?;
+  let id = file.metadata().ok().and_then(|metadata| identity(&metadata));
+  if from_end {
+    file.seek(SeekFrom::End(0)).ok()?;
+  }
+  Some((BufReader::new(file), id))
+}
+
+fn follow(app: tauri::AppHandle, path: PathBuf, generation: u64) {
+  let mut current = open(&path, true);
+  let mut partial = String::new();
+  while FOLLOWING.load(Ordering::SeqCst) && GENERATION.load(Ordering::SeqCst) == generation {
+    let replaced = match (&mut current, std::fs::metadata(&path)) {
+      (Some((reader, id)), Ok(metadata)) => {
+        let position = reader.stream_position().unwrap_or(0);
+        identity(&metadata) != *id || metadata.len() < position
+      }
+      (None, Ok(_)) => true,
+      (_, Err(_)) => false,
+    };
+
+    if let Some((reader, _)) = &mut current {
+      // Drain the old file first so lines written just before rotation aren't lost
+      loop {
+        match reader.read_line(&mut partial) {
+          Ok(0) | Err(_) => break,
+          Ok(_) if !partial.ends_with('\n') => break,
+          Ok(_) => {
+            let line = partial.trim_end_matches(['\r', '\n']).to_string();
+            partial.clear();
+            // Tracing or failing our own emits would log about this event and feed the loop
+            if !line.contains(EVENT) {
+              events::emit(&app, EVENT, LogLine { line });
+            }
+          }
+        }
+      }
+    }
+    if replaced {
+      partial.clear();
+      current = open(&path, false);
+    }
+    std::thread::sleep(POLL_INTERVAL);
+  }
+}
+
+/// Start emitting new diagnostic log lines as `backend://log`. Returns the
+/// file being followed; calling it again while following is a no-op.
+#[tauri::command]
+pub fn follow_log_file(app: tauri::AppHandle) -> Result<PathBuf, String> {
+  let path = diagnostic_log_path().ok_or_else(|| "Could not resolve the diagnostic log location".to_string())?;
+  if !FOLLOWING.swap(true, Ordering::SeqCst) {
+    diag(&format!("Following {}", path.display()));
+    let followed = path.clone();
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    std::thread::spawn(move || follow(app, followed, generation));
+  }
+  Ok(path)
+}
+
+#[tauri::command]
+pub fn unfollow_log_file() {
+  FOLLOWING.store(false, Ordering::SeqCst);
+}
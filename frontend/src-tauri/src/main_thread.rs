@@ -0,0 +1,107 @@
+//! Watchdog for the main (event loop) thread. Synchronous commands run
+//! there, so one that blocks freezes the whole UI. A background thread posts
+//! a ping to the main thread every second; if no pong arrives within
+//! STALL_THRESHOLD it emits `app://main-thread-stalled` with the last
+//! invoked command, and `app://main-thread-recovered` once it answers again.
+//! A frozen webview may only see these after recovery; the diag log always has them.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::{diag, events, ipc_trace, unix_now_ms};
+
+const PING_INTERVAL: Duration = Duration::from_secs(1);
+const STALL_THRESHOLD: Duration = Duration::from_secs(5);
+const MAX_STALLS: usize = 20;
+
+static STALLS: Mutex<VecDeque<Stall>> = Mutex::new(VecDeque::new());
+static STALLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Serialize)]
+pub struct Stall {
+  /// When the stall was detected, unix milliseconds.
+  detected_ms: u64,
+  /// Command invoked most recently before the stall; the likely culprit.
+  last_command: Option<String>,
+  /// How long the main thread was blocked, once it recovered.
+  duration_ms: Option<u64>,
+}
+
+#[derive(Clone, Serialize)]
+struct Stalled {
+  threshold_ms: u64,
+  last_command: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct Recovered {
+  duration_ms: u64,
+  last_command: Option<String>,
+}
+
+pub fn spawn(app: tauri::AppHandle) {
+  std::thread::spawn(move || loop {
+    std::thread::sleep(PING_INTERVAL);
+    let (reply, pong) = mpsc::channel();
+    let sent = Instant::now();
+    let queued = app.run_on_main_thread(move || {
+      let _ = reply.send(());
+    });
+    if queued.is_err() {
+      // Event loop is gone; the app is exiting
+      return;
+    }
+    if pong.recv_timeout(STALL_THRESHOLD).is_ok() {
+      continue;
+    }
+
+    STALLED.store(true, Ordering::SeqCst);
+    let last_command = ipc_trace::last_command();
+    diag(&format!("Main thread unresponsive for {:?} (last command: {:?})", STALL_THRESHOLD, last_command));
+    if let Ok(mut stalls) = STALLS.lock() {
+      if stalls.len() >= MAX_STALLS {
+        stalls.pop_front();
+      }
+      stalls.push_back(Stall {
+        detected_ms: unix_now_ms(),
+        last_command: last_command.clone(),
+        duration_ms: None,
+      });
+    }
+    events::emit(
+      &app,
+      "app://main-thread-stalled",
+      Stalled {
+        threshold_ms: STALL_THRESHOLD.as_millis() as u64,
+        last_command: last_command.clone(),
+      },
+    );
+
+    // A disconnect means the reply was dropped with the event loop
+    if pong.recv().is_err() {
+      return;
+    }
+    STALLED.store(false, Ordering::SeqCst);
+    let duration_ms = sent.elapsed().as_millis() as u64;
+    diag(&format!("Main thread responsive again after {}ms", duration_ms));
+    if let Some(stall) = STALLS.lock().ok().as_mut().and_then(|stalls| stalls.back_mut()) {
+      stall.duration_ms = Some(duration_ms);
+    }
+    events::emit(&app, "app://main-thread-recovered", Recovered { duration_ms, last_command });
+  });
+}
+
+/// Whether the main thread is currently past the stall threshold.
+pub fn is_stalled() -> bool {
+  STALLED.load(Ordering::SeqCst)
+}
+
+/// Recent main-thread stalls, oldest first.
+#[tauri::command]
+pub fn get_main_thread_stalls() -> Vec<Stall> {
+  STALLS.lock().map(|stalls| stalls.iter().cloned().collect()).unwrap_or_default()
+}
@@ -0,0 +1,102 @@
+//! One-time move of the backend's data store (see `data_store`) from the
+//! legacy layout under the code root into the data dir. Legacy data is
+//! copied, never moved; a data dir file at least as new as its legacy copy
+//! is never overwritten, and older ones are saved under `backups/` first. A
+//! marker file makes the step idempotent.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use crate::{backup, config, data_store, determine_project_root, diag, events, unix_now};
+
+/// Where the code-root layout kept the store, relative to the code root.
+const LEGACY_ROOTS: [&str; 2] = ["", "data"];
+const MARKER_FILE: &str = ".legacy-migrated";
+
+#[derive(Clone, Serialize)]
+struct MigrationCompleted {
+  from: String,
+  to: String,
+  /// Files copied into the data dir.
+  copied: usize,
+  /// Where data dir files that were replaced were saved, if any were.
+  backup: Option<String>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+  std::fs::metadata(path).ok()?.modified().ok()
+}
+
+/// A legacy root only counts when it holds the backend's settings file; the
+/// bundled templates under `data/` alone are not user data.
+fn legacy_root(project_root: &Path) -> Option<PathBuf> {
+  LEGACY_ROOTS
+    .iter()
+    .map(|rel| project_root.join(rel))
+    .find(|root| root.join(data_store::SETTINGS_FILE).is_file())
+}
+
+pub fn migrate_legacy_data(app: &tauri::AppHandle) {
+  let data_dir = config::data_dir(app);
+  let marker = data_dir.join(MARKER_FILE);
+  if marker.exists() {
+    return;
+  }
+
+  let project_root = determine_project_root(Some(app));
+  let Some(legacy) = legacy_root(&project_root) else {
+    return;
+  };
+  if legacy == data_dir {
+    return;
+  }
+  if let Err(err) = std::fs::create_dir_all(&data_dir) {
+    diag(&format!("Legacy migration skipped; cannot create {}: {}", data_dir.display(), err));
+    return;
+  }
+
+  match copy_legacy(&legacy, &data_dir) {
+    Ok(completed) if completed.copied > 0 => {
+      diag(&format!("Migrated {} legacy file(s) {} -> {}", completed.copied, completed.from, completed.to));
+      events::emit(app, "migration://completed", completed);
+    }
+    Ok(_) => diag(&format!("Legacy data under {} is not newer than the data dir copy; leaving it", legacy.display())),
+    Err(err) => {
+      // No marker: try again next launch
+      diag(&format!("Legacy migration failed: {}", err));
+      return;
+    }
+  }
+  let _ = std::fs::write(&marker, legacy.display().to_string());
+}
+
+/// Copy every store file under `legacy` into `data_dir`, skipping ones the
+/// data dir already has at least as new and backing up older ones first.
+fn copy_legacy(legacy: &Path, data_dir: &Path) -> Result<MigrationCompleted, String> {
+  let backup_dir = data_dir.join(backup::BACKUP_DIR).join(format!("legacy-migration-{}", unix_now()));
+  let mut copied = 0;
+  let mut backed_up = false;
+  for file in data_store::existing(legacy).iter().flat_map(|entry| data_store::files(entry)) {
+    let Ok(rel) = file.strip_prefix(legacy) else {
+      continue;
+    };
+    let target = data_dir.join(rel);
+    if target.exists() {
+      if modified(&target) >= modified(&file) {
+        continue;
+      }
+      data_store::copy_entry(&target, &backup_dir.join(rel))
+        .map_err(|e| format!("Failed to back up {}: {}", target.display(), e))?;
+      backed_up = true;
+    }
+    data_store::copy_entry(&file, &target)
+      .map_err(|e| format!("Failed to copy {} to {}: {}", file.display(), target.display(), e))?;
+    copied += 1;
+  }
+  Ok(MigrationCompleted {
+    from: legacy.display().to_string(),
+    to: data_dir.display().to_string(),
+    copied,
+    backup: backed_up.then(|| backup_dir.display().to_string()),
+  })
+}
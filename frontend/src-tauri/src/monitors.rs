@@ -0,0 +1,104 @@
+//! Display hotplug detection and moving windows between monitors, for
+//! presentation setups that plug in a projector after launch.
+
+use serde::Serialize;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::{diag, events};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+#[derive(Clone, PartialEq, Serialize)]
+pub struct MonitorInfo {
+  index: usize,
+  name: Option<String>,
+  /// Position and size in physical pixels.
+  x: i32,
+  y: i32,
+  width: u32,
+  height: u32,
+  scale_factor: f64,
+  primary: bool,
+}
+
+fn monitors(app: &tauri::AppHandle) -> Vec<MonitorInfo> {
+  let primary = app.primary_monitor().ok().flatten();
+  app
+    .available_monitors()
+    .unwrap_or_default()
+    .iter()
+    .enumerate()
+    .map(|(index, monitor)| MonitorInfo {
+      index,
+      name: monitor.name().cloned(),
+      x: monitor.position().x,
+      y: monitor.position().y,
+      width: monitor.size().width,
+      height: monitor.size().height,
+      scale_factor: monitor.scale_factor(),
+      primary: primary
+        .as_ref()
+        .is_some_and(|p| p.name() == monitor.name() && p.position() == monitor.position()),
+    })
+    .collect()
+}
+
+/// Tauri has no hotplug event, so poll the monitor list and emit
+/// `monitors://changed` with the new list whenever it differs.
+pub fn watch(app: &tauri::AppHandle) {
+  let app = app.clone();
+  std::thread::spawn(move || {
+    let mut last = monitors(&app);
+    loop {
+      std::thread::sleep(POLL_INTERVAL);
+      let current = monitors(&app);
+      if current != last {
+        diag(&format!("Monitor layout changed: {} -> {} display(s)", last.len(), current.len()));
+        events::emit(&app, "monitors://changed", current.clone());
+        last = current;
+      }
+    }
+  });
+}
+
+#[tauri::command]
+pub fn list_monitors(app: tauri::AppHandle) -> Vec<MonitorInfo> {
+  monitors(&app)
+}
+
+/// Move a window (by `label`, default the calling one) onto monitor
+/// `monitor_index` from `list_monitors`, optionally fullscreening it there.
+#[tauri::command]
+pub fn move_window_to_monitor(
+  app: tauri::AppHandle,
+  window: tauri::WebviewWindow,
+  label: Option<String>,
+  monitor_index: usize,
+  fullscreen: Option<bool>,
+) -> Result<(), String> {
+  let target = match label {
+    Some(label) => app
+      .get_webview_window(&label)
+      .ok_or_else(|| format!("No window labelled '{}'", label))?,
+    None => window,
+  };
+  let available = app.available_monitors().map_err(|e| e.to_string())?;
+  let monitor = available
+    .get(monitor_index)
+    .ok_or_else(|| format!("No monitor {} ({} connected)", monitor_index, available.len()))?;
+
+  // A fullscreen or maximized window won't move; restore it first
+  let _ = target.set_fullscreen(false);
+  let _ = target.unmaximize();
+  // Inset from the corner so the title bar lands fully on the new display
+  let position = monitor.position();
+  target
+    .set_position(tauri::PhysicalPosition::new(position.x + 40, position.y + 40))
+    .map_err(|e| e.to_string())?;
+  if fullscreen.unwrap_or(false) {
+    target.set_fullscreen(true).map_err(|e| e.to_string())?;
+  }
+  diag(&format!("Moved window '{}' to monitor {}", target.label(), monitor_index));
+  Ok(())
+}
@@ -0,0 +1,123 @@
+//! Which addresses other devices could reach this machine on, and where the
+//! backend is actually bound, so the UI can show e.g. "other devices connect
+//! to http://192.168.1.20:5000". Loopback and virtual adapters (containers,
+//! VMs, VPN tunnels) are hidden unless asked for.
+//! Also re-resolves a remote backend's host name when its address changes.
+
+use serde::Serialize;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use sysinfo::Networks;
+
+use crate::{addr_reachable, config, diag, events, BackendState};
+
+/// Name prefixes of adapters that other devices on the LAN can't use.
+const VIRTUAL_PREFIXES: [&str; 14] = [
+  "docker", "veth", "br-", "virbr", "vmnet", "vboxnet", "vethernet", "tun", "tap", "utun", "wg", "zt", "tailscale", "awdl",
+];
+
+#[derive(Serialize)]
+pub struct Interface {
+  name: String,
+  addr: IpAddr,
+  prefix: u8,
+  /// Loopback, or an adapter of a container/VM/VPN.
+  is_virtual: bool,
+}
+
+#[derive(Serialize)]
+pub struct NetworkInfo {
+  interfaces: Vec<Interface>,
+  /// host:port the backend is bound to.
+  bound_addr: String,
+  /// Whether the bind accepts connections from other devices at all; a
+  /// loopback bind means the interfaces above can't reach it.
+  lan_accessible: bool,
+}
+
+fn is_virtual(name: &str, addr: &IpAddr) -> bool {
+  let lower = name.to_lowercase();
+  addr.is_loopback() || lower.starts_with("lo") || VIRTUAL_PREFIXES.iter().any(|prefix| lower.starts_with(prefix))
+}
+
+/// Link-local addresses (169.254/16, fe80::/10) aren't useful to show.
+fn is_link_local(addr: &IpAddr) -> bool {
+  match addr {
+    IpAddr::V4(v4) => v4.is_link_local(),
+    IpAddr::V6(v6) => (v6.segments()[0] & 0xffc0) == 0xfe80,
+  }
+}
+
+#[tauri::command]
+pub fn get_network_info(state: tauri::State<BackendState>, include_virtual: Option<bool>) -> NetworkInfo {
+  let include_virtual = include_virtual.unwrap_or(false);
+  let networks = Networks::new_with_refreshed_list();
+  let mut interfaces: Vec<Interface> = networks
+    .iter()
+    .flat_map(|(name, data)| {
+      data.ip_networks().iter().map(move |network| Interface {
+        name: name.clone(),
+        addr: network.addr,
+        prefix: network.prefix,
+        is_virtual: is_virtual(name, &network.addr),
+      })
+    })
+    .filter(|interface| !is_link_local(&interface.addr))
+    .filter(|interface| include_virtual || !interface.is_virtual)
+    .collect();
+  // IPv4 first; that's what people type into another device
+  interfaces.sort_by_key(|interface| (interface.is_virtual, interface.addr.is_ipv6(), interface.name.clone()));
+
+  let host = state.host();
+  let port = state.port();
+  let lan_accessible = host
+    .parse::<IpAddr>()
+    .map(|addr| !addr.is_loopback())
+    .unwrap_or(host != "localhost");
+  let bound_addr = host
+    .parse::<IpAddr>()
+    .map(|addr| SocketAddr::new(addr, port).to_string())
+    .unwrap_or_else(|_| format!("{}:{}", host, port));
+  NetworkInfo {
+    interfaces,
+    bound_addr,
+    lan_accessible,
+  }
+}
+
+/// First address `host` resolves to, with a readable error when it doesn't.
+pub fn resolve(host: &str, port: u16) -> Result<SocketAddr, String> {
+  (host, port)
+    .to_socket_addrs()
+    .map_err(|e| format!("Could not resolve backend host {}: {}", host, e))?
+    .next()
+    .ok_or_else(|| format!("Backend host {} resolved to no addresses", host))
+}
+
+#[derive(Clone, Serialize)]
+pub struct HostResolved {
+  host: String,
+  /// Address used for health checks from now on.
+  addr: SocketAddr,
+  /// The previous cached address, if any.
+  previous_addr: Option<SocketAddr>,
+  reachable: bool,
+}
+
+/// Re-resolve the configured backend host (TALUS_HOST or the config file),
+/// replace the cached address and re-run the health check, emitting
+/// `backend://host-resolved`. For a remote backend whose DNS record moved.
+#[tauri::command(async)]
+pub fn refresh_backend_host(app: tauri::AppHandle, state: tauri::State<BackendState>) -> Result<HostResolved, String> {
+  let backend = state.inner();
+  let host = config::backend_host(&app);
+  let addr = resolve(&host, backend.port()).inspect_err(|err| diag(err))?;
+  if let Ok(mut current) = backend.host.lock() {
+    *current = host.clone();
+  }
+  let previous_addr = backend.resolved_addr.lock().ok().and_then(|mut resolved| resolved.replace(addr));
+  let reachable = addr_reachable(&addr);
+  diag(&format!("Backend host {} resolved to {} (was {:?}); reachable={}", host, addr, previous_addr, reachable));
+  let resolved = HostResolved { host, addr, previous_addr, reachable };
+  events::emit_lifecycle(&app, backend, "backend://host-resolved", resolved.clone());
+  Ok(resolved)
+}
@@ -0,0 +1,257 @@
+//! Checks (and where possible repairs) access to the data dir, so a database
+//! restored with the wrong owner or mode is reported clearly up front instead
+//! of failing inside the backend. Also lists every path the app writes to,
+//! for checking read-only installs.
+
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::{backup, config, data_lock, determine_project_root, diag, diagnostic_dir, diagnostic_log_path};
+
+#[derive(Clone, Serialize)]
+pub struct PermissionIssue {
+  path: PathBuf,
+  /// What is wrong: "missing", "not a directory", "not readable" or "not writable".
+  problem: String,
+  /// Whether `fix_data_permissions` can likely repair it without admin rights.
+  fixable: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct PermissionReport {
+  data_dir: PathBuf,
+  ok: bool,
+  issues: Vec<PermissionIssue>,
+  /// Plain-language summary for the UI; set whenever something is wrong.
+  message: Option<String>,
+}
+
+fn readable(path: &Path, is_dir: bool) -> bool {
+  if is_dir {
+    std::fs::read_dir(path).is_ok()
+  } else {
+    std::fs::File::open(path).is_ok()
+  }
+}
+
+fn writable(path: &Path, is_dir: bool) -> bool {
+  if is_dir {
+    let probe = path.join(".talus-write-test");
+    let ok = std::fs::write(&probe, b"ok").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    ok
+  } else {
+    // Opening for write without truncate leaves the contents untouched.
+    OpenOptions::new().write(true).open(path).is_ok()
+  }
+}
+
+/// Only the owner (or an admin) can change a file's mode, so a mode we can't
+/// change means the file belongs to someone else.
+#[cfg(unix)]
+fn owner_access_missing(metadata: &std::fs::Metadata, is_dir: bool) -> bool {
+  use std::os::unix::fs::PermissionsExt;
+  let wanted = if is_dir { 0o700 } else { 0o600 };
+  metadata.permissions().mode() & wanted != wanted
+}
+
+#[cfg(not(unix))]
+fn owner_access_missing(metadata: &std::fs::Metadata, _is_dir: bool) -> bool {
+  metadata.permissions().readonly()
+}
+
+fn check_path(path: &Path, issues: &mut Vec<PermissionIssue>) {
+  let Ok(metadata) = std::fs::metadata(path) else {
+    return;
+  };
+  let is_dir = metadata.is_dir();
+  let mut push = |problem: &str| {
+    issues.push(PermissionIssue {
+      path: path.to_path_buf(),
+      problem: problem.to_string(),
+      fixable: owner_access_missing(&metadata, is_dir),
+    })
+  };
+  if !readable(path, is_dir) {
+    push("not readable");
+    // Nothing below an unreadable directory can be inspected.
+    return;
+  }
+  if !writable(path, is_dir) {
+    push("not writable");
+  }
+  if is_dir {
+    if let Ok(entries) = std::fs::read_dir(path) {
+      for entry in entries.flatten() {
+        check_path(&entry.path(), issues);
+      }
+    }
+  }
+}
+
+/// `unfixable` lists paths a fix attempt already failed on, whatever their mode suggests.
+fn build_report(data_dir: PathBuf, unfixable: &[PathBuf]) -> PermissionReport {
+  let mut issues = Vec::new();
+  match std::fs::metadata(&data_dir) {
+    Err(_) => issues.push(PermissionIssue {
+      path: data_dir.clone(),
+      problem: "missing".to_string(),
+      fixable: true,
+    }),
+    Ok(metadata) if !metadata.is_dir() => issues.push(PermissionIssue {
+      path: data_dir.clone(),
+      problem: "not a directory".to_string(),
+      fixable: false,
+    }),
+    Ok(_) => check_path(&data_dir, &mut issues),
+  }
+  for issue in issues.iter_mut() {
+    if unfixable.contains(&issue.path) {
+      issue.fixable = false;
+    }
+  }
+
+  let message = if issues.is_empty() {
+    None
+  } else if issues.iter().all(|issue| issue.fixable) {
+    Some(format!("{} item(s) in {} have wrong permissions; they can be fixed automatically.", issues.len(), data_dir.display()))
+  } else {
+    Some(format!(
+      "Some items in {} are owned by another user or are not regular files. Fix their ownership as an administrator (e.g. `sudo chown -R $USER \"{}\"`) or choose a different data directory.",
+      data_dir.display(),
+      data_dir.display()
+    ))
+  };
+  PermissionReport {
+    data_dir,
+    ok: issues.is_empty(),
+    issues,
+    message,
+  }
+}
+
+#[cfg(unix)]
+fn grant_owner_access(path: &Path, is_dir: bool) -> std::io::Result<()> {
+  use std::os::unix::fs::PermissionsExt;
+  let mut permissions = std::fs::metadata(path)?.permissions();
+  permissions.set_mode(permissions.mode() | if is_dir { 0o700 } else { 0o600 });
+  std::fs::set_permissions(path, permissions)
+}
+
+#[cfg(not(unix))]
+fn grant_owner_access(path: &Path, _is_dir: bool) -> std::io::Result<()> {
+  let mut permissions = std::fs::metadata(path)?.permissions();
+  #[allow(clippy::permissions_set_readonly_false)]
+  permissions.set_readonly(false);
+  std::fs::set_permissions(path, permissions)
+}
+
+#[tauri::command(async)]
+pub fn check_data_permissions(app: tauri::AppHandle) -> PermissionReport {
+  build_report(config::data_dir(&app), &[])
+}
+
+/// Create a missing data dir and restore owner read/write on anything we own,
+/// then re-check. What's left in the returned report needs admin rights.
+#[tauri::command(async)]
+pub fn fix_data_permissions(app: tauri::AppHandle) -> PermissionReport {
+  let data_dir = config::data_dir(&app);
+  let mut failed = Vec::new();
+  for issue in build_report(data_dir.clone(), &[]).issues {
+    if !issue.fixable {
+      continue;
+    }
+    let result = if issue.problem == "missing" {
+      std::fs::create_dir_all(&issue.path)
+    } else {
+      grant_owner_access(&issue.path, issue.path.is_dir())
+    };
+    match result {
+      Ok(()) => diag(&format!("Fixed permissions on {} ({})", issue.path.display(), issue.problem)),
+      Err(err) => {
+        diag(&format!("Could not fix permissions on {}: {}", issue.path.display(), err));
+        failed.push(issue.path);
+      }
+    }
+  }
+  build_report(data_dir, &failed)
+}
+
+#[derive(Serialize)]
+pub struct PathStatus {
+  /// What the app writes there, e.g. "config" or "backups".
+  name: &'static str,
+  path: PathBuf,
+  exists: bool,
+  /// For a path that doesn't exist yet: whether it could be created.
+  writable: bool,
+  /// Set when the path lies under the install/code root, which may be mounted read-only.
+  in_install_dir: bool,
+}
+
+/// Nearest existing ancestor, which decides whether a missing path can be created.
+fn existing_ancestor(path: &Path) -> Option<&Path> {
+  path.ancestors().find(|ancestor| ancestor.exists())
+}
+
+/// Whether `path` is writable, or for a missing path, whether it could be created.
+pub fn can_write(path: &Path, is_dir: bool) -> bool {
+  if path.exists() {
+    writable(path, is_dir)
+  } else {
+    existing_ancestor(path).is_some_and(|ancestor| ancestor.is_dir() && writable(ancestor, true))
+  }
+}
+
+fn path_status(name: &'static str, path: PathBuf, is_dir: bool, install_root: &Path) -> PathStatus {
+  let exists = path.exists();
+  let writable = can_write(&path, is_dir);
+  PathStatus {
+    name,
+    in_install_dir: path.starts_with(install_root),
+    path,
+    exists,
+    writable,
+  }
+}
+
+/// Every place the app writes to, and whether it can. None should be under
+/// the install dir, so a read-only install (e.g. `/opt/talus-tally` mounted
+/// read-only) keeps working; this makes such setups easy to check.
+#[tauri::command(async)]
+pub fn check_writable_paths(app: tauri::AppHandle) -> Vec<PathStatus> {
+  let install_root = determine_project_root(Some(&app));
+  let data_dir = config::data_dir(&app);
+  let mut paths = Vec::new();
+  if let Ok(config_dir) = app.path().app_config_dir() {
+    paths.push(("config", config_dir, true));
+  }
+  if let Some(log) = diagnostic_log_path() {
+    paths.push(("diagnostic log", log, false));
+  }
+  if let Some(dir) = diagnostic_dir() {
+    paths.push(("launch records", dir, true));
+  }
+  paths.extend([
+    ("data", data_dir.clone(), true),
+    ("data lock", data_dir.join(data_lock::LOCK_FILE_NAME), false),
+    ("backups", data_dir.join(backup::BACKUP_DIR), true),
+    ("profiling", data_dir.join("profiling"), true),
+  ]);
+  let statuses: Vec<PathStatus> = paths
+    .into_iter()
+    .map(|(name, path, is_dir)| path_status(name, path, is_dir, &install_root))
+    .collect();
+  for status in statuses.iter().filter(|status| !status.writable || status.in_install_dir) {
+    diag(&format!(
+      "Write path {} ({}) writable={} in_install_dir={}",
+      status.name,
+      status.path.display(),
+      status.writable,
+      status.in_install_dir
+    ));
+  }
+  statuses
+}
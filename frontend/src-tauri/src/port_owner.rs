@@ -0,0 +1,105 @@
+//! Finding and stopping the process listening on a port, for an adopted
+//! backend: we never spawned it, so we have no handle and must not guess by
+//! process name (that would take down unrelated Python processes or another
+//! user's instance). sysinfo can't map sockets to processes, so the owner is
+//! looked up from the OS (`/proc` on Linux, `lsof` on macOS, `netstat` on
+//! Windows) and sysinfo does the kill.
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use std::process::Command;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use crate::diag;
+
+/// Inodes of TCP sockets listening on `port`, from `/proc/net/tcp{,6}`.
+#[cfg(target_os = "linux")]
+fn listening_inodes(port: u16) -> Vec<String> {
+  const LISTEN: &str = "0A";
+  ["/proc/net/tcp", "/proc/net/tcp6"]
+    .iter()
+    .filter_map(|table| std::fs::read_to_string(table).ok())
+    .flat_map(|table| {
+      table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+          let fields: Vec<&str> = line.split_whitespace().collect();
+          let local_port = fields.get(1)?.rsplit(':').next()?;
+          if u16::from_str_radix(local_port, 16).ok() != Some(port) || fields.get(3) != Some(&LISTEN) {
+            return None;
+          }
+          fields.get(9).map(|inode| inode.to_string())
+        })
+        .collect::<Vec<_>>()
+    })
+    .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn listening_pid(port: u16) -> Option<u32> {
+  let sockets: Vec<String> = listening_inodes(port).iter().map(|inode| format!("socket:[{}]", inode)).collect();
+  if sockets.is_empty() {
+    return None;
+  }
+  std::fs::read_dir("/proc").ok()?.flatten().find_map(|entry| {
+    let pid = entry.file_name().to_str()?.parse::<u32>().ok()?;
+    let owns = std::fs::read_dir(entry.path().join("fd"))
+      .ok()?
+      .flatten()
+      .filter_map(|fd| std::fs::read_link(fd.path()).ok())
+      .any(|target| sockets.iter().any(|socket| target.as_os_str() == socket.as_str()));
+    owns.then_some(pid)
+  })
+}
+
+#[cfg(target_os = "macos")]
+fn listening_pid(port: u16) -> Option<u32> {
+  let output = Command::new("lsof")
+    .args(["-nP", "-t", &format!("-iTCP:{}", port), "-sTCP:LISTEN"])
+    .output()
+    .ok()?;
+  String::from_utf8_lossy(&output.stdout).lines().find_map(|line| line.trim().parse().ok())
+}
+
+#[cfg(target_os = "windows")]
+fn listening_pid(port: u16) -> Option<u32> {
+  let output = Command::new("netstat")
+    .args(["-ano", "-p", "TCP"])
+    .creation_flags(0x08000000)
+    .output()
+    .ok()?;
+  let suffix = format!(":{}", port);
+  String::from_utf8_lossy(&output.stdout).lines().find_map(|line| {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    match fields.as_slice() {
+      ["TCP", local, _, "LISTENING", pid] if local.ends_with(&suffix) => pid.parse().ok(),
+      _ => None,
+    }
+  })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn listening_pid(_port: u16) -> Option<u32> {
+  None
+}
+
+/// Kill the process listening on `port`. Returns its PID, or an error when
+/// no owner could be found or the kill failed.
+pub fn kill_listener(port: u16) -> Result<u32, String> {
+  let pid = listening_pid(port).ok_or_else(|| format!("No process found listening on port {}", port))?;
+  let mut system = System::new();
+  let sys_pid = Pid::from_u32(pid);
+  system.refresh_processes_specifics(ProcessesToUpdate::Some(&[sys_pid]), true, ProcessRefreshKind::nothing());
+  let process = system
+    .process(sys_pid)
+    .ok_or_else(|| format!("Process {} listening on port {} has already exited", pid, port))?;
+  diag(&format!("Killing pid={} ({}) listening on port {}", pid, process.name().to_string_lossy(), port));
+  if process.kill() {
+    Ok(pid)
+  } else {
+    Err(format!("Failed to kill pid={} listening on port {}", pid, port))
+  }
+}
@@ -0,0 +1,70 @@
+//! Raising or lowering the OS scheduling priority of the spawned backend.
+
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use crate::{diag, BackendState};
+
+/// Allowed levels with their Unix nice value and Windows priority class.
+const LEVELS: [(&str, i32, &str); 5] = [
+  ("low", 19, "Idle"),
+  ("below_normal", 10, "BelowNormal"),
+  ("normal", 0, "Normal"),
+  ("above_normal", -5, "AboveNormal"),
+  ("high", -10, "High"),
+];
+
+#[cfg(unix)]
+fn apply(pid: u32, nice: i32, _class: &str) -> std::io::Result<std::process::Output> {
+  Command::new("renice")
+    .args(["-n", &nice.to_string(), "-p", &pid.to_string()])
+    .output()
+}
+
+#[cfg(target_os = "windows")]
+fn apply(pid: u32, _nice: i32, class: &str) -> std::io::Result<std::process::Output> {
+  Command::new("powershell")
+    .args([
+      "-NoProfile",
+      "-NonInteractive",
+      "-Command",
+      &format!("(Get-Process -Id {}).PriorityClass = '{}'", pid, class),
+    ])
+    .creation_flags(0x08000000)
+    .output()
+}
+
+/// Set the backend's priority to one of low, below_normal, normal,
+/// above_normal or high. Raising it above normal usually needs admin/root;
+/// the OS refusal is returned as the error.
+#[tauri::command(async)]
+pub fn set_backend_priority(state: tauri::State<BackendState>, level: String) -> Result<(), String> {
+  set_level(state.inner(), &level)
+}
+
+pub fn set_level(backend: &BackendState, level: &str) -> Result<(), String> {
+  let (_, nice, class) = LEVELS
+    .iter()
+    .find(|(name, _, _)| *name == level)
+    .copied()
+    .ok_or_else(|| {
+      let names: Vec<&str> = LEVELS.iter().map(|(name, _, _)| *name).collect();
+      format!("Unknown priority '{}'; expected one of {}", level, names.join(", "))
+    })?;
+  let pid = backend
+    .process
+    .lock()
+    .ok()
+    .and_then(|proc| proc.as_ref().map(|child| child.id()))
+    .ok_or_else(|| "No backend process of ours is running".to_string())?;
+
+  let output = apply(pid, nice, class).map_err(|e| format!("Failed to change backend priority: {}", e))?;
+  if !output.status.success() {
+    let detail = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    return Err(format!("The OS refused to set backend priority to '{}': {}", level, detail));
+  }
+  diag(&format!("Backend pid={} priority set to {}", pid, level));
+  Ok(())
+}
@@ -0,0 +1,123 @@
+//! TALUS_PROFILE: run the backend under a profiler for diagnosing slow tallies.
+//! `cprofile` (or `1`) wraps the Python interpreter in cProfile; `py-spy`
+//! attaches py-spy to the spawned process, which also works for packaged
+//! builds. Output goes to <data dir>/profiling and its path is reported via
+//! `backend://profile-written` once the backend stops.
+
+use serde::Serialize;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::{diag, events, unix_now, BackendState};
+
+/// How long py-spy gets to write its output after the backend exits.
+const SAMPLER_FLUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Profiler {
+  CProfile,
+  PySpy,
+}
+
+/// The profile being collected for the running backend.
+pub struct ProfileRun {
+  output: PathBuf,
+  /// The attached py-spy process; `None` for cProfile, which writes from inside the backend.
+  sampler: Option<Child>,
+}
+
+#[derive(Clone, Serialize)]
+struct ProfileWritten {
+  path: PathBuf,
+}
+
+pub fn requested() -> Option<Profiler> {
+  let value = std::env::var("TALUS_PROFILE").ok()?;
+  match value.trim().to_lowercase().as_str() {
+    "" | "0" | "false" | "no" => None,
+    "1" | "true" | "yes" | "cprofile" => Some(Profiler::CProfile),
+    "py-spy" | "pyspy" => Some(Profiler::PySpy),
+    other => {
+      diag(&format!("Unknown TALUS_PROFILE '{}'; expected cprofile or py-spy. Not profiling", other));
+      None
+    }
+  }
+}
+
+pub fn output_path(data_dir: &Path, profiler: Profiler) -> PathBuf {
+  let extension = match profiler {
+    Profiler::CProfile => "prof",
+    Profiler::PySpy => "svg",
+  };
+  data_dir.join("profiling").join(format!("backend-{}.{}", unix_now(), extension))
+}
+
+/// Interpreter arguments for `python -m backend.app`, wrapped in cProfile when requested.
+pub fn python_args(profiler: Option<Profiler>, output: &Path) -> Vec<OsString> {
+  let mut args: Vec<OsString> = Vec::new();
+  if profiler == Some(Profiler::CProfile) {
+    args.extend(["-m".into(), "cProfile".into(), "-o".into(), output.as_os_str().to_os_string()]);
+  }
+  args.extend(["-m".into(), "backend.app".into()]);
+  args
+}
+
+/// Start collecting for a freshly spawned backend. For py-spy this attaches to `pid`.
+pub fn begin(backend: &BackendState, profiler: Profiler, output: PathBuf, pid: u32) {
+  if let Some(parent) = output.parent() {
+    let _ = std::fs::create_dir_all(parent);
+  }
+  let sampler = match profiler {
+    Profiler::CProfile => None,
+    Profiler::PySpy => {
+      let pid = pid.to_string();
+      let spawned = Command::new("py-spy")
+        .arg("record")
+        .arg("--pid")
+        .arg(&pid)
+        .arg("--output")
+        .arg(&output)
+        .stdin(Stdio::null())
+        .spawn();
+      match spawned {
+        Ok(child) => Some(child),
+        Err(err) => {
+          diag(&format!("Failed to start py-spy (is it installed and on PATH?): {}", err));
+          return;
+        }
+      }
+    }
+  };
+  diag(&format!("Profiling backend; output will be written to {}", output.display()));
+  if let Ok(mut run) = backend.profile.lock() {
+    *run = Some(ProfileRun { output, sampler });
+  }
+}
+
+/// Call after the backend has stopped: let py-spy flush, then report where the profile landed.
+pub fn finish(app: &tauri::AppHandle, backend: &BackendState) {
+  let Some(mut run) = backend.profile.lock().ok().and_then(|mut run| run.take()) else {
+    return;
+  };
+  if let Some(mut sampler) = run.sampler.take() {
+    // py-spy stops and writes its output on its own once the target is gone
+    let deadline = Instant::now() + SAMPLER_FLUSH_TIMEOUT;
+    while matches!(sampler.try_wait(), Ok(None)) && Instant::now() < deadline {
+      std::thread::sleep(Duration::from_millis(100));
+    }
+    if matches!(sampler.try_wait(), Ok(None)) {
+      diag("py-spy did not finish in time; killing it");
+      let _ = sampler.kill();
+      let _ = sampler.wait();
+    }
+  }
+
+  if run.output.exists() {
+    diag(&format!("Backend profile written to {}", run.output.display()));
+    events::emit_lifecycle(app, backend, "backend://profile-written", ProfileWritten { path: run.output });
+  } else {
+    diag(&format!("Backend stopped but no profile was written to {}", run.output.display()));
+  }
+}
@@ -0,0 +1,58 @@
+//! Named backend profiles, each with its own data dir and port, so separate
+//! competitions can run isolated tallies from one install.
+
+use crate::config::{self, Profile};
+use crate::supervisor::RestartReason;
+use crate::{diag, pending_restart, restart, BackendState};
+
+#[derive(serde::Serialize)]
+pub struct ProfileList {
+  profiles: Vec<Profile>,
+  active: Option<String>,
+}
+
+#[tauri::command]
+pub fn list_profiles(app: tauri::AppHandle) -> ProfileList {
+  let config = config::current(&app);
+  ProfileList {
+    profiles: config.profiles,
+    active: config.active_profile,
+  }
+}
+
+/// Add a profile, or replace the existing one with the same name.
+#[tauri::command]
+pub fn save_profile(app: tauri::AppHandle, profile: Profile) -> Result<(), String> {
+  if profile.name.trim().is_empty() {
+    return Err("Profile name must not be empty".to_string());
+  }
+  if profile.port == 0 {
+    return Err("Profile port must be between 1 and 65535".to_string());
+  }
+  config::update(&app, |config| {
+    config.profiles.retain(|existing| existing.name != profile.name);
+    config.profiles.push(profile);
+  })?;
+  Ok(())
+}
+
+#[tauri::command]
+pub fn delete_profile(app: tauri::AppHandle, name: String) -> Result<(), String> {
+  let config = config::current(&app);
+  if config.active_profile.as_deref() == Some(name.as_str()) {
+    return Err(format!("Profile '{}' is active; switch to another profile first", name));
+  }
+  config::update(&app, |config| config.profiles.retain(|profile| profile.name != name))?;
+  Ok(())
+}
+
+/// Make `name` the active profile and restart the backend with its data dir and port.
+#[tauri::command(async)]
+pub fn switch_profile(app: tauri::AppHandle, state: tauri::State<BackendState>, name: String) -> Result<(), String> {
+  if !config::current(&app).profiles.iter().any(|profile| profile.name == name) {
+    return Err(format!("Unknown profile '{}'", name));
+  }
+  config::update(&app, |config| config.active_profile = Some(name.clone()))?;
+  diag(&format!("Switched to profile '{}'; restarting backend", name));
+  restart(&app, state.inner(), RestartReason::Config).map_err(pending_restart)
+}
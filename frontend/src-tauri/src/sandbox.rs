@@ -0,0 +1,140 @@
+//! A throwaway second backend for trying things out (templates, imports)
+//! without touching real data: it gets a fresh temp data dir and its own
+//! port, and is tracked here rather than in `BackendState`, so the
+//! supervisor, tray and lifecycle events never see it. A main-backend
+//! restart sweeps stray backend processes and takes the sandbox with it.
+
+use serde::Serialize;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use crate::{
+  backend_reachable, config, determine_project_root, diag, find_packaged_backend, free_port, long_path,
+  venv_python_path,
+};
+
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+pub struct SandboxState(Mutex<Option<Sandbox>>);
+
+pub struct Sandbox {
+  child: Child,
+  data_dir: PathBuf,
+  port: u16,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SandboxInfo {
+  port: u16,
+  data_dir: PathBuf,
+}
+
+fn command(app: &tauri::AppHandle) -> Command {
+  let project_root = determine_project_root(Some(app));
+  if let Some(binary) = find_packaged_backend(Some(app), &project_root) {
+    let binary = long_path(&binary);
+    let mut command = Command::new(&binary);
+    if let Some(dir) = binary.parent() {
+      command.current_dir(dir);
+    }
+    return command;
+  }
+  let venv_python = venv_python_path(&project_root);
+  let python = if venv_python.exists() {
+    long_path(&venv_python)
+  } else {
+    PathBuf::from(if cfg!(target_os = "windows") { "python" } else { "python3" })
+  };
+  let mut command = Command::new(python);
+  command.args(["-m", "backend.app"]).current_dir(&project_root);
+  command
+}
+
+fn discard(sandbox: Option<Sandbox>) {
+  if let Some(mut sandbox) = sandbox {
+    let _ = sandbox.child.kill();
+    let _ = sandbox.child.wait();
+    if let Err(err) = std::fs::remove_dir_all(&sandbox.data_dir) {
+      diag(&format!("Failed to delete sandbox dir {}: {}", sandbox.data_dir.display(), err));
+    }
+    diag(&format!("Sandbox backend on port {} discarded", sandbox.port));
+  }
+}
+
+/// Kill the sandbox, if any; called on app exit.
+pub fn discard_all(state: &SandboxState) {
+  if let Ok(mut slot) = state.0.lock() {
+    discard(slot.take());
+  }
+}
+
+/// Start a sandbox backend, or return the one already running.
+#[tauri::command(async)]
+pub fn start_sandbox_backend(app: tauri::AppHandle, state: tauri::State<SandboxState>) -> Result<SandboxInfo, String> {
+  let mut slot = state.0.lock().map_err(|_| "Sandbox lock poisoned".to_string())?;
+  if let Some(sandbox) = slot.as_mut() {
+    if matches!(sandbox.child.try_wait(), Ok(None)) {
+      return Ok(SandboxInfo { port: sandbox.port, data_dir: sandbox.data_dir.clone() });
+    }
+    discard(slot.take());
+  }
+
+  let port = free_port().ok_or_else(|| "No free port for the sandbox backend".to_string())?;
+  let data_dir = std::env::temp_dir().join(format!("talus-sandbox-{}-{}", std::process::id(), port));
+  std::fs::create_dir_all(&data_dir)
+    .map_err(|e| format!("Failed to create sandbox dir {}: {}", data_dir.display(), e))?;
+
+  let mut command = command(&app);
+  command
+    .env("TALUS_DAEMON", "1")
+    .env("TALUS_PORT", port.to_string())
+    .env("TALUS_DATA_DIR", &data_dir)
+    .env("TALUS_HEALTH_SOCKET", "")
+    .env("TALUS_INMEMORY", "0")
+    .stdout(Stdio::null())
+    .stderr(Stdio::null());
+  #[cfg(target_os = "windows")]
+  {
+    command.creation_flags(0x08000000);
+  }
+  let child = match command.spawn() {
+    Ok(child) => child,
+    Err(err) => {
+      let _ = std::fs::remove_dir_all(&data_dir);
+      return Err(format!("Failed to start sandbox backend: {}", err));
+    }
+  };
+  diag(&format!("Sandbox backend pid={} on port {} with data in {}", child.id(), port, data_dir.display()));
+  let mut sandbox = Sandbox { child, data_dir, port };
+
+  let started = Instant::now();
+  while !backend_reachable(config::DEFAULT_HOST, port) {
+    if let Ok(Some(status)) = sandbox.child.try_wait() {
+      discard(Some(sandbox));
+      return Err(format!("Sandbox backend exited during startup ({})", status));
+    }
+    if started.elapsed() > READY_TIMEOUT {
+      discard(Some(sandbox));
+      return Err(format!("Sandbox backend not reachable after {}s", READY_TIMEOUT.as_secs()));
+    }
+    std::thread::sleep(Duration::from_millis(200));
+  }
+
+  let info = SandboxInfo { port, data_dir: sandbox.data_dir.clone() };
+  *slot = Some(sandbox);
+  Ok(info)
+}
+
+/// Kill the sandbox backend and delete its data dir. No-op without one.
+#[tauri::command(async)]
+pub fn discard_sandbox(state: tauri::State<SandboxState>) -> Result<(), String> {
+  let mut slot = state.0.lock().map_err(|_| "Sandbox lock poisoned".to_string())?;
+  discard(slot.take());
+  Ok(())
+}
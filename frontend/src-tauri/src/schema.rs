@@ -0,0 +1,140 @@
+//! Project file schema compatibility. Version strings don't say whether a
+//! saved project has the shape this frontend expects, so before loading one
+//! the UI asks the backend for the file's schema version and compares it
+//! with `REQUIRED_SCHEMA`. An older file can be upgraded through the
+//! backend, always after a copy is saved under `<data dir>/backups`.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::backend_api::{self, CommandError};
+use crate::{backup, config, diag, events, http, unix_now, BackendState};
+
+/// Schema of saved project files this frontend was built against.
+pub const REQUIRED_SCHEMA: u32 = 1;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Migrations rewrite the whole file, so allow for a large project.
+const MIGRATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Clone, Serialize)]
+pub struct SchemaCompatibility {
+  compatible: bool,
+  backend_schema: u32,
+  required_schema: u32,
+  /// The file is older than required and the backend can upgrade it.
+  migration_available: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SchemaMigrated {
+  path: PathBuf,
+  from: u32,
+  to: u32,
+  /// Copy of the file taken before migrating.
+  backup: PathBuf,
+}
+
+fn schema_compatibility(backend: &BackendState, path: &Path) -> Result<SchemaCompatibility, String> {
+  let body = serde_json::json!({ "path": path });
+  let response = http::post_json(&backend.host(), backend.port(), "/api/v1/schema/check", &body, REQUEST_TIMEOUT)?;
+  let body = match response.status {
+    404 => return Err("This backend version does not report project schemas".to_string()),
+    _ if response.is_success() => response.json()?,
+    status => return Err(format!("/api/v1/schema/check returned HTTP {}: {}", status, response.body.trim())),
+  };
+  let field = |name: &str| body.get(name).and_then(|v| v.as_u64()).map(|v| v as u32);
+  // A missing or unreadable file has no known schema; never report it as current
+  let backend_schema = field("schema_version")
+    .ok_or_else(|| format!("{} is missing or not a readable project file; its schema is unknown", path.display()))?;
+  let latest_schema = field("latest_schema").unwrap_or(backend_schema);
+  let backend_can_migrate = body.get("migration_available").and_then(|v| v.as_bool()).unwrap_or(false);
+  Ok(SchemaCompatibility {
+    compatible: backend_schema == REQUIRED_SCHEMA,
+    backend_schema,
+    required_schema: REQUIRED_SCHEMA,
+    migration_available: backend_schema < REQUIRED_SCHEMA && latest_schema >= REQUIRED_SCHEMA && backend_can_migrate,
+  })
+}
+
+/// Copy `path` to `<data dir>/backups/pre-migration-<ts>/`.
+fn back_up(app: &tauri::AppHandle, path: &Path) -> Result<PathBuf, String> {
+  let name = path.file_name().ok_or_else(|| format!("{} is not a file", path.display()))?;
+  let dir = config::data_dir(app).join(backup::BACKUP_DIR).join(format!("pre-migration-{}", unix_now()));
+  std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+  let target = dir.join(name);
+  std::fs::copy(path, &target).map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+  Ok(target)
+}
+
+/// Compare the schema of the project file at `path` with the one this
+/// frontend needs, before loading it. An incompatible file should not be
+/// opened; when `migration_available` is set, `run_schema_migration` can
+/// upgrade it. A missing file is an error, not "compatible".
+#[tauri::command(async)]
+pub fn check_schema_compatibility(
+  app: tauri::AppHandle,
+  state: tauri::State<BackendState>,
+  path: String,
+) -> Result<SchemaCompatibility, CommandError> {
+  compatibility(&app, state.inner(), PathBuf::from(path))
+}
+
+fn compatibility(app: &tauri::AppHandle, backend: &BackendState, path: PathBuf) -> Result<SchemaCompatibility, CommandError> {
+  let backend = backend.clone();
+  backend_api::with_timeout(app, "check_schema_compatibility", move || schema_compatibility(&backend, &path))
+}
+
+/// Copy the project file at `path` into the backup dir, then have the
+/// backend migrate it to the latest schema. Nothing is migrated if the copy
+/// fails.
+#[tauri::command(async)]
+pub fn run_schema_migration(app: tauri::AppHandle, state: tauri::State<BackendState>, path: String) -> Result<SchemaMigrated, CommandError> {
+  let backend = state.inner();
+  let path = PathBuf::from(path);
+  let before = compatibility(&app, backend, path.clone())?;
+  if before.compatible {
+    return Err(format!("{} is already at schema {}", path.display(), before.backend_schema).into());
+  }
+  if !before.migration_available {
+    return Err(format!(
+      "No migration from schema {} to {} is available in this backend",
+      before.backend_schema, before.required_schema
+    )
+    .into());
+  }
+
+  let backup = back_up(&app, &path).map_err(|e| format!("Pre-migration backup failed; not migrating: {}", e))?;
+  diag(&format!(
+    "Migrating {} schema {} -> {} (backup {})",
+    path.display(),
+    before.backend_schema,
+    before.required_schema,
+    backup.display()
+  ));
+  let body = serde_json::json!({ "path": path });
+  let (host, port) = (backend.host(), backend.port());
+  let response = backend_api::with_timeout_of("run_schema_migration", MIGRATION_TIMEOUT, move || {
+    http::post_json(&host, port, "/api/v1/schema/migrate", &body, MIGRATION_TIMEOUT)
+  })?;
+  if !response.is_success() {
+    let detail = response
+      .json()
+      .ok()
+      .and_then(|body| body.get("error").and_then(|e| e.as_str()).map(str::to_string))
+      .unwrap_or_else(|| response.body.trim().to_string());
+    diag(&format!("Schema migration failed: {}", detail));
+    return Err(format!("Schema migration failed (backup at {}): {}", backup.display(), detail).into());
+  }
+
+  let after = compatibility(&app, backend, path.clone())?;
+  let migrated = SchemaMigrated {
+    path,
+    from: before.backend_schema,
+    to: after.backend_schema,
+    backup,
+  };
+  diag(&format!("Project schema migrated {} -> {}", migrated.from, migrated.to));
+  events::emit(&app, "schema://migrated", migrated.clone());
+  Ok(migrated)
+}
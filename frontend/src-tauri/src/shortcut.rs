@@ -0,0 +1,59 @@
+//! Global show/hide accelerator for quick access to the window during events.
+
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::{config, diag, toggle_main_window};
+
+pub const DEFAULT_SHORTCUT: &str = "CommandOrControl+Shift+T";
+
+pub fn plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+  tauri_plugin_global_shortcut::Builder::new()
+    .with_handler(|app, shortcut, event| {
+      if event.state() == ShortcutState::Pressed {
+        diag(&format!("Global shortcut {} pressed; toggling window", shortcut));
+        toggle_main_window(app);
+      }
+    })
+    .build()
+}
+
+/// Register the configured accelerator at startup. A combo already taken by
+/// another app is logged rather than failing setup.
+pub fn register_configured(app: &tauri::AppHandle) {
+  let accelerator = config::current(app).global_shortcut;
+  if accelerator.is_empty() {
+    return;
+  }
+  match app.global_shortcut().register(accelerator.as_str()) {
+    Ok(()) => diag(&format!("Registered global shortcut {}", accelerator)),
+    Err(err) => diag(&format!("Failed to register global shortcut {}: {}", accelerator, err)),
+  }
+}
+
+/// Swap the global accelerator. An empty string disables it. If the new combo
+/// can't be registered the previous one is restored and an error returned.
+#[tauri::command]
+pub fn set_global_shortcut(app: tauri::AppHandle, accelerator: String) -> Result<(), String> {
+  let accelerator = accelerator.trim().to_string();
+  let previous = config::current(&app).global_shortcut;
+  let shortcuts = app.global_shortcut();
+
+  if !previous.is_empty() && shortcuts.is_registered(previous.as_str()) {
+    shortcuts
+      .unregister(previous.as_str())
+      .map_err(|e| format!("Failed to release {}: {}", previous, e))?;
+  }
+
+  if !accelerator.is_empty() {
+    if let Err(err) = shortcuts.register(accelerator.as_str()) {
+      if !previous.is_empty() {
+        let _ = shortcuts.register(previous.as_str());
+      }
+      return Err(format!("Could not register {} (it may already be in use): {}", accelerator, err));
+    }
+  }
+
+  config::update(&app, |config| config.global_shortcut = accelerator.clone())?;
+  diag(&format!("Global shortcut changed from '{}' to '{}'", previous, accelerator));
+  Ok(())
+}
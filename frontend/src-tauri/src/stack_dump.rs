@@ -0,0 +1,79 @@
+//! Thread dumps of a hung backend. Each spawned backend is told (via
+//! TALUS_STACK_DUMP) where to append a faulthandler dump when it gets SIGQUIT;
+//! before the watchdog kills an unresponsive backend it sends the signal and
+//! copies the new dump into its own file in the crash directory. faulthandler
+//! writes from the signal handler, so this works even when no Python thread
+//! can run. Windows has no SIGQUIT, so there the backend's `/api/v1/debug/dump`
+//! endpoint is tried instead, which only helps if the HTTP server still runs
+//! and, since the endpoint is a debug one, only with TALUS_DEV=1.
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::{diag, diagnostic_dir, http, run_with_timeout, unix_now, BackendState, CLEANUP_TIMEOUT};
+
+/// How long to wait for the dump to be written.
+const DUMP_WAIT: Duration = Duration::from_secs(2);
+
+fn crash_dir() -> Option<PathBuf> {
+  diagnostic_dir().map(|dir| dir.join("crashes"))
+}
+
+/// File the backend appends SIGQUIT dumps to, passed as TALUS_STACK_DUMP.
+pub fn dump_file() -> Option<PathBuf> {
+  crash_dir().map(|dir| dir.join("backend-stacks.log"))
+}
+
+/// Ask the backend for a dump of every thread and save it as
+/// `crashes/backend-hang-<ts>.txt`. Returns the file's path, or `None` if no
+/// dump could be had.
+pub fn capture(backend: &BackendState) -> Option<PathBuf> {
+  let pid = backend.process.lock().ok().and_then(|proc| proc.as_ref().map(|child| child.id()))?;
+  let dump = if cfg!(unix) { signal_dump(pid) } else { endpoint_dump(backend) };
+  let Some(dump) = dump.filter(|dump| !dump.trim().is_empty()) else {
+    diag(&format!("No thread dump from hung backend pid={}", pid));
+    return None;
+  };
+  let dir = crash_dir()?;
+  let _ = std::fs::create_dir_all(&dir);
+  let path = dir.join(format!("backend-hang-{}.txt", unix_now()));
+  match std::fs::write(&path, format!("Thread dump of unresponsive backend pid={}\n\n{}", pid, dump)) {
+    Ok(()) => {
+      diag(&format!("Saved thread dump of hung backend to {}", path.display()));
+      Some(path)
+    }
+    Err(err) => {
+      diag(&format!("Failed to save thread dump to {}: {}", path.display(), err));
+      None
+    }
+  }
+}
+
+/// SIGQUIT the backend and return what it appended to the dump file.
+fn signal_dump(pid: u32) -> Option<String> {
+  let file = dump_file()?;
+  let start = std::fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+  run_with_timeout(Command::new("kill").args(["-QUIT", &pid.to_string()]), "kill -QUIT", CLEANUP_TIMEOUT);
+
+  // Wait for the file to grow, then for the writes to settle
+  let deadline = Instant::now() + DUMP_WAIT;
+  let mut last_len = start;
+  while Instant::now() < deadline {
+    std::thread::sleep(Duration::from_millis(100));
+    let len = std::fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+    if len > start && len == last_len {
+      break;
+    }
+    last_len = len;
+  }
+  let bytes = std::fs::read(&file).ok()?;
+  bytes.get(start as usize..).map(|new| String::from_utf8_lossy(new).into_owned())
+}
+
+fn endpoint_dump(backend: &BackendState) -> Option<String> {
+  http::post_json(&backend.host(), backend.port(), "/api/v1/debug/dump", &serde_json::json!({}), DUMP_WAIT)
+    .ok()
+    .filter(|response| response.is_success())
+    .map(|response| response.body)
+}
@@ -0,0 +1,144 @@
+//! Per-stage startup timeouts. start_backend reports each stage it enters
+//! (`backend://startup-stage`); a monitor thread checks the current stage
+//! against its own limit. A stage that overruns is treated as a stuck
+//! startup: the partial backend is killed and the app enters safe mode with
+//! `backend://startup-stuck` naming the stage, instead of leaving the user on
+//! the splash indefinitely.
+
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use crate::{diag, enter_safe_mode, events, terminate_backend_process, BackendState, PORT_RELEASE_TIMEOUT, STARTUP_TIMEOUT, WARMUP_TIMEOUT};
+
+const MONITOR_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+  /// Killing stray backends and giving them a moment to exit.
+  Cleanup,
+  /// Waiting for the OS to release the port after a killed backend.
+  Port,
+  /// Resolving the backend and launching the process.
+  Spawn,
+  /// Waiting for the backend to answer health checks.
+  Health,
+  /// The first representative request.
+  Warmup,
+}
+
+impl Stage {
+  /// Generous bounds: each covers the stage's own internal timeouts plus slack,
+  /// so only a genuinely stuck stage trips it.
+  fn timeout(self) -> Duration {
+    match self {
+      Stage::Cleanup => Duration::from_secs(15),
+      Stage::Port => PORT_RELEASE_TIMEOUT + Duration::from_secs(5),
+      Stage::Spawn => Duration::from_secs(10),
+      Stage::Health => STARTUP_TIMEOUT + Duration::from_secs(15),
+      Stage::Warmup => WARMUP_TIMEOUT + Duration::from_secs(5),
+    }
+  }
+}
+
+#[derive(Clone, Serialize)]
+struct StageEntered {
+  stage: Stage,
+  timeout_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct StartupStuck {
+  stage: Stage,
+  elapsed_ms: u64,
+  timeout_ms: u64,
+  message: String,
+}
+
+/// Record that start_backend moved on to `stage`.
+pub fn enter(app: &tauri::AppHandle, backend: &BackendState, stage: Stage) {
+  if let Ok(mut current) = backend.startup_stage.lock() {
+    *current = Some((stage, Instant::now()));
+  }
+  events::emit_lifecycle(app, backend, "backend://startup-stage", StageEntered { stage, timeout_ms: stage.timeout().as_millis() as u64 });
+}
+
+/// Whether the running startup was aborted; start_backend checks this between
+/// stages and stops without spawning (or keeping) a backend.
+pub fn aborted(backend: &BackendState) -> bool {
+  backend.startup_aborted.load(Ordering::SeqCst)
+}
+
+/// Clears the current stage when start_backend returns, however it returns.
+pub struct Tracker<'a>(&'a BackendState);
+
+impl<'a> Tracker<'a> {
+  pub fn new(backend: &'a BackendState) -> Self {
+    backend.startup_aborted.store(false, Ordering::SeqCst);
+    Tracker(backend)
+  }
+}
+
+impl Drop for Tracker<'_> {
+  fn drop(&mut self) {
+    if let Ok(mut current) = self.0.startup_stage.lock() {
+      *current = None;
+    }
+  }
+}
+
+/// Kill whatever the startup got as far as spawning and drop into safe mode.
+fn abort(app: &tauri::AppHandle, backend: &BackendState, stage: Stage, elapsed: Duration, message: String) {
+  if backend.startup_aborted.swap(true, Ordering::SeqCst) {
+    return;
+  }
+  diag(&message);
+  terminate_backend_process(&backend.process, "startup aborted", Duration::ZERO);
+  crate::set_mode(backend, None);
+  events::emit_lifecycle(
+    app,
+    backend,
+    "backend://startup-stuck",
+    StartupStuck {
+      stage,
+      elapsed_ms: elapsed.as_millis() as u64,
+      timeout_ms: stage.timeout().as_millis() as u64,
+      message: message.clone(),
+    },
+  );
+  enter_safe_mode(app, backend, message);
+}
+
+pub fn spawn_monitor(app: tauri::AppHandle, backend: BackendState) {
+  std::thread::spawn(move || loop {
+    std::thread::sleep(MONITOR_INTERVAL);
+    let current = backend.startup_stage.lock().ok().and_then(|current| *current);
+    if let Some((stage, since)) = current {
+      let elapsed = since.elapsed();
+      if elapsed >= stage.timeout() && !aborted(&backend) {
+        let message = format!(
+          "Startup stuck in the {:?} stage for {}s (limit {}s); stopped the backend and entered safe mode",
+          stage,
+          elapsed.as_secs(),
+          stage.timeout().as_secs()
+        );
+        abort(&app, &backend, stage, elapsed, message);
+      }
+    }
+  });
+}
+
+/// Give up on the startup in progress now rather than waiting for its stage
+/// to time out, e.g. from a "Stop waiting" button on the splash. Returns false
+/// if no startup was running.
+#[tauri::command]
+pub fn abort_startup(app: tauri::AppHandle, state: tauri::State<BackendState>) -> bool {
+  let backend = state.inner();
+  let Some((stage, since)) = backend.startup_stage.lock().ok().and_then(|current| *current) else {
+    return false;
+  };
+  let message = format!("Startup aborted by the user during the {:?} stage; entered safe mode", stage);
+  abort(&app, backend, stage, since.elapsed(), message);
+  true
+}
@@ -0,0 +1,64 @@
+//! Rolling history of backend startup durations, kept next to the diagnostic log
+//! so a diagnostics view can spot launches getting slower over time.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::{diag, diagnostic_dir, unix_now};
+
+const MAX_ENTRIES: usize = 50;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StartupRecord {
+  /// Unix seconds when the backend became reachable.
+  pub timestamp: u64,
+  /// Time from spawn until the backend first answered.
+  pub duration_ms: u64,
+  /// How the backend was launched: "packaged", "venv" or "system".
+  pub mode: String,
+}
+
+fn history_path() -> Option<PathBuf> {
+  diagnostic_dir().map(|d| d.join("startup-history.json"))
+}
+
+fn read_history() -> Vec<StartupRecord> {
+  history_path()
+    .and_then(|path| std::fs::read_to_string(path).ok())
+    .and_then(|text| serde_json::from_str(&text).ok())
+    .unwrap_or_default()
+}
+
+/// Append one successful startup, dropping the oldest entries beyond MAX_ENTRIES.
+pub fn record(mode: &str, duration_ms: u64) {
+  let Some(path) = history_path() else {
+    return;
+  };
+  let mut history = read_history();
+  history.push(StartupRecord {
+    timestamp: unix_now(),
+    duration_ms,
+    mode: mode.to_string(),
+  });
+  if history.len() > MAX_ENTRIES {
+    history.drain(..history.len() - MAX_ENTRIES);
+  }
+
+  if let Some(parent) = path.parent() {
+    let _ = std::fs::create_dir_all(parent);
+  }
+  match serde_json::to_string(&history) {
+    Ok(text) => {
+      if let Err(err) = std::fs::write(&path, text) {
+        diag(&format!("Failed to write startup history {}: {}", path.display(), err));
+      }
+    }
+    Err(err) => diag(&format!("Failed to serialize startup history: {}", err)),
+  }
+}
+
+/// Oldest first, as recorded.
+#[tauri::command]
+pub fn get_startup_history() -> Vec<StartupRecord> {
+  read_history()
+}
@@ -0,0 +1,283 @@
+//! Crash supervisor and health watchdog for the backend we spawned.
+//! A single thread ticks every WATCHDOG_INTERVAL: if our child exited, it is
+//! restarted (reason "crash"); if it is alive but stops answering for
+//! UNRESPONSIVE_TICKS ticks, a thread dump is taken (see stack_dump) and it
+//! is killed and restarted (reason "watchdog").
+//! After MAX_RECOVERY_ATTEMPTS recoveries without a STABLE_UPTIME stretch in
+//! between, `backend://fatal` is emitted and supervision stops.
+//! A wall-clock gap of SLEEP_GAP between ticks means the system slept; the
+//! backend is re-checked, `backend://resumed-from-sleep` emitted, and it is
+//! restarted (reason "resume") if it didn't survive.
+//! A backend over the configured RSS limit (TALUS_MAX_RSS) gets
+//! `backend://memory-limit`, and with the "restart" action is restarted
+//! (reason "memory") under the same recovery budget.
+//! Adopted and remote backends, and a stopped backend, are left alone.
+
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::config::MemoryLimitAction;
+use crate::{backend_api, config, diag, events, restart, stack_dump, BackendState};
+
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(2);
+const UNRESPONSIVE_TICKS: u32 = 3;
+const MAX_RECOVERY_ATTEMPTS: u32 = 3;
+/// Healthy this long after a recovery and the attempt budget is refilled.
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+/// Ticks this far apart on the wall clock mean the machine was asleep. The
+/// monotonic clock can't be used: on Linux and macOS it stops during suspend.
+const SLEEP_GAP: Duration = Duration::from_secs(30);
+/// Health checks after waking, giving the network stack a moment to come back.
+const RESUME_CHECKS: u32 = 3;
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RestartReason {
+  User,
+  Crash,
+  Watchdog,
+  Config,
+  Resume,
+  Memory,
+  Import,
+}
+
+#[derive(Clone, Serialize)]
+struct Restarting {
+  reason: RestartReason,
+}
+
+#[derive(Clone, Serialize)]
+struct Crashed {
+  status: String,
+}
+
+#[derive(Clone, Serialize)]
+struct Unresponsive {
+  missed_checks: u32,
+  /// Thread dump taken before the restart, if the backend produced one.
+  stack_dump: Option<PathBuf>,
+}
+
+#[derive(Clone, Serialize)]
+struct MemoryLimit {
+  rss_bytes: u64,
+  limit_bytes: u64,
+  action: MemoryLimitAction,
+}
+
+#[derive(Clone, Serialize)]
+struct ResumedFromSleep {
+  slept_secs: u64,
+  /// False if the backend had to be restarted.
+  backend_survived: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct Fatal {
+  error: String,
+  attempts: u32,
+}
+
+#[derive(Clone, Serialize)]
+struct RecoveryReset {
+  previous_attempts: u32,
+  was_exhausted: bool,
+}
+
+/// Marks the backend as starting for as long as it's alive, so the watchdog
+/// doesn't fire on a backend that hasn't come up yet.
+pub struct Starting<'a>(&'a AtomicBool);
+
+impl<'a> Starting<'a> {
+  pub fn new(flag: &'a AtomicBool) -> Self {
+    flag.store(true, Ordering::SeqCst);
+    Starting(flag)
+  }
+}
+
+impl Drop for Starting<'_> {
+  fn drop(&mut self) {
+    self.0.store(false, Ordering::SeqCst);
+  }
+}
+
+pub fn notify_restarting(app: &tauri::AppHandle, backend: &BackendState, reason: RestartReason) {
+  diag(&format!("Restarting backend (reason: {:?})", reason));
+  events::emit_lifecycle(app, backend, "backend://restarting", Restarting { reason });
+}
+
+/// Re-check the backend after a wake-up and restart our own backend if it no
+/// longer answers. Emits `backend://resumed-from-sleep`; returns whether the
+/// backend survived.
+fn check_after_resume(app: &tauri::AppHandle, backend: &BackendState, slept: Duration) -> bool {
+  if slept.is_zero() {
+    diag("Reconnect requested; re-checking backend");
+  } else {
+    diag(&format!("System resumed after ~{}s asleep; re-checking backend", slept.as_secs()));
+  }
+  let ours = backend.process.lock().is_ok_and(|proc| proc.is_some());
+  let mut survived = backend.is_reachable();
+  for _ in 1..RESUME_CHECKS {
+    if survived {
+      break;
+    }
+    std::thread::sleep(Duration::from_secs(1));
+    survived = backend.is_reachable();
+  }
+  events::emit_lifecycle(
+    app,
+    backend,
+    "backend://resumed-from-sleep",
+    ResumedFromSleep {
+      slept_secs: slept.as_secs(),
+      backend_survived: survived || !ours,
+    },
+  );
+  if ours && !survived {
+    // A restart already in progress brings the backend back anyway
+    let _ = restart(app, backend, RestartReason::Resume);
+  }
+  survived || !ours
+}
+
+/// Compare the backend's RSS against the configured limit. Warns once per
+/// excursion over it (`warned` tracks that); returns true when the backend
+/// should be restarted.
+fn over_memory_limit(app: &tauri::AppHandle, backend: &BackendState, warned: &mut bool) -> bool {
+  let Some(limit_bytes) = config::max_rss_bytes(app) else {
+    return false;
+  };
+  let pid = backend.process.lock().ok().and_then(|proc| proc.as_ref().map(|child| child.id()));
+  let Some(rss_bytes) = pid.and_then(backend_api::rss) else {
+    return false;
+  };
+  if rss_bytes <= limit_bytes {
+    *warned = false;
+    return false;
+  }
+  let action = config::memory_limit_action(app);
+  if action == MemoryLimitAction::Warn && *warned {
+    return false;
+  }
+  *warned = true;
+  diag(&format!("Backend RSS {} bytes exceeds limit {} bytes (action: {:?})", rss_bytes, limit_bytes, action));
+  events::emit_lifecycle(app, backend, "backend://memory-limit", MemoryLimit { rss_bytes, limit_bytes, action });
+  action == MemoryLimitAction::Restart
+}
+
+pub fn spawn(app: tauri::AppHandle, backend: BackendState) {
+  std::thread::spawn(move || {
+    let mut missed = 0;
+    let mut memory_warned = false;
+    let mut healthy_since: Option<Instant> = None;
+    let mut last_tick = SystemTime::now();
+    loop {
+      std::thread::sleep(WATCHDOG_INTERVAL);
+      let now = SystemTime::now();
+      let gap = now.duration_since(last_tick).unwrap_or_default();
+      last_tick = now;
+      if backend.starting.load(Ordering::SeqCst) || backend.recovery_exhausted.load(Ordering::SeqCst) {
+        missed = 0;
+        continue;
+      }
+      if gap >= SLEEP_GAP {
+        missed = 0;
+        healthy_since = None;
+        check_after_resume(&app, &backend, gap);
+        last_tick = SystemTime::now();
+        continue;
+      }
+
+      // Take an exited child out of the slot; `None` means nothing of ours to supervise.
+      let exited = match backend.process.lock() {
+        Ok(mut proc) => match proc.as_mut().map(|child| child.try_wait()) {
+          None => {
+            missed = 0;
+            healthy_since = None;
+            continue;
+          }
+          Some(Ok(Some(status))) => {
+            proc.take();
+            Some(status.to_string())
+          }
+          Some(_) => None,
+        },
+        Err(_) => continue,
+      };
+
+      let reason = if let Some(status) = exited {
+        diag(&format!("Backend exited unexpectedly with status {}", status));
+        backend.crash_count.fetch_add(1, Ordering::SeqCst);
+        if let Ok(mut since) = backend.backend_since.lock() {
+          *since = None;
+        }
+        events::emit_lifecycle(&app, &backend, "backend://crashed", Crashed { status });
+        RestartReason::Crash
+      } else if backend.is_reachable() {
+        missed = 0;
+        if !over_memory_limit(&app, &backend, &mut memory_warned) {
+          let since = *healthy_since.get_or_insert_with(Instant::now);
+          if since.elapsed() >= STABLE_UPTIME {
+            backend.recovery_attempts.store(0, Ordering::SeqCst);
+          }
+          continue;
+        }
+        memory_warned = false;
+        RestartReason::Memory
+      } else {
+        missed += 1;
+        healthy_since = None;
+        if missed < UNRESPONSIVE_TICKS {
+          continue;
+        }
+        diag(&format!("Backend missed {} health checks in a row; restarting it", missed));
+        let stack_dump = stack_dump::capture(&backend);
+        events::emit_lifecycle(&app, &backend, "backend://unresponsive", Unresponsive { missed_checks: missed, stack_dump });
+        RestartReason::Watchdog
+      };
+      missed = 0;
+      healthy_since = None;
+
+      let attempts = backend.recovery_attempts.fetch_add(1, Ordering::SeqCst) + 1;
+      if attempts > MAX_RECOVERY_ATTEMPTS {
+        let error = format!("Backend failed {} recovery attempts; giving up", MAX_RECOVERY_ATTEMPTS);
+        diag(&error);
+        backend.recovery_exhausted.store(true, Ordering::SeqCst);
+        // Don't leave a hung process behind once we stop supervising it.
+        crate::terminate_backend_process(&backend.process, "recovery exhausted", config::shutdown_timeout(&app));
+        events::emit_lifecycle(&app, &backend, "backend://fatal", Fatal { error, attempts: MAX_RECOVERY_ATTEMPTS });
+        continue;
+      }
+      if restart(&app, &backend, reason).is_err() {
+        // One already under way isn't another recovery attempt
+        backend.recovery_attempts.fetch_sub(1, Ordering::SeqCst);
+      }
+    }
+  });
+}
+
+/// Clear the recovery budget (and a `backend://fatal` give-up) and start the
+/// backend afresh, for when the user has fixed what kept it crashing.
+#[tauri::command(async)]
+pub fn reset_recovery(app: tauri::AppHandle, state: tauri::State<BackendState>) -> Result<(), String> {
+  let backend = state.inner();
+  let previous_attempts = backend.recovery_attempts.swap(0, Ordering::SeqCst);
+  let was_exhausted = backend.recovery_exhausted.swap(false, Ordering::SeqCst);
+  diag(&format!(
+    "Recovery reset (attempts={}, exhausted={}); starting backend fresh",
+    previous_attempts, was_exhausted
+  ));
+  events::emit_lifecycle(&app, backend, "backend://recovery-reset", RecoveryReset { previous_attempts, was_exhausted });
+  restart(&app, backend, RestartReason::User)
+}
+
+/// Re-check the backend as after a system resume (the event carries
+/// `slept_secs: 0`), restarting it if it's gone; for a frontend that noticed
+/// stale state on its own. Returns whether the backend was still answering.
+#[tauri::command(async)]
+pub fn reconnect_backend(app: tauri::AppHandle, state: tauri::State<BackendState>) -> bool {
+  check_after_resume(&app, state.inner(), Duration::ZERO)
+}
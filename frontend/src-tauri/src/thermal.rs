@@ -0,0 +1,165 @@
+//! Best-effort thermal monitoring for long events on fanless machines.
+//! Linux reads `/sys/class/thermal` zones; elsewhere sysinfo's component
+//! sensors are used (IOKit/SMC on macOS, where readable). A monitor thread
+//! emits `system://thermal-warning` once each time the hottest sensor crosses
+//! the configured threshold and, with `thermal_lower_priority` on, drops the
+//! backend to below-normal priority so the UI stays responsive.
+
+use serde::Serialize;
+use std::time::Duration;
+use sysinfo::Components;
+
+use crate::{config, diag, events, priority, BackendState};
+
+pub const MIN_WARNING_CELSIUS: f32 = 40.0;
+pub const MAX_WARNING_CELSIUS: f32 = 120.0;
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+/// Cooling this far below the threshold re-arms the warning.
+const HYSTERESIS_CELSIUS: f32 = 5.0;
+
+#[derive(Clone, Serialize)]
+pub struct Sensor {
+  name: String,
+  celsius: f32,
+  /// Temperature the sensor reports as critical, if any.
+  critical_celsius: Option<f32>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ThermalState {
+  /// False when no sensor could be read here; `message` says why.
+  supported: bool,
+  sensors: Vec<Sensor>,
+  max_celsius: Option<f32>,
+  threshold_celsius: f32,
+  /// The hottest sensor is at or over the threshold.
+  hot: bool,
+  message: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct ThermalWarning {
+  sensor: String,
+  celsius: f32,
+  threshold_celsius: f32,
+  /// Whether the backend's priority was lowered in response.
+  lowered_priority: bool,
+}
+
+#[cfg(target_os = "linux")]
+fn read_sensors() -> Vec<Sensor> {
+  let Ok(zones) = std::fs::read_dir("/sys/class/thermal") else {
+    return Vec::new();
+  };
+  zones
+    .filter_map(|zone| zone.ok().map(|zone| zone.path()))
+    .filter(|path| path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with("thermal_zone")))
+    .filter_map(|path| {
+      let millidegrees: f32 = std::fs::read_to_string(path.join("temp")).ok()?.trim().parse().ok()?;
+      let name = std::fs::read_to_string(path.join("type"))
+        .map(|kind| kind.trim().to_string())
+        .unwrap_or_else(|_| path.file_name().unwrap_or_default().to_string_lossy().into_owned());
+      // The first trip point of type "critical", if the zone declares one
+      let critical_celsius = (0..16).find_map(|trip| {
+        let kind = std::fs::read_to_string(path.join(format!("trip_point_{}_type", trip))).ok()?;
+        if kind.trim() != "critical" {
+          return None;
+        }
+        let temp: f32 = std::fs::read_to_string(path.join(format!("trip_point_{}_temp", trip))).ok()?.trim().parse().ok()?;
+        Some(temp / 1000.0)
+      });
+      Some(Sensor { name, celsius: millidegrees / 1000.0, critical_celsius })
+    })
+    .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_sensors() -> Vec<Sensor> {
+  Components::new_with_refreshed_list()
+    .iter()
+    .filter_map(|component| {
+      Some(Sensor {
+        name: component.label().to_string(),
+        celsius: component.temperature().filter(|t| t.is_finite())?,
+        critical_celsius: component.critical(),
+      })
+    })
+    .collect()
+}
+
+fn thermal_state(app: &tauri::AppHandle) -> ThermalState {
+  let threshold_celsius = config::current(app).thermal_warning_celsius;
+  let mut sensors = read_sensors();
+  // Some Linux machines expose only hwmon sensors, no thermal zones
+  if sensors.is_empty() && cfg!(target_os = "linux") {
+    sensors = Components::new_with_refreshed_list()
+      .iter()
+      .filter_map(|component| {
+        Some(Sensor {
+          name: component.label().to_string(),
+          celsius: component.temperature().filter(|t| t.is_finite())?,
+          critical_celsius: component.critical(),
+        })
+      })
+      .collect();
+  }
+  let max_celsius = sensors.iter().map(|sensor| sensor.celsius).reduce(f32::max);
+  let message = sensors
+    .is_empty()
+    .then(|| "Temperature sensors are unsupported or not readable on this platform".to_string());
+  ThermalState {
+    supported: !sensors.is_empty(),
+    hot: max_celsius.is_some_and(|max| max >= threshold_celsius),
+    sensors,
+    max_celsius,
+    threshold_celsius,
+    message,
+  }
+}
+
+pub fn spawn(app: tauri::AppHandle, backend: BackendState) {
+  std::thread::spawn(move || {
+    let mut warned = false;
+    loop {
+      std::thread::sleep(CHECK_INTERVAL);
+      let state = thermal_state(&app);
+      let Some(hottest) = state.sensors.iter().max_by(|a, b| a.celsius.total_cmp(&b.celsius)) else {
+        // Nothing to watch on this machine
+        return;
+      };
+      if !state.hot {
+        if hottest.celsius < state.threshold_celsius - HYSTERESIS_CELSIUS {
+          warned = false;
+        }
+        continue;
+      }
+      if warned {
+        continue;
+      }
+      warned = true;
+      let lowered_priority = config::current(&app).thermal_lower_priority
+        && priority::set_level(&backend, "below_normal").inspect_err(|err| diag(err)).is_ok();
+      diag(&format!(
+        "Thermal warning: {} at {:.1}°C (threshold {:.1}°C), lowered_priority={}",
+        hottest.name, hottest.celsius, state.threshold_celsius, lowered_priority
+      ));
+      events::emit(
+        &app,
+        "system://thermal-warning",
+        ThermalWarning {
+          sensor: hottest.name.clone(),
+          celsius: hottest.celsius,
+          threshold_celsius: state.threshold_celsius,
+          lowered_priority,
+        },
+      );
+    }
+  });
+}
+
+/// Current temperatures and whether they're over the warning threshold.
+/// Reports `supported: false` with a message where sensors can't be read.
+#[tauri::command(async)]
+pub fn get_thermal_state(app: tauri::AppHandle) -> ThermalState {
+  thermal_state(&app)
+}
@@ -0,0 +1,133 @@
+//! System tray icon with Show/Reload Window/Safe Mode/Quit, and stashing every window to the tray at once.
+
+use std::sync::Mutex;
+use tauri::menu::{Menu, MenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::Manager;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::{config, diag, main_window, relaunch_in_safe_mode, show_main_window, webview};
+
+/// Labels of the windows `minimize_all_to_tray` hid, so "Show" brings back
+/// exactly those and never re-opens windows the user had closed.
+#[derive(Default)]
+pub struct TrayState {
+  hidden: Mutex<Vec<String>>,
+}
+
+pub fn create(app: &tauri::AppHandle) -> tauri::Result<()> {
+  let show = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
+  let reload = MenuItem::with_id(app, "reload-window", "Reload Window", true, None::<&str>)?;
+  let safe_mode = MenuItem::with_id(app, "safe-mode", "Restart in Safe Mode", true, None::<&str>)?;
+  let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+  let menu = Menu::with_items(app, &[&show, &reload, &safe_mode, &quit])?;
+
+  let mut builder = TrayIconBuilder::with_id("main")
+    .tooltip("Talus Tally")
+    .menu(&menu)
+    .on_menu_event(|app, event| match event.id.as_ref() {
+      "show" => restore_windows(app),
+      "reload-window" => {
+        // Reachable even when the page itself is dead; recovery blocks, so off the event loop
+        let app = app.clone();
+        std::thread::spawn(move || {
+          if let Err(err) = webview::recover_webview(app) {
+            diag(&format!("Reload Window failed: {}", err));
+          }
+        });
+      }
+      "safe-mode" => relaunch_in_safe_mode(app),
+      // Exit goes through RunEvent::Exit, which stops the backend.
+      "quit" => app.exit(0),
+      _ => {}
+    });
+  if let Some(icon) = app.default_window_icon() {
+    builder = builder.icon(icon.clone());
+  }
+  builder.build(app)?;
+  Ok(())
+}
+
+/// Show the windows hidden by the last `minimize_all_to_tray` that still exist,
+/// or just the main window if nothing was stashed.
+fn restore_windows(app: &tauri::AppHandle) {
+  let hidden = app
+    .state::<TrayState>()
+    .hidden
+    .lock()
+    .map(|mut hidden| std::mem::take(&mut *hidden))
+    .unwrap_or_default();
+  let mut restored = 0;
+  for label in &hidden {
+    if let Some(window) = app.get_webview_window(label) {
+      let _ = window.show();
+      restored += 1;
+    }
+  }
+  if restored == 0 {
+    show_main_window(app);
+  } else if let Some(window) = main_window(app) {
+    let _ = window.set_focus();
+  }
+}
+
+/// Hide a closing window to the tray (keep-in-tray), so "Show" brings it back.
+pub fn hide_to_tray(app: &tauri::AppHandle, window: &tauri::Window) {
+  if let Err(err) = window.hide() {
+    diag(&format!("Failed to hide '{}' to tray: {}", window.label(), err));
+    return;
+  }
+  if let Ok(mut stashed) = app.state::<TrayState>().hidden.lock() {
+    if !stashed.iter().any(|label| label == window.label()) {
+      stashed.push(window.label().to_string());
+    }
+  }
+  diag(&format!("Closed '{}' to tray", window.label()));
+}
+
+#[tauri::command]
+pub fn get_keep_in_tray(app: tauri::AppHandle) -> bool {
+  config::current(&app).keep_in_tray
+}
+
+/// Whether closing the last visible window hides to the tray or quits.
+#[tauri::command]
+pub fn set_keep_in_tray(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+  if enabled && app.tray_by_id("main").is_none() {
+    return Err("No tray icon is available, so closing to the tray would leave no way back".to_string());
+  }
+  config::update(&app, |config| config.keep_in_tray = enabled)?;
+  Ok(())
+}
+
+/// Hide every visible window and point the user at the tray.
+#[tauri::command]
+pub fn minimize_all_to_tray(app: tauri::AppHandle, tray: tauri::State<TrayState>) -> Result<(), String> {
+  let mut hidden = Vec::new();
+  for (label, window) in app.webview_windows() {
+    if window.is_visible().unwrap_or(false) {
+      window.hide().map_err(|e| e.to_string())?;
+      hidden.push(label);
+    }
+  }
+  diag(&format!("Minimized {} window(s) to tray", hidden.len()));
+  if let Ok(mut stashed) = tray.hidden.lock() {
+    // Keep anything already stashed by an earlier call that hasn't been restored.
+    for label in hidden {
+      if !stashed.contains(&label) {
+        stashed.push(label);
+      }
+    }
+  }
+
+  if let Err(err) = app
+    .notification()
+    .builder()
+    .title("Talus Tally")
+    .body("Talus Tally is still running. Use the tray icon to show it again.")
+    .show()
+  {
+    diag(&format!("Failed to show tray notification: {}", err));
+  }
+  Ok(())
+}
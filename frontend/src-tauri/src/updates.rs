@@ -0,0 +1,266 @@
+//! How the frontend receives live tally updates: by polling the backend
+//! itself ("poll", the default), or from a websocket the app holds to the
+//! backend's Socket.IO `/graph` namespace ("ws"), whose events are forwarded
+//! as `tally://update`. If the websocket can't connect, or drops later, the
+//! app falls back to polling. `tally://transport` always reports the
+//! transport actually in effect.
+//!
+//! Forwarded events are coalesced: everything arriving within
+//! `update_batch_ms` of the first one goes out as a single `tally://update`
+//! carrying all of them in order, so a burst of changes doesn't flood the
+//! webview. A pending batch is always flushed, including when the socket
+//! stops or drops, so the final state is never lost.
+
+use serde::Serialize;
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tungstenite::{Message, WebSocket};
+
+use crate::{config, diag, events, BackendState};
+
+const NAMESPACE: &str = "/graph";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Read timeout on the socket, bounding how long a stop takes to be noticed.
+const READ_TICK: Duration = Duration::from_secs(1);
+pub const MAX_BATCH_MS: u64 = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+  Poll,
+  Ws,
+}
+
+/// Bumping `generation` tells a running websocket reader to stop.
+#[derive(Default)]
+pub struct UpdateState {
+  generation: AtomicU64,
+  effective: Mutex<Option<Transport>>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TransportChanged {
+  transport: Transport,
+  /// Why this isn't the requested transport, when it isn't.
+  fallback_reason: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct TallyUpdate {
+  event: String,
+  data: serde_json::Value,
+}
+
+/// Payload of `tally://update`: one or more updates, oldest first.
+#[derive(Clone, Serialize)]
+struct TallyBatch {
+  updates: Vec<TallyUpdate>,
+}
+
+/// Updates held back until the batch window closes.
+struct Batch {
+  updates: Vec<TallyUpdate>,
+  deadline: Instant,
+}
+
+impl Batch {
+  fn new() -> Self {
+    Batch { updates: Vec::new(), deadline: Instant::now() }
+  }
+
+  fn push(&mut self, app: &tauri::AppHandle, update: TallyUpdate) {
+    if self.updates.is_empty() {
+      self.deadline = Instant::now() + Duration::from_millis(config::current(app).update_batch_ms);
+    }
+    self.updates.push(update);
+  }
+
+  fn due(&self) -> bool {
+    !self.updates.is_empty() && Instant::now() >= self.deadline
+  }
+
+  /// How long the next read may block: until the window closes, if one is open.
+  fn read_timeout(&self) -> Duration {
+    if self.updates.is_empty() {
+      READ_TICK
+    } else {
+      // A zero read timeout is rejected, so wait at least a millisecond
+      self.deadline.saturating_duration_since(Instant::now()).clamp(Duration::from_millis(1), READ_TICK)
+    }
+  }
+
+  fn flush(&mut self, app: &tauri::AppHandle) {
+    if !self.updates.is_empty() {
+      events::emit(app, "tally://update", TallyBatch { updates: std::mem::take(&mut self.updates) });
+    }
+  }
+}
+
+type Socket = WebSocket<TcpStream>;
+
+fn set_effective(app: &tauri::AppHandle, transport: Transport, fallback_reason: Option<String>) -> TransportChanged {
+  if let Ok(mut effective) = app.state::<UpdateState>().effective.lock() {
+    *effective = Some(transport);
+  }
+  let changed = TransportChanged { transport, fallback_reason };
+  events::emit(app, "tally://transport", changed.clone());
+  changed
+}
+
+fn send(socket: &mut Socket, text: String) -> Result<(), String> {
+  socket.send(Message::text(text)).map_err(|e| format!("Websocket send failed: {}", e))
+}
+
+fn read_text(socket: &mut Socket) -> Result<Option<String>, tungstenite::Error> {
+  match socket.read()? {
+    Message::Text(text) => Ok(Some(text.to_string())),
+    Message::Close(_) => Err(tungstenite::Error::ConnectionClosed),
+    _ => Ok(None),
+  }
+}
+
+fn timed_out(err: &tungstenite::Error) -> bool {
+  matches!(err, tungstenite::Error::Io(io) if matches!(io.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut))
+}
+
+/// Open an Engine.IO v4 websocket, join the `/graph` namespace and, with a
+/// session id, its room.
+fn connect(backend: &BackendState, session_id: Option<&str>) -> Result<Socket, String> {
+  let addr = crate::network::resolve(&backend.host(), backend.port())?;
+  let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).map_err(|e| format!("Connect to {} failed: {}", addr, e))?;
+  stream.set_read_timeout(Some(CONNECT_TIMEOUT)).map_err(|e| e.to_string())?;
+  let url = format!("ws://{}/socket.io/?EIO=4&transport=websocket", addr);
+  let (mut socket, _) = tungstenite::client(url.as_str(), stream).map_err(|e| format!("Websocket handshake failed: {}", e))?;
+
+  // "0{...}" open, then "40/graph,{...}" once the namespace connect is accepted
+  let deadline = Instant::now() + CONNECT_TIMEOUT;
+  send(&mut socket, format!("40{},", NAMESPACE))?;
+  loop {
+    if Instant::now() >= deadline {
+      return Err("Backend did not accept the Socket.IO connection".to_string());
+    }
+    let packet = read_text(&mut socket).map_err(|e| format!("Websocket closed during connect: {}", e))?;
+    match packet.as_deref() {
+      Some(p) if p.starts_with(&format!("40{}", NAMESPACE)) => break,
+      Some(p) if p.starts_with(&format!("44{}", NAMESPACE)) => return Err(format!("Backend refused the {} namespace", NAMESPACE)),
+      Some("2") => send(&mut socket, "3".to_string())?,
+      _ => {}
+    }
+  }
+  if let Some(session_id) = session_id {
+    let join = serde_json::json!(["join_session", { "session_id": session_id }]);
+    send(&mut socket, format!("42{},{}", NAMESPACE, join))?;
+  }
+  socket.get_mut().set_read_timeout(Some(READ_TICK)).map_err(|e| e.to_string())?;
+  Ok(socket)
+}
+
+/// Forward namespace events until stopped or the socket fails; returns the
+/// failure, or `None` when stopped on purpose. Whatever is batched is flushed
+/// either way.
+fn forward(app: &tauri::AppHandle, socket: &mut Socket, generation: u64) -> Option<String> {
+  let mut batch = Batch::new();
+  let result = forward_batched(app, socket, generation, &mut batch);
+  batch.flush(app);
+  result
+}
+
+fn forward_batched(app: &tauri::AppHandle, socket: &mut Socket, generation: u64, batch: &mut Batch) -> Option<String> {
+  let state = app.state::<UpdateState>();
+  let prefix = format!("42{},", NAMESPACE);
+  while state.generation.load(Ordering::SeqCst) == generation {
+    if batch.due() {
+      batch.flush(app);
+    }
+    let _ = socket.get_mut().set_read_timeout(Some(batch.read_timeout()));
+    let packet = match read_text(socket) {
+      Ok(packet) => packet,
+      Err(err) if timed_out(&err) => continue,
+      Err(err) => return Some(format!("Websocket lost: {}", err)),
+    };
+    let Some(packet) = packet else { continue };
+    if packet == "2" {
+      if let Err(err) = send(socket, "3".to_string()) {
+        return Some(err);
+      }
+    } else if let Some(body) = packet.strip_prefix(&prefix) {
+      let Ok(serde_json::Value::Array(mut parts)) = serde_json::from_str(body) else {
+        continue;
+      };
+      if parts.is_empty() {
+        continue;
+      }
+      let data = if parts.len() > 1 { parts.remove(1) } else { serde_json::Value::Null };
+      let event = parts[0].as_str().unwrap_or_default().to_string();
+      batch.push(app, TallyUpdate { event, data });
+      if batch.due() {
+        batch.flush(app);
+      }
+    }
+  }
+  let _ = socket.close(None);
+  None
+}
+
+/// Switch how updates arrive: "poll" (the frontend polls on its own timer)
+/// or "ws" (this app forwards backend websocket events as `tally://update`,
+/// joining `session_id`'s room when given). Returns the transport in effect,
+/// which is "poll" with a `fallback_reason` if the websocket couldn't connect.
+#[tauri::command(async)]
+pub fn set_update_transport(
+  app: tauri::AppHandle,
+  state: tauri::State<UpdateState>,
+  backend: tauri::State<BackendState>,
+  transport: String,
+  session_id: Option<String>,
+) -> Result<TransportChanged, String> {
+  let generation = state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+  match transport.as_str() {
+    "poll" => {
+      diag("Update transport set to poll");
+      Ok(set_effective(&app, Transport::Poll, None))
+    }
+    "ws" => match connect(backend.inner(), session_id.as_deref()) {
+      Ok(mut socket) => {
+        diag("Update transport set to ws");
+        let changed = set_effective(&app, Transport::Ws, None);
+        let app = app.clone();
+        std::thread::spawn(move || {
+          if let Some(error) = forward(&app, &mut socket, generation) {
+            diag(&format!("{}; falling back to polling", error));
+            set_effective(&app, Transport::Poll, Some(error));
+          }
+        });
+        Ok(changed)
+      }
+      Err(error) => {
+        diag(&format!("Websocket updates unavailable ({}); using polling", error));
+        Ok(set_effective(&app, Transport::Poll, Some(error)))
+      }
+    },
+    other => Err(format!("Unknown update transport {:?}; expected \"poll\" or \"ws\"", other)),
+  }
+}
+
+#[tauri::command]
+pub fn get_update_batch_window(app: tauri::AppHandle) -> u64 {
+  config::current(&app).update_batch_ms
+}
+
+/// Persist how long (ms, clamped) forwarded updates are held to coalesce
+/// them; 0 emits each on arrival. Applies from the next batch.
+#[tauri::command]
+pub fn set_update_batch_window(app: tauri::AppHandle, ms: u64) -> Result<u64, String> {
+  let ms = ms.min(MAX_BATCH_MS);
+  config::update(&app, |config| config.update_batch_ms = ms)?;
+  Ok(ms)
+}
+
+/// The transport currently in effect ("poll" until set otherwise).
+#[tauri::command]
+pub fn get_update_transport(state: tauri::State<UpdateState>) -> Transport {
+  state.effective.lock().ok().and_then(|effective| *effective).unwrap_or(Transport::Poll)
+}
@@ -0,0 +1,185 @@
+//! Check and rebuild the development virtualenv so a broken `.venv` is repaired
+//! instead of silently falling back to the system interpreter, and validate a
+//! user-chosen interpreter (conda env, pyenv shim) before it is used.
+
+use serde::Serialize;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use crate::{config, determine_project_root, diag, events, venv_python_path};
+
+#[derive(Clone, Serialize)]
+struct VenvProgress {
+  stage: &'static str,
+  message: String,
+}
+
+#[derive(Serialize)]
+pub struct VenvRepairResult {
+  /// False when the existing venv was already healthy.
+  repaired: bool,
+  python: String,
+}
+
+fn progress(app: &tauri::AppHandle, stage: &'static str, message: impl Into<String>) {
+  let message = message.into();
+  diag(&format!("[venv:{}] {}", stage, message));
+  events::emit(app, "venv://progress", VenvProgress { stage, message });
+}
+
+/// True when `python` starts and can import the backend from `project_root`.
+fn venv_is_healthy(python: &Path, project_root: &Path) -> bool {
+  python.exists()
+    && Command::new(python)
+      .args(["-c", "import backend.app"])
+      .current_dir(project_root)
+      .stdout(Stdio::null())
+      .stderr(Stdio::null())
+      .status()
+      .map(|status| status.success())
+      .unwrap_or(false)
+}
+
+#[derive(Serialize)]
+pub struct PythonValidation {
+  valid: bool,
+  path: String,
+  /// Interpreter version, e.g. "3.11.4", once it was able to run.
+  version: Option<String>,
+  /// What failed: not found, won't run, or can't import the backend (with its traceback tail).
+  error: Option<String>,
+}
+
+impl PythonValidation {
+  pub fn error(&self) -> Option<&str> {
+    self.error.as_deref()
+  }
+}
+
+/// Run `python` in `project_root` and try importing the backend, reporting
+/// its version or why it can't be used.
+pub fn validate_python(python: &Path, project_root: &Path) -> PythonValidation {
+  let path = python.display().to_string();
+  let invalid = |version: Option<String>, error: String| PythonValidation { valid: false, path: path.clone(), version, error: Some(error) };
+  if python.components().count() > 1 && !python.exists() {
+    return invalid(None, format!("{} does not exist", path));
+  }
+  let output = match Command::new(python)
+    .args(["-c", "import sys; print(sys.version.split()[0], flush=True); import backend.app"])
+    .current_dir(project_root)
+    .stdin(Stdio::null())
+    .output()
+  {
+    Ok(output) => output,
+    Err(err) => return invalid(None, format!("Failed to run {}: {}", path, err)),
+  };
+  let version = String::from_utf8_lossy(&output.stdout).lines().next().map(|line| line.trim().to_string()).filter(|v| !v.is_empty());
+  if output.status.success() {
+    return PythonValidation { valid: true, path: path.clone(), version, error: None };
+  }
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  let detail = stderr.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or("no output").trim().to_string();
+  if version.is_none() {
+    invalid(None, format!("{} did not run as Python ({}): {}", path, output.status, detail))
+  } else {
+    invalid(version, format!("{} cannot import the backend from {}: {}", path, project_root.display(), detail))
+  }
+}
+
+/// Use `path` as the backend interpreter from the next start on, ahead of the
+/// virtualenv and system Python. It is only saved if it runs and can import
+/// the backend; an empty path clears the setting.
+#[tauri::command(async)]
+pub fn set_python_path(app: tauri::AppHandle, path: String) -> Result<PythonValidation, String> {
+  let path = path.trim();
+  if path.is_empty() {
+    config::update(&app, |config| config.python_path = None)?;
+    diag("Cleared configured Python interpreter");
+    return Ok(PythonValidation { valid: true, path: String::new(), version: None, error: None });
+  }
+  let python = PathBuf::from(path);
+  let validation = validate_python(&python, &determine_project_root(Some(&app)));
+  if validation.valid {
+    config::update(&app, |config| config.python_path = Some(python))?;
+    diag(&format!("Python interpreter set to {} ({:?}); applies on next start", path, validation.version));
+  } else {
+    diag(&format!("Rejected Python interpreter {}: {:?}", path, validation.error));
+  }
+  Ok(validation)
+}
+
+/// Run `command`, forwarding each output line as a progress event.
+fn run_streamed(app: &tauri::AppHandle, stage: &'static str, mut command: Command) -> Result<(), String> {
+  let mut child = command
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|e| format!("Failed to run {:?}: {}", command.get_program(), e))?;
+
+  let stderr = child.stderr.take();
+  let stderr_app = app.clone();
+  let stderr_thread = std::thread::spawn(move || {
+    if let Some(stderr) = stderr {
+      for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+        progress(&stderr_app, stage, line);
+      }
+    }
+  });
+  if let Some(stdout) = child.stdout.take() {
+    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+      progress(app, stage, line);
+    }
+  }
+  let _ = stderr_thread.join();
+
+  let status = child.wait().map_err(|e| e.to_string())?;
+  if status.success() {
+    Ok(())
+  } else {
+    Err(format!("{} step failed with status {}", stage, status))
+  }
+}
+
+#[tauri::command(async)]
+pub fn repair_venv(app: tauri::AppHandle) -> Result<VenvRepairResult, String> {
+  let project_root = determine_project_root(Some(&app));
+  let venv_dir = project_root.join(".venv");
+  let python = venv_python_path(&project_root);
+
+  progress(&app, "check", format!("Checking {}", python.display()));
+  if venv_is_healthy(&python, &project_root) {
+    progress(&app, "done", "Virtualenv is healthy; nothing to repair");
+    return Ok(VenvRepairResult {
+      repaired: false,
+      python: python.display().to_string(),
+    });
+  }
+
+  let requirements = project_root.join("requirements.txt");
+  if !requirements.exists() {
+    return Err(format!("Cannot repair venv: {} not found", requirements.display()));
+  }
+
+  let system_python = if cfg!(target_os = "windows") { "python" } else { "python3" };
+  progress(&app, "create", format!("Recreating {} with {}", venv_dir.display(), system_python));
+  let mut create = Command::new(system_python);
+  create.args(["-m", "venv", "--clear"]).arg(&venv_dir).current_dir(&project_root);
+  run_streamed(&app, "create", create)?;
+
+  progress(&app, "install", format!("Installing {}", requirements.display()));
+  let mut install = Command::new(&python);
+  install
+    .args(["-m", "pip", "install", "-r"])
+    .arg(&requirements)
+    .current_dir(&project_root);
+  run_streamed(&app, "install", install)?;
+
+  if !venv_is_healthy(&python, &project_root) {
+    return Err("Virtualenv was rebuilt but still cannot import the backend".to_string());
+  }
+  progress(&app, "done", "Virtualenv repaired");
+  Ok(VenvRepairResult {
+    repaired: true,
+    python: python.display().to_string(),
+  })
+}
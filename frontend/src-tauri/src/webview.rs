@@ -0,0 +1,247 @@
+//! Best-effort check that the webview is actually rendering. Some DRM and
+//! screen-capture tools leave it black: animation frames stop arriving or
+//! canvas readback comes back blank. A JS probe is injected and reports
+//! back through `report_webview_probe`.
+//!
+//! Separately, a crash watch pings the main window's page and treats
+//! repeated silence as a dead renderer (e.g. the WebView2 process crashing
+//! on its own), since Tauri exposes no portable render-process-gone hook.
+//! `recover_webview` brings the page back without touching the backend.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::{diag, events, main_thread, main_window, window};
+
+/// How long to wait for the probe to report before calling the webview stuck.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+const ALIVE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Consecutive unanswered pings before the page is reported crashed.
+const ALIVE_MISSES: u32 = 2;
+/// How long a reload gets to bring the page back before the window is rebuilt.
+const RELOAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Latest token the main window's page answered; pings count up from 1.
+static LAST_ALIVE: AtomicU64 = AtomicU64::new(0);
+static NEXT_ALIVE: AtomicU64 = AtomicU64::new(1);
+/// Set while the main window is destroyed and rebuilt, so closing it
+/// doesn't count as closing the last window and quit the app.
+static RECREATING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Default)]
+pub struct WebviewProbeState {
+  next_token: AtomicU64,
+  pending: Mutex<Option<(u64, Sender<WebviewHealth>)>>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct WebviewHealth {
+  ok: bool,
+  detail: String,
+}
+
+/// Two animation frames (so a frame was actually composited), then a red
+/// fill read back from a 2D canvas.
+fn probe_script(token: u64) -> String {
+  format!(
+    r#"(function () {{
+  var reported = false;
+  function done(ok, detail) {{
+    if (reported) return;
+    reported = true;
+    window.__TAURI_INTERNALS__.invoke('report_webview_probe', {{ token: {token}, ok: ok, detail: detail }});
+  }}
+  setTimeout(function () {{ done(false, 'no animation frame within 1s'); }}, 1000);
+  requestAnimationFrame(function () {{
+    requestAnimationFrame(function () {{
+      try {{
+        var canvas = document.createElement('canvas');
+        canvas.width = canvas.height = 4;
+        var ctx = canvas.getContext('2d');
+        ctx.fillStyle = 'rgb(255, 0, 0)';
+        ctx.fillRect(0, 0, 4, 4);
+        var px = ctx.getImageData(1, 1, 1, 1).data;
+        done(px[0] === 255 && px[1] === 0 && px[2] === 0, 'canvas pixel ' + px[0] + ',' + px[1] + ',' + px[2]);
+      }} catch (e) {{
+        done(false, 'canvas probe failed: ' + e);
+      }}
+    }});
+  }});
+}})();"#
+  )
+}
+
+/// Run the render probe in the calling window. Emits `webview://render-issue`
+/// when it fails so the UI can point at known conflicting apps.
+#[tauri::command(async)]
+pub fn check_webview_health(app: tauri::AppHandle, window: tauri::WebviewWindow, state: tauri::State<WebviewProbeState>) -> Result<WebviewHealth, String> {
+  if !window.is_visible().unwrap_or(false) {
+    return Err("Window is hidden; the render probe needs a visible window".to_string());
+  }
+  let token = state.next_token.fetch_add(1, Ordering::SeqCst);
+  let (sender, receiver) = mpsc::channel();
+  if let Ok(mut pending) = state.pending.lock() {
+    *pending = Some((token, sender));
+  }
+  window.eval(probe_script(token)).map_err(|e| format!("Failed to inject render probe: {}", e))?;
+
+  let health = receiver.recv_timeout(PROBE_TIMEOUT).unwrap_or_else(|_| WebviewHealth {
+    ok: false,
+    detail: format!("render probe did not report within {:?}", PROBE_TIMEOUT),
+  });
+  if !health.ok {
+    diag(&format!("Webview render issue in '{}': {}", window.label(), health.detail));
+    events::emit(&app, "webview://render-issue", health.clone());
+  }
+  Ok(health)
+}
+
+/// Called by the injected probe; not meant for direct frontend use.
+#[tauri::command]
+pub fn report_webview_probe(state: tauri::State<WebviewProbeState>, token: u64, ok: bool, detail: String) {
+  let pending = state.pending.lock().ok().and_then(|mut pending| {
+    if pending.as_ref().is_some_and(|(expected, _)| *expected == token) {
+      pending.take()
+    } else {
+      None
+    }
+  });
+  if let Some((_, sender)) = pending {
+    let _ = sender.send(WebviewHealth { ok, detail });
+  }
+}
+
+#[derive(Clone, Serialize)]
+struct WebviewCrashed {
+  label: String,
+  detail: String,
+}
+
+#[derive(Serialize)]
+pub struct WebviewRecovery {
+  /// "reload" or "recreate".
+  method: &'static str,
+}
+
+/// Called by the crash watch's ping; not meant for direct frontend use.
+#[tauri::command]
+pub fn report_webview_alive(token: u64) {
+  LAST_ALIVE.fetch_max(token, Ordering::SeqCst);
+}
+
+/// Ping the page and wait for it to answer. `None` means the ping couldn't be sent.
+fn ping(window: &tauri::WebviewWindow, timeout: Duration) -> Option<bool> {
+  let token = NEXT_ALIVE.fetch_add(1, Ordering::SeqCst);
+  let script = format!("window.__TAURI_INTERNALS__.invoke('report_webview_alive', {{ token: {} }});", token);
+  window.eval(script).ok()?;
+  let deadline = std::time::Instant::now() + timeout;
+  while std::time::Instant::now() < deadline {
+    if LAST_ALIVE.load(Ordering::SeqCst) >= token {
+      return Some(true);
+    }
+    std::thread::sleep(Duration::from_millis(100));
+  }
+  Some(false)
+}
+
+/// Watch the main window's page for a crashed renderer and emit
+/// `webview://crashed` once per crash. Hidden windows and a stalled main
+/// thread (which delays the ping itself) don't count as misses.
+pub fn spawn_crash_watch(app: tauri::AppHandle) {
+  std::thread::spawn(move || {
+    let mut misses = 0;
+    loop {
+      std::thread::sleep(ALIVE_INTERVAL);
+      let Some(window) = main_window(&app) else {
+        continue;
+      };
+      if RECREATING.load(Ordering::SeqCst) || !window.is_visible().unwrap_or(false) {
+        continue;
+      }
+      match ping(&window, ALIVE_TIMEOUT) {
+        Some(false) if !main_thread::is_stalled() => misses += 1,
+        _ => misses = 0,
+      }
+      if misses == ALIVE_MISSES {
+        let detail = format!("page did not answer {} pings {:?} apart", ALIVE_MISSES, ALIVE_INTERVAL);
+        diag(&format!("Webview '{}' appears to have crashed: {}", window.label(), detail));
+        events::emit(&app, "webview://crashed", WebviewCrashed { label: window.label().to_string(), detail });
+      }
+    }
+  });
+}
+
+/// Whether the app is in the middle of rebuilding the main window; the
+/// exit handler checks this (once) before treating the close as a quit.
+pub fn take_recreating() -> bool {
+  RECREATING.swap(false, Ordering::SeqCst)
+}
+
+/// Keep pinging until the page answers; a ping sent while a reload is still
+/// navigating is lost, so one miss proves nothing.
+fn answers_within(window: &tauri::WebviewWindow, timeout: Duration) -> bool {
+  let deadline = std::time::Instant::now() + timeout;
+  while std::time::Instant::now() < deadline {
+    if ping(window, Duration::from_secs(1)) == Some(true) {
+      return true;
+    }
+  }
+  false
+}
+
+/// Destroy and rebuild the main window from tauri.conf.json, keeping its
+/// size, position and zoom. The new window gets the app's usual window event
+/// handling; the backend and its sessions are untouched.
+fn recreate(app: &tauri::AppHandle, old: tauri::WebviewWindow) -> Result<(), String> {
+  let window_config = app
+    .config()
+    .app
+    .windows
+    .iter()
+    .find(|config| config.label == old.label())
+    .cloned()
+    .ok_or_else(|| format!("No window config for '{}'", old.label()))?;
+  let size = old.outer_size().ok();
+  let position = old.outer_position().ok();
+  let zoom = app.state::<window::ZoomState>().0.lock().map(|zoom| *zoom).unwrap_or(1.0);
+
+  RECREATING.store(app.webview_windows().len() == 1, Ordering::SeqCst);
+  old.destroy().map_err(|e| format!("Failed to close the crashed window: {}", e))?;
+  let rebuilt = tauri::WebviewWindowBuilder::from_config(app, &window_config)
+    .and_then(|builder| builder.build())
+    .map_err(|e| {
+      // With no window left, let the app quit normally rather than linger invisibly
+      RECREATING.store(false, Ordering::SeqCst);
+      format!("Failed to rebuild the main window: {}", e)
+    })?;
+  if let Some(size) = size {
+    let _ = rebuilt.set_size(size);
+  }
+  if let Some(position) = position {
+    let _ = rebuilt.set_position(position);
+  }
+  let _ = rebuilt.set_zoom(zoom);
+  window::apply_saved_settings(app);
+  let _ = rebuilt.set_focus();
+  Ok(())
+}
+
+/// Bring a blank or crashed main window back: reload the page, and if it
+/// still doesn't answer, rebuild the window. The backend keeps running, so
+/// sessions survive; the frontend reconnects as on a normal launch.
+#[tauri::command(async)]
+pub fn recover_webview(app: tauri::AppHandle) -> Result<WebviewRecovery, String> {
+  let old = main_window(&app).ok_or_else(|| "Main window not found".to_string())?;
+  if old.reload().is_ok() && answers_within(&old, RELOAD_TIMEOUT) {
+    diag("Recovered webview by reloading the page");
+    return Ok(WebviewRecovery { method: "reload" });
+  }
+  diag("Webview reload didn't bring the page back; rebuilding the main window");
+  recreate(&app, old)?;
+  Ok(WebviewRecovery { method: "recreate" })
+}
@@ -0,0 +1,270 @@
+//! Window presentation settings (kiosk/presentation lock-down, accent color)
+//! and named layout presets, persisted in config.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{LogicalPosition, LogicalSize, Manager};
+
+use crate::{config, diag, events, main_window};
+
+/// Webviews expose no zoom getter, so the last value set through `set_zoom` is tracked here.
+pub struct ZoomState(pub Mutex<f64>);
+
+impl Default for ZoomState {
+  fn default() -> Self {
+    ZoomState(Mutex::new(1.0))
+  }
+}
+
+/// Size/position are logical pixels; `None` position means centered.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LayoutPreset {
+  pub name: String,
+  pub width: f64,
+  pub height: f64,
+  pub x: Option<f64>,
+  pub y: Option<f64>,
+  pub maximized: bool,
+  pub fullscreen: bool,
+  pub always_on_top: bool,
+  pub zoom: f64,
+}
+
+fn builtin_presets() -> Vec<LayoutPreset> {
+  vec![
+    LayoutPreset {
+      name: "control".to_string(),
+      width: 1200.0,
+      height: 800.0,
+      x: None,
+      y: None,
+      maximized: false,
+      fullscreen: false,
+      always_on_top: false,
+      zoom: 1.0,
+    },
+    LayoutPreset {
+      name: "presentation".to_string(),
+      width: 1200.0,
+      height: 800.0,
+      x: None,
+      y: None,
+      maximized: false,
+      fullscreen: true,
+      always_on_top: true,
+      zoom: 1.25,
+    },
+  ]
+}
+
+fn is_builtin(name: &str) -> bool {
+  builtin_presets().iter().any(|preset| preset.name == name)
+}
+
+/// Re-apply persisted window settings to the main window at startup.
+pub fn apply_saved_settings(app: &tauri::AppHandle) {
+  let config = config::current(app);
+  if let Some(window) = main_window(app) {
+    if let Err(err) = window.set_resizable(config.resizable) {
+      diag(&format!("Failed to apply saved resizable={}: {}", config.resizable, err));
+    }
+  }
+}
+
+#[tauri::command]
+pub fn set_resizable(app: tauri::AppHandle, window: tauri::Window, resizable: bool) -> Result<(), String> {
+  window.set_resizable(resizable).map_err(|e| e.to_string())?;
+  config::update(&app, |config| config.resizable = resizable)?;
+  Ok(())
+}
+
+#[derive(Clone, Serialize)]
+struct AccentChanged {
+  color: String,
+}
+
+/// Accept "#rgb" or "#rrggbb" (the '#' optional) and return "#rrggbb" lowercase.
+pub fn normalize_hex(hex: &str) -> Option<String> {
+  let digits = hex.trim().trim_start_matches('#');
+  if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+    return None;
+  }
+  let digits = match digits.len() {
+    3 => digits.chars().flat_map(|c| [c, c]).collect(),
+    6 => digits.to_string(),
+    _ => return None,
+  };
+  Some(format!("#{}", digits.to_lowercase()))
+}
+
+/// Persist the branding accent color and tell the frontend to restyle.
+#[tauri::command]
+pub fn set_accent_color(app: tauri::AppHandle, hex: String) -> Result<String, String> {
+  let color = normalize_hex(&hex).ok_or_else(|| format!("{:?} is not a hex color like #1e90ff", hex))?;
+  config::update(&app, |config| config.accent_color = Some(color.clone()))?;
+  events::emit(&app, "theme://accent-changed", AccentChanged { color: color.clone() });
+  Ok(color)
+}
+
+#[tauri::command]
+pub fn get_accent_color(app: tauri::AppHandle) -> Option<String> {
+  config::current(&app).accent_color
+}
+
+#[tauri::command]
+pub fn set_zoom(window: tauri::WebviewWindow, zoom: tauri::State<ZoomState>, factor: f64) -> Result<(), String> {
+  if !(0.25..=5.0).contains(&factor) {
+    return Err(format!("Zoom factor {} is out of range (0.25-5.0)", factor));
+  }
+  window.set_zoom(factor).map_err(|e| e.to_string())?;
+  if let Ok(mut current) = zoom.0.lock() {
+    *current = factor;
+  }
+  Ok(())
+}
+
+/// Built-in presets first, then user presets.
+#[tauri::command]
+pub fn list_layout_presets(app: tauri::AppHandle) -> Vec<LayoutPreset> {
+  let mut presets = builtin_presets();
+  presets.extend(config::current(&app).layout_presets);
+  presets
+}
+
+/// Capture the window's current layout under `name`, replacing a user preset of the same name.
+#[tauri::command]
+pub fn save_layout_preset(app: tauri::AppHandle, window: tauri::WebviewWindow, zoom: tauri::State<ZoomState>, name: String) -> Result<LayoutPreset, String> {
+  if name.trim().is_empty() {
+    return Err("Preset name must not be empty".to_string());
+  }
+  if is_builtin(&name) {
+    return Err(format!("'{}' is a built-in preset and can't be overwritten", name));
+  }
+  let scale = window.scale_factor().map_err(|e| e.to_string())?;
+  let size: LogicalSize<f64> = window.inner_size().map_err(|e| e.to_string())?.to_logical(scale);
+  let position: LogicalPosition<f64> = window.outer_position().map_err(|e| e.to_string())?.to_logical(scale);
+  let preset = LayoutPreset {
+    name: name.clone(),
+    width: size.width,
+    height: size.height,
+    x: Some(position.x),
+    y: Some(position.y),
+    maximized: window.is_maximized().unwrap_or(false),
+    fullscreen: window.is_fullscreen().unwrap_or(false),
+    always_on_top: window.is_always_on_top().unwrap_or(false),
+    zoom: zoom.0.lock().map(|z| *z).unwrap_or(1.0),
+  };
+  let saved = preset.clone();
+  config::update(&app, move |config| {
+    config.layout_presets.retain(|existing| existing.name != name);
+    config.layout_presets.push(preset);
+  })?;
+  Ok(saved)
+}
+
+#[tauri::command]
+pub fn apply_layout_preset(app: tauri::AppHandle, window: tauri::WebviewWindow, zoom: tauri::State<ZoomState>, name: String) -> Result<(), String> {
+  let preset = list_layout_presets(app)
+    .into_iter()
+    .find(|preset| preset.name == name)
+    .ok_or_else(|| format!("Unknown layout preset '{}'", name))?;
+
+  // Leave fullscreen/maximized first so the size and position stick
+  let _ = window.set_fullscreen(false);
+  let _ = window.unmaximize();
+  window
+    .set_size(LogicalSize::new(preset.width, preset.height))
+    .map_err(|e| e.to_string())?;
+  match (preset.x, preset.y) {
+    (Some(x), Some(y)) => window.set_position(LogicalPosition::new(x, y)).map_err(|e| e.to_string())?,
+    _ => window.center().map_err(|e| e.to_string())?,
+  }
+  if preset.maximized {
+    window.maximize().map_err(|e| e.to_string())?;
+  }
+  if preset.fullscreen {
+    window.set_fullscreen(true).map_err(|e| e.to_string())?;
+  }
+  window.set_always_on_top(preset.always_on_top).map_err(|e| e.to_string())?;
+  set_zoom(window, zoom, preset.zoom)?;
+  diag(&format!("Applied layout preset '{}'", name));
+  Ok(())
+}
+
+/// Command-line flag that resets the main window before it is shown, for when
+/// it opens entirely off-screen and no UI is reachable.
+pub const RESET_WINDOW_FLAG: &str = "--reset-window";
+
+/// Window size from tauri.conf.json.
+const DEFAULT_SIZE: LogicalSize<f64> = LogicalSize { width: 1200.0, height: 800.0 };
+
+/// Put the main window back to its default size, centered on the current
+/// monitor, not fullscreen/maximized/on-top, at 100% zoom.
+pub fn reset_main_window(app: &tauri::AppHandle) -> Result<(), String> {
+  let window = main_window(app).ok_or_else(|| "Main window not found".to_string())?;
+  let _ = window.set_fullscreen(false);
+  let _ = window.unmaximize();
+  let _ = window.unminimize();
+  window.set_size(DEFAULT_SIZE).map_err(|e| e.to_string())?;
+  window.center().map_err(|e| e.to_string())?;
+  window.set_always_on_top(false).map_err(|e| e.to_string())?;
+  window.set_zoom(1.0).map_err(|e| e.to_string())?;
+  if let Ok(mut zoom) = app.state::<ZoomState>().0.lock() {
+    *zoom = 1.0;
+  }
+  let _ = window.show();
+  let _ = window.set_focus();
+  diag("Reset main window to default size and position");
+  Ok(())
+}
+
+#[tauri::command]
+pub fn reset_window_state(app: tauri::AppHandle) -> Result<(), String> {
+  reset_main_window(&app)
+}
+
+#[tauri::command]
+pub fn delete_layout_preset(app: tauri::AppHandle, name: String) -> Result<(), String> {
+  if is_builtin(&name) {
+    return Err(format!("'{}' is a built-in preset and can't be deleted", name));
+  }
+  config::update(&app, |config| config.layout_presets.retain(|preset| preset.name != name))?;
+  Ok(())
+}
+
+#[derive(Serialize)]
+pub struct WindowCapabilities {
+  fullscreen: bool,
+  always_on_top: bool,
+  tray: bool,
+  transparent: bool,
+  /// Whether the app can place the window on a given monitor itself.
+  per_monitor_move: bool,
+}
+
+/// A Wayland session where GTK uses the Wayland backend (not XWayland).
+fn is_wayland() -> bool {
+  cfg!(target_os = "linux")
+    && (std::env::var("XDG_SESSION_TYPE").is_ok_and(|session| session.eq_ignore_ascii_case("wayland"))
+      || std::env::var_os("WAYLAND_DISPLAY").is_some())
+    && !std::env::var("GDK_BACKEND").is_ok_and(|backend| backend.starts_with("x11"))
+}
+
+/// Window operations that reliably work on this platform and session, so
+/// the titlebar can hide controls that would silently do nothing. Wayland
+/// compositors own stacking and placement; GNOME shows no tray icons
+/// without an extension; macOS transparency needs the private-API build
+/// flag, which we don't enable.
+#[tauri::command]
+pub fn get_window_capabilities(app: tauri::AppHandle) -> WindowCapabilities {
+  let wayland = is_wayland();
+  let gnome = cfg!(target_os = "linux")
+    && std::env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktop| desktop.to_uppercase().contains("GNOME"));
+  WindowCapabilities {
+    fullscreen: true,
+    always_on_top: !wayland,
+    tray: app.tray_by_id("main").is_some() && !gnome,
+    transparent: !cfg!(target_os = "macos"),
+    per_monitor_move: !wayland,
+  }
+}
@@ -0,0 +1,99 @@
+//! Inspect and kill worker subprocesses of the backend without restarting it.
+//! Only processes descended from the backend we spawned are ever touched.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+use crate::{diag, BackendState};
+
+#[derive(Serialize)]
+pub struct WorkerInfo {
+  pid: u32,
+  parent_pid: u32,
+  name: String,
+  command: String,
+  /// Resident memory in bytes.
+  memory: u64,
+  /// Unix seconds when the worker started.
+  start_time: u64,
+}
+
+fn backend_pid(backend: &BackendState) -> Result<u32, String> {
+  backend
+    .process
+    .lock()
+    .ok()
+    .and_then(|proc| proc.as_ref().map(|child| child.id()))
+    .ok_or_else(|| "No spawned backend process".to_string())
+}
+
+fn process_table() -> System {
+  let mut system = System::new();
+  system.refresh_processes_specifics(
+    ProcessesToUpdate::All,
+    true,
+    ProcessRefreshKind::nothing().with_memory().with_cmd(UpdateKind::OnlyIfNotSet),
+  );
+  system
+}
+
+/// All processes below `root` in the process tree, excluding `root` itself.
+fn descendants(system: &System, root: Pid) -> HashSet<Pid> {
+  let mut found = HashSet::new();
+  let mut frontier = vec![root];
+  while let Some(parent) = frontier.pop() {
+    for (pid, process) in system.processes() {
+      if process.parent() == Some(parent) && found.insert(*pid) {
+        frontier.push(*pid);
+      }
+    }
+  }
+  found
+}
+
+#[tauri::command(async)]
+pub fn list_backend_workers(state: tauri::State<BackendState>) -> Result<Vec<WorkerInfo>, String> {
+  let root = Pid::from_u32(backend_pid(state.inner())?);
+  let system = process_table();
+  let mut workers: Vec<WorkerInfo> = descendants(&system, root)
+    .into_iter()
+    .filter_map(|pid| system.process(pid))
+    .map(|process| WorkerInfo {
+      pid: process.pid().as_u32(),
+      parent_pid: process.parent().map(|pid| pid.as_u32()).unwrap_or_default(),
+      name: process.name().to_string_lossy().into_owned(),
+      command: process
+        .cmd()
+        .iter()
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" "),
+      memory: process.memory(),
+      start_time: process.start_time(),
+    })
+    .collect();
+  workers.sort_by_key(|worker| worker.pid);
+  Ok(workers)
+}
+
+/// Kill one backend worker. Refuses the backend itself and anything outside
+/// its process subtree.
+#[tauri::command(async)]
+pub fn kill_backend_worker(state: tauri::State<BackendState>, pid: u32) -> Result<(), String> {
+  let root = backend_pid(state.inner())?;
+  if pid == root {
+    return Err("Refusing to kill the backend itself; use restart_backend instead".to_string());
+  }
+  let system = process_table();
+  let target = Pid::from_u32(pid);
+  if !descendants(&system, Pid::from_u32(root)).contains(&target) {
+    return Err(format!("Process {} is not a worker of the backend (PID {})", pid, root));
+  }
+  let process = system.process(target).ok_or_else(|| format!("Process {} has already exited", pid))?;
+  if !process.kill() {
+    return Err(format!("Failed to kill worker {}", pid));
+  }
+  diag(&format!("Killed backend worker {} ({})", pid, process.name().to_string_lossy()));
+  Ok(())
+}
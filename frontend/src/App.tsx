@@ -1979,7 +1979,7 @@ function App() {
         console.log('[CLOSE HANDLER SETUP] Got window reference');
         
         // Listen for the custom close event emitted by Rust
-        unlisten = await listen('talus://close-requested', async () => {
+        unlisten = await listen('app://close-requested', async () => {
           // Guard against re-entry (e.g. user clicks close button again while save dialog is open)
           if (closeInProgressRef.current) {
             console.log('[CLOSE REQUESTED] Already handling close, ignoring duplicate event');
@@ -2036,7 +2036,7 @@ function App() {
             }
           }
         });
-        console.log('[CLOSE HANDLER SETUP] ✓ Event listener registered for talus://close-requested');
+        console.log('[CLOSE HANDLER SETUP] ✓ Event listener registered for app://close-requested');
       } catch (err) {
         console.error('[CLOSE HANDLER SETUP] ❌ Failed to setup close handler:', err);
       }
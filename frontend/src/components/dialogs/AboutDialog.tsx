@@ -1,15 +1,41 @@
+import { useEffect, useState } from 'react';
 import { X, ExternalLink } from 'lucide-react';
 import { openExternalUrl } from '../../utils/openExternal';
 
 const GITHUB_URL = 'https://github.com/PipeManMusic/Talus_Tally';
 const DOCS_URL = `${GITHUB_URL}#readme`;
 
+interface BuildInfo {
+  version: string;
+  commit: string;
+  build_time: number;
+  target: string;
+  profile: string;
+}
+
 interface AboutDialogProps {
   isOpen: boolean;
   onClose: () => void;
 }
 
 export function AboutDialog({ isOpen, onClose }: AboutDialogProps) {
+  const [buildInfo, setBuildInfo] = useState<BuildInfo | null>(null);
+
+  useEffect(() => {
+    if (!isOpen || buildInfo) return;
+    // Only the desktop app has build metadata; the browser build just shows the version
+    (async () => {
+      try {
+        const { isTauri, invoke } = await import('@tauri-apps/api/core');
+        if (isTauri()) {
+          setBuildInfo(await invoke<BuildInfo>('get_build_info'));
+        }
+      } catch (e) {
+        console.error('Failed to load build info:', e);
+      }
+    })();
+  }, [isOpen, buildInfo]);
+
   if (!isOpen) return null;
 
   // Injected by Vite at build time from tauri.conf.json (see vite.config.ts)
@@ -38,6 +64,13 @@ export function AboutDialog({ isOpen, onClose }: AboutDialogProps) {
             <div className="text-sm text-[var(--color-fg-secondary)] mt-1 font-mono">
               v{version}
             </div>
+            {buildInfo && (
+              <div className="text-xs text-[var(--color-fg-secondary)] mt-1 font-mono select-text">
+                {buildInfo.commit} · {buildInfo.target} · {buildInfo.profile}
+                <br />
+                built {new Date(buildInfo.build_time * 1000).toISOString().replace('T', ' ').slice(0, 16)} UTC
+              </div>
+            )}
           </div>
 
           <p className="text-sm text-[var(--color-fg-secondary)] leading-relaxed">
@@ -3,6 +3,7 @@
 
 from __future__ import annotations
 
+import hashlib
 import shutil
 import subprocess
 import sys
@@ -16,6 +17,7 @@ SPEC_FILE = PROJECT_ROOT / "talus-tally.spec"
 ASSETS_DIR = PROJECT_ROOT / "assets"
 DATA_DIR = PROJECT_ROOT / "data"
 REQUIREMENTS = PROJECT_ROOT / "requirements.txt"
+CHECKSUM_MANIFEST = "checksums.sha256"
 
 PYTHON = sys.executable
 
@@ -61,6 +63,19 @@ def copy_tree(source: Path, destination: Path) -> None:
     shutil.copytree(source, destination)
 
 
+def write_checksum_manifest(bundle_dir: Path) -> None:
+    """Write sha256sum-style checksums of the backend bundle; the app checks
+    them in verify_bundle_integrity."""
+    lines = []
+    for path in sorted(p for p in bundle_dir.rglob("*") if p.is_file()):
+        if path.name == CHECKSUM_MANIFEST:
+            continue
+        digest = hashlib.sha256(path.read_bytes()).hexdigest()
+        lines.append(f"{digest}  {path.relative_to(bundle_dir).as_posix()}")
+    (bundle_dir / CHECKSUM_MANIFEST).write_text("\n".join(lines) + "\n")
+    print(f"[prepare-tauri] Wrote checksums for {len(lines)} backend files")
+
+
 def stage_resources() -> None:
     RESOURCES_ROOT.mkdir(parents=True, exist_ok=True)
 
@@ -69,6 +84,7 @@ def stage_resources() -> None:
     data_dest = RESOURCES_ROOT / "data"
 
     copy_tree(BACKEND_DIST, backend_dest)
+    write_checksum_manifest(backend_dest)
     copy_tree(ASSETS_DIR, assets_dest)
     copy_tree(DATA_DIR, data_dest)
 
@@ -0,0 +1,31 @@
+"""Tests for the backend self-test the desktop shell's diagnostics call."""
+
+import tempfile
+
+import pytest
+
+from backend.app import create_app
+
+
+@pytest.fixture
+def client(tmp_path, monkeypatch):
+    monkeypatch.setenv('TALUS_DATA_DIR', str(tmp_path))
+    monkeypatch.delenv('TALUS_INMEMORY', raising=False)
+    with tempfile.TemporaryDirectory() as tmpdir:
+        app = create_app({'TESTING': True, 'PROJECT_DIR': tmpdir})
+        yield app.test_client()
+
+
+def test_self_test_reports_each_check(client):
+    response = client.get('/api/v1/self-test')
+
+    assert response.status_code == 200
+    checks = {check['name']: check for check in response.json['checks']}
+    assert set(checks) == {'data_dir', 'tally_round_trip', 'version'}
+    assert all(check['status'] == 'pass' for check in checks.values())
+
+
+def test_self_test_leaves_nothing_behind(client, tmp_path):
+    client.get('/api/v1/self-test')
+
+    assert not any(path.name.startswith('.self-test-') for path in tmp_path.iterdir())
@@ -0,0 +1,42 @@
+import json
+
+import pytest
+
+from backend.infra import project_schema
+
+
+def write(path, data):
+    path.write_text(json.dumps(data))
+    return path
+
+
+def test_missing_file_is_unknown_not_current(tmp_path):
+    version = project_schema.file_version(tmp_path / "missing.json")
+    assert version is None
+    assert not project_schema.migration_available(version)
+
+
+def test_unversioned_file_is_version_zero(tmp_path):
+    path = write(tmp_path / "old.json", {"version": "1.0", "nodes": {}})
+    assert project_schema.file_version(path) == 0
+    assert project_schema.migration_available(0)
+
+
+def test_migrate_moves_graph_node_list_into_nodes_map(tmp_path):
+    path = write(tmp_path / "old.json", {
+        "version": "1.0",
+        "graph": {"nodes": [{"id": "n1", "name": "Root"}]},
+    })
+
+    assert project_schema.migrate(path) == project_schema.SCHEMA_VERSION
+
+    data = json.loads(path.read_text())
+    assert data["schema_version"] == project_schema.SCHEMA_VERSION
+    assert data["nodes"]["n1"]["name"] == "Root"
+    assert "graph" not in data
+
+
+def test_migrate_rejects_newer_schema(tmp_path):
+    path = write(tmp_path / "new.json", {"schema_version": project_schema.SCHEMA_VERSION + 1, "nodes": {}})
+    with pytest.raises(ValueError, match="newer"):
+        project_schema.migrate(path)